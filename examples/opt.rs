@@ -99,8 +99,8 @@ impl SimModelTrait<LEN_Y, LEN_P, LEN_B> for Model {
     next_t: &[Decimal; LEN_B],
     y: &[f64; LEN_Y],
   ) {
-    act[0] = if *dec_t == next_t[0] { true } else { false };
-    act[1] = if *dec_t == next_t[1] { true } else { false };
+    act[0] = *dec_t == next_t[0];
+    act[1] = *dec_t == next_t[1];
   }
 
   #[allow(unused_variables)]
@@ -146,7 +146,7 @@ impl OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X> for Model {
 }
 
 #[allow(dead_code)]
-fn obs() -> Vec<(usize, f64, f64, Option<f64>, Option<f64>)> {
+fn obs() -> Vec<Obs> {
   vec![
     // A <=> B
     // ddt_A = -k12*A + k21*B
@@ -85,8 +85,8 @@ impl SimModelTrait<LEN_Y, LEN_P, LEN_B> for Model {
     next_t: &[Decimal; LEN_B],
     y: &[f64; LEN_Y],
   ) {
-    act[0] = if *dec_t == next_t[0] { true } else { false };
-    act[1] = if *dec_t == next_t[1] { true } else { false };
+    act[0] = *dec_t == next_t[0];
+    act[1] = *dec_t == next_t[1];
   }
 
   #[allow(unused_variables)]
@@ -0,0 +1,5 @@
+mod base;
+mod result;
+
+pub use crate::bootstrap::base::{Bootstrap, Resampling};
+pub use crate::bootstrap::result::BootstrapResult;
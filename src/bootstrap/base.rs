@@ -0,0 +1,163 @@
+use super::result::BootstrapResult;
+
+use crate::data::{Censor, Data};
+use crate::model::OptModelTrait;
+use crate::objective::Objective;
+use crate::optimizer::{OptResult, Optimizer};
+use crate::stats;
+
+use ndarray::Array1;
+use rand::rngs::StdRng;
+use rand::Rng;
+use std::thread;
+
+// how a bootstrap replicate of data is made.
+#[derive(Clone, Copy)]
+pub enum Resampling {
+  // y* = fit + sd * e, where e is drawn from the standardized residuals
+  // (r / sd) of quantified observations at the best fit, inflated by
+  // sqrt(n / (n - len_x)). censored observations are kept as they are.
+  Residual,
+
+  // observations (with their censoring) are drawn with replacement.
+  Case,
+}
+
+// Bootstrap of the parameters in x_index.
+// each replicate is fitted by the optimizer starting from the best-fit x,
+// and percentile confidence intervals are computed at the confidence level.
+// replicates are distributed over threads and are reproducible if seed is given.
+pub struct Bootstrap {
+  pub resampling: Resampling,
+  pub n_boot: usize,
+  pub level: f64,
+  pub seed: Option<u64>,
+}
+
+impl Bootstrap {
+  pub fn new(resampling: Resampling, n_boot: usize, level: f64, seed: Option<u64>) -> Self {
+    Self {
+      resampling,
+      n_boot,
+      level,
+      seed,
+    }
+  }
+
+  pub fn run<M, const LEN_Y: usize, const LEN_P: usize, const LEN_B: usize, const LEN_X: usize>(
+    &self,
+    objective: &mut Objective<M, LEN_Y, LEN_P, LEN_B, LEN_X>,
+    optimizer: &Optimizer,
+    optres: &OptResult,
+  ) -> BootstrapResult
+  where
+    M: OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X>,
+  {
+    // fitted values and residuals at the best fit
+    let arr_res_y = objective.residuals(&optres.x);
+    let arr_obs_sd = objective.data.make_arr_obs_sd();
+    let arr_fit_y = &objective.data.make_arr_obs_y() - &arr_res_y;
+
+    // standardized residuals of quantified observations
+    let n_obs = objective.data.obs.len() as f64;
+    let inflation = (n_obs / (n_obs - objective.len_x as f64).max(1.0)).sqrt();
+    let pool: Vec<f64> = (0..objective.data.obs.len())
+      .filter(|&i| objective.data.censor()[i] == Censor::Quantified)
+      .map(|i| inflation * arr_res_y[i] / arr_obs_sd[i])
+      .collect();
+
+    let n_threads = thread::available_parallelism().map_or(1, |n| n.get());
+
+    // vector for join-handles
+    let mut handles = Vec::new();
+
+    for n_thread in 0..n_threads {
+      let thread_objective = objective.clone();
+      let thread_optimizer = optimizer.clone();
+      let (thread_fit_y, thread_obs_sd, thread_pool) =
+        (arr_fit_y.clone(), arr_obs_sd.clone(), pool.clone());
+      let (resampling, n_boot, seed) = (self.resampling, self.n_boot, self.seed);
+
+      // ===== FORK =====
+      let handle = thread::spawn(move || {
+        let mut replicates = Vec::new();
+        for n_rep in (n_thread..n_boot).step_by(n_threads) {
+          let mut rng = stats::make_rng(seed, n_rep as u64);
+          let data = match resampling {
+            Resampling::Residual => resample_residuals(
+              &thread_objective.data,
+              (&thread_fit_y, &thread_obs_sd, &thread_pool),
+              &mut rng,
+            ),
+            Resampling::Case => resample_cases(&thread_objective.data, &mut rng),
+          };
+
+          // fit the replicate from the best-fit x
+          let mut rep_objective = thread_objective.with_data(data);
+          let rep_optres = thread_optimizer.run(&mut rep_objective);
+          let x = if rep_optres.f.is_finite() {
+            Some(rep_optres.x)
+          } else {
+            None
+          };
+          replicates.push((n_rep, x));
+        }
+        replicates
+      });
+      // ================
+
+      handles.push(handle);
+    }
+
+    // ===== JOIN =====
+    let mut replicates = Vec::new();
+    for handle in handles {
+      replicates.extend(handle.join().unwrap());
+    }
+    // ================
+
+    // replicates in order, failed fits are dropped
+    replicates.sort_by_key(|(n_rep, _)| *n_rep);
+    let n_failed = replicates.iter().filter(|(_, x)| x.is_none()).count();
+    let samples = replicates.into_iter().filter_map(|(_, x)| x).collect();
+
+    BootstrapResult::new(
+      objective.x_index.clone(),
+      optres.x.clone(),
+      samples,
+      n_failed,
+      self.level,
+    )
+  }
+}
+
+fn resample_residuals(
+  data: &Data,
+  (arr_fit_y, arr_obs_sd, pool): (&Array1<f64>, &Array1<f64>, &[f64]),
+  rng: &mut StdRng,
+) -> Data {
+  let mut obs = data.obs.clone();
+  for (i, o) in obs.iter_mut().enumerate() {
+    if data.censor()[i] == Censor::Quantified && !pool.is_empty() {
+      let e = pool[rng.gen_range(0..pool.len())];
+      o.2 = arr_fit_y[i] + arr_obs_sd[i] * e;
+    }
+  }
+  let mut new_data = Data::with_censor(obs, data.censor().to_vec());
+  new_data.covariates = data.covariates.clone();
+  new_data
+}
+
+fn resample_cases(data: &Data, rng: &mut StdRng) -> Data {
+  let n_obs = data.obs.len();
+  let mut obs = Vec::new();
+  let mut censor = Vec::new();
+  for _ in 0..n_obs {
+    let i = rng.gen_range(0..n_obs);
+    obs.push(data.obs[i]);
+    censor.push(data.censor()[i]);
+  }
+  let mut new_data = Data::with_censor(obs, censor);
+  new_data.covariates = data.covariates.clone();
+  new_data
+}
@@ -0,0 +1,83 @@
+use std::path::Path;
+
+use crate::simulator::write_file;
+use crate::stats;
+
+use ndarray::Array1;
+
+// fitted x of bootstrap replicates and percentile confidence intervals.
+pub struct BootstrapResult {
+  pub index: Vec<usize>,
+  pub estimate: Array1<f64>,
+  pub samples: Vec<Array1<f64>>,
+  pub n_failed: usize,
+  pub level: f64,
+  pub se: Vec<f64>,
+  pub lower: Vec<f64>,
+  pub upper: Vec<f64>,
+}
+
+impl BootstrapResult {
+  pub fn new(
+    index: Vec<usize>,
+    estimate: Array1<f64>,
+    samples: Vec<Array1<f64>>,
+    n_failed: usize,
+    level: f64,
+  ) -> Self {
+    let alpha = 1.0 - level;
+    let mut se = Vec::new();
+    let mut lower = Vec::new();
+    let mut upper = Vec::new();
+    for k in 0..index.len() {
+      let mut values: Vec<f64> = samples.iter().map(|x| x[k]).collect();
+      values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+      if values.len() < 2 {
+        se.push(f64::NAN);
+        lower.push(f64::NAN);
+        upper.push(f64::NAN);
+      } else {
+        se.push(stats::sd(&values));
+        lower.push(stats::quantile(&values, 0.5 * alpha));
+        upper.push(stats::quantile(&values, 1.0 - 0.5 * alpha));
+      }
+    }
+
+    Self {
+      index,
+      estimate,
+      samples,
+      n_failed,
+      level,
+      se,
+      lower,
+      upper,
+    }
+  }
+
+  pub fn save(&self, dir: &str) {
+    let save_dir = Path::new(dir);
+
+    // replicates: replicate, x
+    let mut str_result = String::new();
+    for (n_rep, x) in self.samples.iter().enumerate() {
+      str_result.push_str(&n_rep.to_string());
+      for v in x.iter() {
+        str_result.push(',');
+        str_result.push_str(&v.to_string());
+      }
+      str_result.push('\n');
+    }
+    write_file(&save_dir.join("bootstrap.csv"), &str_result);
+
+    // confidence intervals: index, estimate, se, lower, upper
+    let mut str_result = String::new();
+    for (k, index) in self.index.iter().enumerate() {
+      str_result.push_str(&format!(
+        "{},{},{},{},{}\n",
+        index, self.estimate[k], self.se[k], self.lower[k], self.upper[k]
+      ));
+    }
+    write_file(&save_dir.join("bootstrap_ci.csv"), &str_result);
+  }
+}
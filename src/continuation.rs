@@ -0,0 +1,5 @@
+mod base;
+mod result;
+
+pub use crate::continuation::base::{Continuation, ContinuationMethod};
+pub use crate::continuation::result::{Bifurcation, BifurcationKind, ContinuationResult};
@@ -0,0 +1,322 @@
+use super::result::ContinuationResult;
+
+use crate::linalg;
+use crate::model::OptModelTrait;
+use crate::simulator::Simulator;
+
+use ndarray::{Array1, Array2};
+
+// Natural: p is stepped by step and y is corrected by Newton iterations,
+// which stops at a fold of the branch.
+// PseudoArclength: (y, p) is stepped by the arclength |step| along the tangent
+// of the branch and corrected on the hyperplane normal to it,
+// which follows a branch around folds.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ContinuationMethod {
+  Natural,
+  PseudoArclength,
+}
+
+// continuation of equilibria f(y; p[index]) = 0 of ode at the initial time
+// from the steady state at the value of p[index] in a model.
+// the sign of step gives the direction in which p[index] goes first.
+// continuation stops when p[index] goes out of bounds, after max_steps,
+// or when the corrector does not converge.
+pub struct Continuation {
+  pub index: usize,
+  pub method: ContinuationMethod,
+  pub bounds: (f64, f64),
+  pub step: f64,
+  pub max_steps: usize,
+  pub tol: f64,
+}
+
+// integration time to find the first equilibrium
+const MAX_T: f64 = 1e4;
+const MAX_NEWTON: usize = 20;
+
+impl Continuation {
+  pub fn new(
+    index: usize,
+    method: ContinuationMethod,
+    bounds: (f64, f64),
+    step: f64,
+    max_steps: usize,
+    tol: f64,
+  ) -> Self {
+    Self {
+      index,
+      method,
+      bounds,
+      step,
+      max_steps,
+      tol,
+    }
+  }
+
+  pub fn run<M, const LEN_Y: usize, const LEN_P: usize, const LEN_B: usize, const LEN_X: usize>(
+    &self,
+    simulator: &Simulator<M, LEN_Y, LEN_P, LEN_B>,
+  ) -> ContinuationResult<LEN_Y>
+  where
+    M: OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X>,
+  {
+    let p0 = simulator.model.getp()[self.index];
+    let y0 = simulator
+      .steady_state(self.tol, MAX_T)
+      .expect("equilibrium is not found at the initial parameter value.");
+    let mut system = System::new(&simulator.model, self.index);
+
+    let branch = match self.method {
+      ContinuationMethod::Natural => self.natural(&mut system, p0, y0),
+      ContinuationMethod::PseudoArclength => self.pseudo_arclength(&mut system, p0, y0),
+    };
+
+    // eigenvalues of the Jacobian for stability
+    let mut eigvals = Vec::new();
+    for (p, y) in branch.iter() {
+      let jac = system.jacobian(y, *p).0;
+      eigvals.push(linalg::eigvals(&jac).unwrap_or_else(|| vec![(f64::NAN, f64::NAN); LEN_Y]));
+    }
+
+    let (p, y) = branch.into_iter().unzip();
+    ContinuationResult::new(self.index, p, y, eigvals)
+  }
+
+  fn natural<M, const LEN_Y: usize, const LEN_P: usize, const LEN_B: usize, const LEN_X: usize>(
+    &self,
+    system: &mut System<M, LEN_Y, LEN_P, LEN_B, LEN_X>,
+    p0: f64,
+    y0: [f64; LEN_Y],
+  ) -> Vec<(f64, [f64; LEN_Y])>
+  where
+    M: OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X>,
+  {
+    let mut branch = vec![(p0, y0)];
+    for k in 1..=self.max_steps {
+      let p = p0 + k as f64 * self.step;
+      if !self.in_bounds(p) {
+        break;
+      }
+
+      // secant predictor
+      let mut y = branch[k - 1].1;
+      if k >= 2 {
+        for i in 0..LEN_Y {
+          y[i] += y[i] - branch[k - 2].1[i];
+        }
+      }
+
+      // Newton corrector
+      let mut converged = false;
+      for _ in 0..MAX_NEWTON {
+        let (jac, _, f) = system.jacobian(&y, p);
+        let delta = match linalg::solve(&jac, &-f) {
+          Some(delta) => delta,
+          None => break,
+        };
+        for i in 0..LEN_Y {
+          y[i] += delta[i];
+        }
+        if !delta.iter().all(|d| d.is_finite()) {
+          break;
+        }
+        if norm(&delta) < self.tol * (1.0 + y.iter().map(|v| v * v).sum::<f64>().sqrt()) {
+          converged = true;
+          break;
+        }
+      }
+      if !converged {
+        break;
+      }
+      branch.push((p, y));
+    }
+    branch
+  }
+
+  fn pseudo_arclength<
+    M,
+    const LEN_Y: usize,
+    const LEN_P: usize,
+    const LEN_B: usize,
+    const LEN_X: usize,
+  >(
+    &self,
+    system: &mut System<M, LEN_Y, LEN_P, LEN_B, LEN_X>,
+    p0: f64,
+    y0: [f64; LEN_Y],
+  ) -> Vec<(f64, [f64; LEN_Y])>
+  where
+    M: OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X>,
+  {
+    let n = LEN_Y;
+    let max_ds = self.step.abs();
+    let min_ds = max_ds * 1e-3;
+
+    // u = (y, p)
+    let mut u = Array1::from_shape_fn(n + 1, |i| if i < n { y0[i] } else { p0 });
+
+    // the first tangent is (dy/dp, 1) normalized in the direction of step
+    let (jac_y, jac_p, _) = system.jacobian(&y0, p0);
+    let dy_dp = linalg::solve(&jac_y, &-jac_p).expect("the Jacobian is singular at the start.");
+    let mut tangent = Array1::from_shape_fn(n + 1, |i| if i < n { dy_dp[i] } else { 1.0 });
+    tangent *= self.step.signum() / norm(&tangent);
+
+    let mut branch = vec![(p0, y0)];
+    let mut ds = max_ds;
+    while branch.len() <= self.max_steps {
+      // predictor along the tangent
+      let mut new_u = &u + &(&tangent * ds);
+
+      // Newton corrector on the hyperplane normal to the tangent
+      let mut converged = false;
+      let mut n_iter = 0;
+      for _ in 0..MAX_NEWTON {
+        n_iter += 1;
+        let (y, p) = split(&new_u);
+        let (mat, f) = extended(system, &y, p, &tangent);
+        let mut rhs = -f;
+        rhs[n] = -(tangent.dot(&(&new_u - &u)) - ds);
+        let delta = match linalg::solve(&mat, &rhs) {
+          Some(delta) => delta,
+          None => break,
+        };
+        new_u += &delta;
+        if !delta.iter().all(|d| d.is_finite()) {
+          break;
+        }
+        if norm(&delta) < self.tol * (1.0 + norm(&new_u)) {
+          converged = true;
+          break;
+        }
+      }
+
+      if !converged {
+        // shorten the step
+        ds *= 0.5;
+        if ds < min_ds {
+          break;
+        }
+        continue;
+      }
+
+      // new tangent in the same orientation
+      let (y, p) = split(&new_u);
+      let (mat, _) = extended(system, &y, p, &tangent);
+      let mut rhs = Array1::zeros(n + 1);
+      rhs[n] = 1.0;
+      match linalg::solve(&mat, &rhs) {
+        Some(new_tangent) => tangent = &new_tangent / norm(&new_tangent),
+        None => break,
+      }
+
+      // the point out of bounds is dropped
+      if !self.in_bounds(p) {
+        break;
+      }
+      u = new_u;
+      branch.push((p, y));
+
+      // extend the step if the corrector converges quickly
+      if n_iter <= 3 {
+        ds = (2.0 * ds).min(max_ds);
+      }
+    }
+    branch
+  }
+
+  fn in_bounds(&self, p: f64) -> bool {
+    self.bounds.0 <= p && p <= self.bounds.1
+  }
+}
+
+// ode of a model as a function of (y, p[index]).
+struct System<M, const LEN_Y: usize, const LEN_P: usize, const LEN_B: usize, const LEN_X: usize>
+where
+  M: OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X>,
+{
+  model: M,
+  index: usize,
+  t: f64,
+}
+
+impl<M, const LEN_Y: usize, const LEN_P: usize, const LEN_B: usize, const LEN_X: usize>
+  System<M, LEN_Y, LEN_P, LEN_B, LEN_X>
+where
+  M: OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X>,
+{
+  fn new(model: &M, index: usize) -> Self {
+    let t = model.init().0;
+    Self {
+      model: model.clone(),
+      index,
+      t,
+    }
+  }
+
+  fn f(&mut self, y: &[f64; LEN_Y], p: f64) -> Array1<f64> {
+    self.model.setp(self.index, p);
+    let mut deriv_y = [0f64; LEN_Y];
+    self.model.ode(&self.t, y, &mut deriv_y);
+    Array1::from(deriv_y.to_vec())
+  }
+
+  // (df/dy, df/dp, f) by finite differences.
+  fn jacobian(&mut self, y: &[f64; LEN_Y], p: f64) -> (Array2<f64>, Array1<f64>, Array1<f64>) {
+    let f = self.f(y, p);
+    let mut jac_y = Array2::zeros((LEN_Y, LEN_Y));
+    for j in 0..LEN_Y {
+      let mut wk = *y;
+      let dx = f64::EPSILON.sqrt() * y[j].abs().max(1.0);
+      wk[j] += dx;
+      let f_dx = self.f(&wk, p);
+      for i in 0..LEN_Y {
+        jac_y[[i, j]] = (f_dx[i] - f[i]) / dx;
+      }
+    }
+    let dp = f64::EPSILON.sqrt() * p.abs().max(1.0);
+    let jac_p = (self.f(y, p + dp) - &f) / dp;
+    (jac_y, jac_p, f)
+  }
+}
+
+// ([df/dy, df/dp; tangent^T], f) of the extended system.
+fn extended<M, const LEN_Y: usize, const LEN_P: usize, const LEN_B: usize, const LEN_X: usize>(
+  system: &mut System<M, LEN_Y, LEN_P, LEN_B, LEN_X>,
+  y: &[f64; LEN_Y],
+  p: f64,
+  tangent: &Array1<f64>,
+) -> (Array2<f64>, Array1<f64>)
+where
+  M: OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X>,
+{
+  let n = LEN_Y;
+  let (jac_y, jac_p, f) = system.jacobian(y, p);
+  let mut mat = Array2::zeros((n + 1, n + 1));
+  for i in 0..n {
+    for j in 0..n {
+      mat[[i, j]] = jac_y[[i, j]];
+    }
+    mat[[i, n]] = jac_p[i];
+  }
+  for j in 0..=n {
+    mat[[n, j]] = tangent[j];
+  }
+  let mut f_ext = Array1::zeros(n + 1);
+  for i in 0..n {
+    f_ext[i] = f[i];
+  }
+  (mat, f_ext)
+}
+
+fn split<const LEN_Y: usize>(u: &Array1<f64>) -> ([f64; LEN_Y], f64) {
+  let mut y = [0f64; LEN_Y];
+  for i in 0..LEN_Y {
+    y[i] = u[i];
+  }
+  (y, u[LEN_Y])
+}
+
+fn norm(v: &Array1<f64>) -> f64 {
+  v.dot(v).sqrt()
+}
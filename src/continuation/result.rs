@@ -0,0 +1,178 @@
+use std::path::Path;
+
+use crate::simulator::write_file;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum BifurcationKind {
+  // a real eigenvalue crosses zero (including branch points)
+  SaddleNode,
+  // a pair of complex eigenvalues crosses the imaginary axis
+  Hopf,
+}
+
+// bifurcation between the step-th point and the previous one,
+// where (p, y) is linearly interpolated to the crossing.
+#[derive(Clone, Copy, Debug)]
+pub struct Bifurcation<const LEN_Y: usize> {
+  pub kind: BifurcationKind,
+  pub step: usize,
+  pub p: f64,
+  pub y: [f64; LEN_Y],
+}
+
+// branch of equilibria (p[index], y) with eigenvalues (re, im) of the Jacobian.
+// an equilibrium is stable if all eigenvalues have negative real parts.
+pub struct ContinuationResult<const LEN_Y: usize> {
+  pub index: usize,
+  pub p: Vec<f64>,
+  pub y: Vec<[f64; LEN_Y]>,
+  pub eigvals: Vec<Vec<(f64, f64)>>,
+  pub stable: Vec<bool>,
+  pub bifurcations: Vec<Bifurcation<LEN_Y>>,
+}
+
+impl<const LEN_Y: usize> ContinuationResult<LEN_Y> {
+  pub fn new(
+    index: usize,
+    p: Vec<f64>,
+    y: Vec<[f64; LEN_Y]>,
+    eigvals: Vec<Vec<(f64, f64)>>,
+  ) -> Self {
+    let stable = eigvals
+      .iter()
+      .map(|eigvals| eigvals.iter().all(|&(re, _)| re < 0.0))
+      .collect();
+
+    // sign changes of the test functions between consecutive points
+    let mut bifurcations = Vec::new();
+    for k in 1..p.len() {
+      let (prev, cur) = (&eigvals[k - 1], &eigvals[k]);
+
+      let (a, b) = (determinant(prev), determinant(cur));
+      if crosses(a, b) {
+        bifurcations.push(interpolate(BifurcationKind::SaddleNode, k, a, b, &p, &y));
+      }
+
+      // a complex pair is required on both sides,
+      // which excludes neutral saddles (real eigenvalues l and -l).
+      let (a, b) = (bialternate(prev), bialternate(cur));
+      if crosses(a, b) && has_complex(prev) && has_complex(cur) {
+        bifurcations.push(interpolate(BifurcationKind::Hopf, k, a, b, &p, &y));
+      }
+    }
+
+    Self {
+      index,
+      p,
+      y,
+      eigvals,
+      stable,
+      bifurcations,
+    }
+  }
+
+  pub fn save(&self, dir: &str) {
+    let save_dir = Path::new(dir);
+
+    // branch: p, stable, y
+    let mut str_result = String::new();
+    for (k, (p, y)) in self.p.iter().zip(self.y.iter()).enumerate() {
+      str_result.push_str(&format!("{},{}", p, self.stable[k] as u8));
+      for v in y.iter() {
+        str_result.push(',');
+        str_result.push_str(&v.to_string());
+      }
+      str_result.push('\n');
+    }
+    write_file(&save_dir.join("continuation.csv"), &str_result);
+
+    // eigenvalues: step, re, im
+    let mut str_result = String::new();
+    for (k, eigvals) in self.eigvals.iter().enumerate() {
+      for (re, im) in eigvals.iter() {
+        str_result.push_str(&format!("{},{},{}\n", k, re, im));
+      }
+    }
+    write_file(&save_dir.join("continuation_eigvals.csv"), &str_result);
+
+    // bifurcations: kind, step, p, y
+    let mut str_result = String::new();
+    for bifurcation in self.bifurcations.iter() {
+      str_result.push_str(&format!(
+        "{:?},{},{}",
+        bifurcation.kind, bifurcation.step, bifurcation.p
+      ));
+      for v in bifurcation.y.iter() {
+        str_result.push(',');
+        str_result.push_str(&v.to_string());
+      }
+      str_result.push('\n');
+    }
+    write_file(&save_dir.join("continuation_bifurcation.csv"), &str_result);
+  }
+}
+
+// det(J) as the product of all eigenvalues,
+// which changes its sign when a real eigenvalue crosses zero.
+// complex pairs (re^2 + im^2 > 0) do not change the sign,
+// e.g. when an unstable focus turns into an unstable node.
+fn determinant(eigvals: &[(f64, f64)]) -> f64 {
+  eigvals
+    .iter()
+    .fold((1.0, 0.0), |acc, &eigval| mul(acc, eigval))
+    .0
+}
+
+// product of l_i + l_j (i < j) over eigenvalues, which changes its sign
+// when the real part of a complex pair (l + conj(l) = 2 re) crosses zero.
+// it is continuous where a complex pair turns into two real eigenvalues.
+fn bialternate(eigvals: &[(f64, f64)]) -> f64 {
+  let mut product = (1.0, 0.0);
+  for (i, &(re_i, im_i)) in eigvals.iter().enumerate() {
+    for &(re_j, im_j) in eigvals.iter().skip(i + 1) {
+      product = mul(product, (re_i + re_j, im_i + im_j));
+    }
+  }
+  product.0
+}
+
+// a test function goes from a to b across zero
+// (a point just at zero counts as the end of the crossing).
+fn crosses(a: f64, b: f64) -> bool {
+  a.is_finite() && b.is_finite() && (a < 0.0) != (b < 0.0)
+}
+
+fn has_complex(eigvals: &[(f64, f64)]) -> bool {
+  eigvals.iter().any(|&(_, im)| im != 0.0)
+}
+
+// product of complex numbers (re, im).
+fn mul((a, b): (f64, f64), (c, d): (f64, f64)) -> (f64, f64) {
+  (a * c - b * d, a * d + b * c)
+}
+
+// (p, y) where the test function goes from a to b linearly crosses zero.
+// the midpoint if the crossing is not found.
+fn interpolate<const LEN_Y: usize>(
+  kind: BifurcationKind,
+  step: usize,
+  a: f64,
+  b: f64,
+  p: &[f64],
+  y: &[[f64; LEN_Y]],
+) -> Bifurcation<LEN_Y> {
+  let mut s = a / (a - b);
+  if !(0.0..=1.0).contains(&s) {
+    s = 0.5;
+  }
+  let mut y_s = [0f64; LEN_Y];
+  for i in 0..LEN_Y {
+    y_s[i] = y[step - 1][i] + s * (y[step][i] - y[step - 1][i]);
+  }
+  Bifurcation {
+    kind,
+    step,
+    p: p[step - 1] + s * (p[step] - p[step - 1]),
+    y: y_s,
+  }
+}
@@ -0,0 +1,48 @@
+// Covariate relationship of a parameter declared by a model (OptModelTrait::covariates).
+// p[index] of a subject is the value in a model (typical value)
+// multiplied by the effect of the covariate named name in Data.
+#[derive(Clone, Debug)]
+pub struct Covariate {
+  pub index: usize,
+  pub name: String,
+  pub relation: Relation,
+}
+
+#[derive(Clone, Debug)]
+pub enum Relation {
+  // (c / reference)^exponent, e.g. allometric scaling by body weight
+  Power { reference: f64, exponent: f64 },
+
+  // 1 + slope * (c - reference)
+  Linear { reference: f64, slope: f64 },
+
+  // factor of the category equal to c (1 for other categories),
+  // given as (category, factor)
+  Categorical { factors: Vec<(f64, f64)> },
+}
+
+impl Covariate {
+  pub fn new(index: usize, name: &str, relation: Relation) -> Self {
+    Self {
+      index,
+      name: name.to_string(),
+      relation,
+    }
+  }
+
+  pub fn effect(&self, c: f64) -> f64 {
+    match &self.relation {
+      Relation::Power {
+        reference,
+        exponent,
+      } => (c / reference).powf(*exponent),
+
+      Relation::Linear { reference, slope } => 1.0 + slope * (c - reference),
+
+      Relation::Categorical { factors } => factors
+        .iter()
+        .find(|(category, _)| *category == c)
+        .map_or(1.0, |&(_, factor)| factor),
+    }
+  }
+}
@@ -0,0 +1,117 @@
+use ndarray::Array1;
+
+// (index, t, y, terr, yerr)
+pub type Obs = (usize, f64, f64, Option<f64>, Option<f64>);
+
+// censoring of an observation.
+// y of a censored observation is the limit of quantification.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Censor {
+  Quantified,
+  // below the lower limit of quantification (LLOQ)
+  Below(f64),
+  // above the upper limit of quantification (ULOQ)
+  Above(f64),
+}
+
+// observations of a subject.
+// censoring of obs[i] is censor()[i], given at construction.
+// covariates are (name, value) of the subject, e.g. ("WT", 70.0).
+#[derive(Clone)]
+pub struct Data {
+  pub obs: Vec<Obs>,
+  censor: Vec<Censor>,
+  pub covariates: Vec<(String, f64)>,
+}
+
+#[allow(dead_code)]
+impl Data {
+  pub fn new(obs: Vec<Obs>) -> Self {
+    let censor = vec![Censor::Quantified; obs.len()];
+    Self::with_censor(obs, censor)
+  }
+
+  // censor[i] corresponds to obs[i].
+  pub fn with_censor(obs: Vec<Obs>, censor: Vec<Censor>) -> Self {
+    if obs.len() != censor.len() {
+      panic!("obs and censor must have the same length.");
+    }
+    Self {
+      obs,
+      censor,
+      covariates: Vec::new(),
+    }
+  }
+
+  // censoring of each observation.
+  pub fn censor(&self) -> &[Censor] {
+    if self.obs.len() != self.censor.len() {
+      panic!("obs and censor must have the same length.");
+    }
+    &self.censor
+  }
+
+  // whether yerr is given in all observations.
+  pub fn has_yerr(&self) -> bool {
+    self.obs.iter().all(|&(_, _, _, _, yerr)| yerr.is_some())
+  }
+
+  // set (or overwrite) the value of a covariate.
+  pub fn set_covariate(&mut self, name: &str, value: f64) {
+    match self.covariates.iter_mut().find(|(n, _)| n == name) {
+      Some(covariate) => covariate.1 = value,
+      None => self.covariates.push((name.to_string(), value)),
+    }
+  }
+
+  pub fn covariate(&self, name: &str) -> Option<f64> {
+    self
+      .covariates
+      .iter()
+      .find(|(n, _)| n == name)
+      .map(|&(_, value)| value)
+  }
+
+  pub fn make_sampling_time(&self) -> Vec<f64> {
+    // collect all of the unique observation times in data.
+    let mut vec_smp_t = Vec::new();
+    for &(_, t, _, _, _) in self.obs.iter() {
+      vec_smp_t.push(t);
+    }
+    vec_smp_t.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    vec_smp_t.dedup();
+    vec_smp_t
+  }
+
+  pub fn make_arr_obs_y(&self) -> Array1<f64> {
+    let mut vec_obs_y = Vec::new();
+    for &(_, _, y, _, _) in self.obs.iter() {
+      vec_obs_y.push(y);
+    }
+    Array1::from(vec_obs_y)
+  }
+
+  // SD of each observation (yerr, or 1.0 if not given).
+  // the Gaussian loss requires yerr of all observations.
+  pub fn make_arr_obs_sd(&self) -> Array1<f64> {
+    let mut vec_obs_sd = Vec::new();
+    for &(_, _, _, _, yerr) in self.obs.iter() {
+      vec_obs_sd.push(yerr.unwrap_or(1.0));
+    }
+    Array1::from(vec_obs_sd)
+  }
+
+  pub fn make_ty_index(&self, vec_smp_t: &[f64]) -> Vec<(usize, usize)> {
+    let mut ty_index = Vec::new();
+    for &(y_index, obs_t, _, _, _) in self.obs.iter() {
+      let mut t_index = 0;
+      for (j, &smp_t) in vec_smp_t.iter().enumerate() {
+        if obs_t == smp_t {
+          t_index = j;
+        }
+      }
+      ty_index.push((t_index, y_index));
+    }
+    ty_index
+  }
+}
@@ -0,0 +1,421 @@
+use super::base::{Censor, Data, Obs};
+
+use std::fmt;
+use std::fs;
+
+// layout of a CSV file with a header line.
+// Long: one observation per row with the columns
+//   state, time, value[, time_err][, value_err][, blq][, lloq][, uloq]
+//   where state is a name in the state map or a state index.
+//   a row flagged by blq is censored at lloq (or value if lloq is not given).
+// Wide: a time column and one column per state named in the state map
+//   (or a state index). every other column must be a covariate.
+// A cell of "<LLOQ" (e.g. "<0.5") or ">ULOQ" is a censored observation,
+// and "BLQ" / "ALQ" cells are censored at lloq / uloq of the row (long only).
+// A censored observation without its limit is an error.
+// Empty, "NA", "NaN" and "." cells are missing values and skipped.
+// Fields may be quoted ("...") to contain commas, with "" for a quote.
+// Covariates of the subject are columns named in covariates (both formats),
+// which must have the same value in every row (missing cells are skipped).
+#[derive(Clone, Copy)]
+pub enum CsvFormat {
+  Long,
+  Wide,
+}
+
+#[derive(Debug)]
+pub enum DataError {
+  Io(std::io::Error),
+  Empty,
+  MissingColumn(String),
+  UnknownState {
+    line: usize,
+    name: String,
+  },
+  RowLength {
+    line: usize,
+    expected: usize,
+    found: usize,
+  },
+  InvalidValue {
+    line: usize,
+    column: String,
+    value: String,
+  },
+  InconsistentCovariate {
+    line: usize,
+    name: String,
+  },
+  MissingLimit {
+    line: usize,
+    column: String,
+  },
+}
+
+impl fmt::Display for DataError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      DataError::Io(err) => write!(f, "failed to read CSV file: {}", err),
+      DataError::Empty => write!(f, "CSV file has no header line"),
+      DataError::MissingColumn(column) => write!(f, "column '{}' is missing in header", column),
+      DataError::UnknownState { line, name } => {
+        write!(f, "line {}: '{}' is not in the state map", line, name)
+      }
+      DataError::RowLength {
+        line,
+        expected,
+        found,
+      } => write!(
+        f,
+        "line {}: expected {} fields but found {}",
+        line, expected, found
+      ),
+      DataError::InvalidValue {
+        line,
+        column,
+        value,
+      } => write!(
+        f,
+        "line {}: invalid value '{}' in column '{}'",
+        line, value, column
+      ),
+      DataError::InconsistentCovariate { line, name } => write!(
+        f,
+        "line {}: covariate '{}' differs from the previous rows",
+        line, name
+      ),
+      DataError::MissingLimit { line, column } => write!(
+        f,
+        "line {}: censored value in column '{}' has no limit of quantification",
+        line, column
+      ),
+    }
+  }
+}
+
+impl std::error::Error for DataError {}
+
+impl From<std::io::Error> for DataError {
+  fn from(err: std::io::Error) -> Self {
+    DataError::Io(err)
+  }
+}
+
+// content of a cell
+enum Cell {
+  Value(f64),
+  Missing,
+  Blq(Option<f64>),
+  Alq(Option<f64>),
+}
+
+impl Data {
+  // read observations from a CSV file.
+  // states maps names of states (long: values in the state column,
+  // wide: column names) to state indices in a model.
+  // state indices are checked against a model by Objective::new.
+  // covariates are names of covariate columns (e.g. &["WT"]).
+  pub fn from_csv(
+    path: &str,
+    format: CsvFormat,
+    states: &[(&str, usize)],
+    covariates: &[&str],
+  ) -> Result<Self, DataError> {
+    let text = fs::read_to_string(path)?;
+
+    // (line number, fields) of non-empty lines except comments
+    let mut rows = text
+      .lines()
+      .enumerate()
+      .map(|(i, line)| (i + 1, line.trim()))
+      .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+      .map(|(i, line)| (i, split_fields(line)));
+
+    let (header_line, header) = rows.next().ok_or(DataError::Empty)?;
+    let rows: Vec<(usize, Vec<String>)> = rows.collect();
+
+    for (line, fields) in rows.iter() {
+      if fields.len() != header.len() {
+        return Err(DataError::RowLength {
+          line: *line,
+          expected: header.len(),
+          found: fields.len(),
+        });
+      }
+    }
+
+    let obs = match format {
+      CsvFormat::Long => read_long(&header, &rows, states)?,
+      CsvFormat::Wide => read_wide(header_line, &header, &rows, states, covariates)?,
+    };
+
+    let (obs, censor) = obs.into_iter().unzip();
+    let mut data = Self::with_censor(obs, censor);
+    for &name in covariates.iter() {
+      if let Some(value) = read_covariate(&header, &rows, name)? {
+        data.set_covariate(name, value);
+      }
+    }
+    Ok(data)
+  }
+}
+
+// observations with censoring
+type CensoredObs = Vec<(Obs, Censor)>;
+
+fn read_long(
+  header: &[String],
+  rows: &[(usize, Vec<String>)],
+  states: &[(&str, usize)],
+) -> Result<CensoredObs, DataError> {
+  let col_state = find_column(header, "state")?;
+  let col_time = find_column(header, "time")?;
+  let col_value = find_column(header, "value")?;
+  let col_time_err = find_column(header, "time_err").ok();
+  let col_value_err = find_column(header, "value_err").ok();
+  let col_blq = find_column(header, "blq").ok();
+  let col_lloq = find_column(header, "lloq").ok();
+  let col_uloq = find_column(header, "uloq").ok();
+
+  let mut obs = Vec::new();
+  for (line, fields) in rows.iter() {
+    let line = *line;
+
+    let name = &fields[col_state];
+    let y_index = match lookup_state(states, name) {
+      Some(y_index) => y_index,
+      None => {
+        return Err(DataError::UnknownState {
+          line,
+          name: name.clone(),
+        })
+      }
+    };
+
+    let t = match parse_cell(line, &header[col_time], &fields[col_time])? {
+      Cell::Value(t) => t,
+      _ => return Err(invalid_value(line, &header[col_time], &fields[col_time])),
+    };
+
+    let terr = parse_optional(line, header, fields, col_time_err)?;
+    let yerr = parse_optional(line, header, fields, col_value_err)?;
+
+    let is_blq = match col_blq {
+      Some(col) => parse_flag(line, &header[col], &fields[col])?,
+      None => false,
+    };
+    let lloq = parse_optional(line, header, fields, col_lloq)?;
+    let uloq = parse_optional(line, header, fields, col_uloq)?;
+
+    let missing_limit = || DataError::MissingLimit {
+      line,
+      column: header[col_value].clone(),
+    };
+    let limit = match parse_cell(line, &header[col_value], &fields[col_value])? {
+      Cell::Value(y) if is_blq => Some((lloq.unwrap_or(y), Censor::Below(lloq.unwrap_or(y)))),
+      Cell::Value(y) => Some((y, Censor::Quantified)),
+      Cell::Missing if is_blq => {
+        let l = lloq.ok_or_else(missing_limit)?;
+        Some((l, Censor::Below(l)))
+      }
+      Cell::Missing => None,
+      Cell::Blq(l) => {
+        let l = l.or(lloq).ok_or_else(missing_limit)?;
+        Some((l, Censor::Below(l)))
+      }
+      Cell::Alq(u) => {
+        let u = u.or(uloq).ok_or_else(missing_limit)?;
+        Some((u, Censor::Above(u)))
+      }
+    };
+
+    if let Some((y, censor)) = limit {
+      obs.push(((y_index, t, y, terr, yerr), censor));
+    }
+  }
+
+  Ok(obs)
+}
+
+fn read_wide(
+  header_line: usize,
+  header: &[String],
+  rows: &[(usize, Vec<String>)],
+  states: &[(&str, usize)],
+  covariates: &[&str],
+) -> Result<CensoredObs, DataError> {
+  let col_time = find_column(header, "time")?;
+
+  // (column, state index)
+  let mut state_columns = Vec::new();
+  for (col, name) in header.iter().enumerate() {
+    if col == col_time || covariates.iter().any(|c| c.eq_ignore_ascii_case(name)) {
+      continue;
+    }
+    match lookup_state(states, name) {
+      Some(y_index) => state_columns.push((col, y_index)),
+      None => {
+        return Err(DataError::UnknownState {
+          line: header_line,
+          name: name.clone(),
+        })
+      }
+    }
+  }
+
+  let mut obs = Vec::new();
+  for (line, fields) in rows.iter() {
+    let line = *line;
+
+    let t = match parse_cell(line, &header[col_time], &fields[col_time])? {
+      Cell::Value(t) => t,
+      _ => return Err(invalid_value(line, &header[col_time], &fields[col_time])),
+    };
+
+    for &(col, y_index) in state_columns.iter() {
+      match parse_cell(line, &header[col], &fields[col])? {
+        Cell::Value(y) => obs.push(((y_index, t, y, None, None), Censor::Quantified)),
+        Cell::Blq(Some(l)) => obs.push(((y_index, t, l, None, None), Censor::Below(l))),
+        Cell::Alq(Some(u)) => obs.push(((y_index, t, u, None, None), Censor::Above(u))),
+        Cell::Blq(None) | Cell::Alq(None) => {
+          return Err(DataError::MissingLimit {
+            line,
+            column: header[col].clone(),
+          })
+        }
+        Cell::Missing => {}
+      }
+    }
+  }
+
+  Ok(obs)
+}
+
+// value of a covariate column, which is the same in every row.
+// None if all cells are missing.
+fn read_covariate(
+  header: &[String],
+  rows: &[(usize, Vec<String>)],
+  name: &str,
+) -> Result<Option<f64>, DataError> {
+  let col = find_column(header, name)?;
+  let mut value = None;
+  for (line, fields) in rows.iter() {
+    match parse_cell(*line, &header[col], &fields[col])? {
+      Cell::Value(v) if value.is_none() => value = Some(v),
+      Cell::Value(v) if value != Some(v) => {
+        return Err(DataError::InconsistentCovariate {
+          line: *line,
+          name: name.to_string(),
+        })
+      }
+      Cell::Value(_) | Cell::Missing => {}
+      Cell::Blq(_) | Cell::Alq(_) => return Err(invalid_value(*line, &header[col], &fields[col])),
+    }
+  }
+  Ok(value)
+}
+
+// fields of a line separated by commas outside quotes.
+fn split_fields(line: &str) -> Vec<String> {
+  let mut fields = Vec::new();
+  let mut field = String::new();
+  let mut in_quotes = false;
+  let mut chars = line.chars().peekable();
+  while let Some(c) = chars.next() {
+    match c {
+      '"' if in_quotes && chars.peek() == Some(&'"') => {
+        field.push('"');
+        chars.next();
+      }
+      '"' => in_quotes = !in_quotes,
+      ',' if !in_quotes => fields.push(std::mem::take(&mut field).trim().to_string()),
+      _ => field.push(c),
+    }
+  }
+  fields.push(field.trim().to_string());
+  fields
+}
+
+fn find_column(header: &[String], name: &str) -> Result<usize, DataError> {
+  header
+    .iter()
+    .position(|column| column.eq_ignore_ascii_case(name))
+    .ok_or_else(|| DataError::MissingColumn(name.to_string()))
+}
+
+fn lookup_state(states: &[(&str, usize)], name: &str) -> Option<usize> {
+  match states.iter().find(|(state, _)| *state == name) {
+    Some(&(_, y_index)) => Some(y_index),
+    None => name.parse::<usize>().ok(),
+  }
+}
+
+fn parse_cell(line: usize, column: &str, value: &str) -> Result<Cell, DataError> {
+  if value.is_empty()
+    || value == "."
+    || value.eq_ignore_ascii_case("na")
+    || value.eq_ignore_ascii_case("nan")
+  {
+    return Ok(Cell::Missing);
+  }
+
+  if value.eq_ignore_ascii_case("blq") {
+    return Ok(Cell::Blq(None));
+  }
+
+  if value.eq_ignore_ascii_case("alq") {
+    return Ok(Cell::Alq(None));
+  }
+
+  if let Some(lloq) = value.strip_prefix('<') {
+    return match lloq.trim().parse::<f64>() {
+      Ok(lloq) => Ok(Cell::Blq(Some(lloq))),
+      Err(_) => Err(invalid_value(line, column, value)),
+    };
+  }
+
+  if let Some(uloq) = value.strip_prefix('>') {
+    return match uloq.trim().parse::<f64>() {
+      Ok(uloq) => Ok(Cell::Alq(Some(uloq))),
+      Err(_) => Err(invalid_value(line, column, value)),
+    };
+  }
+
+  match value.parse::<f64>() {
+    Ok(value) => Ok(Cell::Value(value)),
+    Err(_) => Err(invalid_value(line, column, value)),
+  }
+}
+
+fn parse_optional(
+  line: usize,
+  header: &[String],
+  fields: &[String],
+  col: Option<usize>,
+) -> Result<Option<f64>, DataError> {
+  match col {
+    Some(col) => match parse_cell(line, &header[col], &fields[col])? {
+      Cell::Value(value) => Ok(Some(value)),
+      Cell::Missing => Ok(None),
+      Cell::Blq(_) | Cell::Alq(_) => Err(invalid_value(line, &header[col], &fields[col])),
+    },
+    None => Ok(None),
+  }
+}
+
+fn parse_flag(line: usize, column: &str, value: &str) -> Result<bool, DataError> {
+  match value.to_ascii_lowercase().as_str() {
+    "" | "0" | "false" | "no" | "n" => Ok(false),
+    "1" | "true" | "yes" | "y" => Ok(true),
+    _ => Err(invalid_value(line, column, value)),
+  }
+}
+
+fn invalid_value(line: usize, column: &str, value: &str) -> DataError {
+  DataError::InvalidValue {
+    line,
+    column: column.to_string(),
+    value: value.to_string(),
+  }
+}
@@ -0,0 +1,142 @@
+// A dose into y[compartment] at time.
+// duration 0 is a bolus (instantaneous), otherwise a zero-order infusion
+// of amount over duration. bioavailability scales the amount.
+#[derive(Clone, Debug)]
+pub struct Dose {
+  pub time: f64,
+  pub amount: f64,
+  pub compartment: usize,
+  pub duration: f64,
+  pub bioavailability: f64,
+}
+
+impl Dose {
+  pub fn bolus(time: f64, amount: f64, compartment: usize) -> Self {
+    Self {
+      time,
+      amount,
+      compartment,
+      duration: 0.0,
+      bioavailability: 1.0,
+    }
+  }
+
+  pub fn infusion(time: f64, amount: f64, compartment: usize, duration: f64) -> Self {
+    if duration <= 0.0 {
+      panic!("duration of an infusion must be positive.");
+    }
+    Self {
+      time,
+      amount,
+      compartment,
+      duration,
+      bioavailability: 1.0,
+    }
+  }
+
+  // infusion given by its rate (amount per time)
+  pub fn infusion_rate(time: f64, rate: f64, compartment: usize, duration: f64) -> Self {
+    Self::infusion(time, rate * duration, compartment, duration)
+  }
+
+  pub fn bioavailability(mut self, bioavailability: f64) -> Self {
+    self.bioavailability = bioavailability;
+    self
+  }
+
+  pub fn is_bolus(&self) -> bool {
+    self.duration == 0.0
+  }
+
+  // rate of the infusion into the compartment (0 for a bolus)
+  pub fn rate(&self) -> f64 {
+    if self.is_bolus() {
+      0.0
+    } else {
+      self.bioavailability * self.amount / self.duration
+    }
+  }
+}
+
+// Doses applied by Simulator::run in addition to the model equations,
+// so that a model does not need beats and rec for dosing.
+// e.g. a loading dose followed by 10 maintenance doses every 12 hours:
+//   DosingRegimen::new()
+//     .dose(Dose::bolus(0.0, 200.0, 0))
+//     .repeat(Dose::infusion(12.0, 100.0, 1, 0.5), 12.0, 10)
+#[derive(Clone, Debug, Default)]
+pub struct DosingRegimen {
+  pub doses: Vec<Dose>,
+}
+
+impl DosingRegimen {
+  pub fn new() -> Self {
+    Self { doses: Vec::new() }
+  }
+
+  pub fn dose(mut self, dose: Dose) -> Self {
+    self.doses.push(dose);
+    self
+  }
+
+  // count doses from dose.time every interval.
+  pub fn repeat(mut self, dose: Dose, interval: f64, count: usize) -> Self {
+    for n in 0..count {
+      let mut repeated = dose.clone();
+      repeated.time += n as f64 * interval;
+      self.doses.push(repeated);
+    }
+    self
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.doses.is_empty()
+  }
+
+  // amounts of boluses given at t, whose times are in (prev_t, t]
+  // within a tolerance for rounding errors (e.g. of repeated doses),
+  // so that each bolus is given once over increasing t.
+  // prev_t is None at the initial time, when boluses at t are given.
+  pub fn bolus_at<const LEN_Y: usize>(&self, prev_t: Option<f64>, t: f64, y: &mut [f64; LEN_Y]) {
+    let upper = t + time_tol(t);
+    let is_given = |time: f64| match prev_t {
+      Some(prev_t) => prev_t + time_tol(prev_t) < time && time <= upper,
+      None => t - time_tol(t) <= time && time <= upper,
+    };
+    for dose in self.doses.iter() {
+      if dose.is_bolus() && is_given(dose.time) {
+        y[dose.compartment] += dose.bioavailability * dose.amount;
+      }
+    }
+  }
+
+  // total infusion rates into each compartment in [t, next breakpoint)
+  pub fn rates_at<const LEN_Y: usize>(&self, t: f64) -> [f64; LEN_Y] {
+    let mut rates = [0f64; LEN_Y];
+    for dose in self.doses.iter() {
+      if !dose.is_bolus() && dose.time <= t && t < dose.time + dose.duration {
+        rates[dose.compartment] += dose.rate();
+      }
+    }
+    rates
+  }
+
+  // times where the ODE must be restarted (doses and ends of infusions)
+  pub fn breakpoints(&self) -> Vec<f64> {
+    let mut breakpoints = Vec::new();
+    for dose in self.doses.iter() {
+      breakpoints.push(dose.time);
+      if !dose.is_bolus() {
+        breakpoints.push(dose.time + dose.duration);
+      }
+    }
+    breakpoints.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    breakpoints.dedup();
+    breakpoints
+  }
+}
+
+// tolerance for matching times of doses
+fn time_tol(t: f64) -> f64 {
+  1e-10 * t.abs().max(1.0)
+}
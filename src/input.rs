@@ -0,0 +1,143 @@
+// Time-varying exogenous input u(t) from a table of (t, u).
+// t must be strictly increasing. u is held at the first (last) value
+// before (after) the table. values are given to SimModelTrait::ode_input
+// in the order of Simulator::inputs, and t of the table are breakpoints
+// where Simulator::run restarts the ODE solving.
+#[derive(Clone, Debug)]
+pub struct Input {
+  interpolation: Interpolation,
+  t: Vec<f64>,
+  u: Vec<f64>,
+  slopes: Vec<f64>,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Interpolation {
+  // u[i] in [t[i], t[i + 1])
+  PiecewiseConstant,
+  // linear between points
+  PiecewiseLinear,
+  // monotone cubic Hermite (Fritsch-Carlson), which does not overshoot the table
+  Cubic,
+}
+
+impl Input {
+  pub fn new(interpolation: Interpolation, t: Vec<f64>, u: Vec<f64>) -> Self {
+    if t.is_empty() || t.len() != u.len() {
+      panic!("t and u of an input must have the same nonzero length.");
+    }
+    if t.windows(2).any(|w| w[0] >= w[1]) {
+      panic!("t of an input must be strictly increasing.");
+    }
+    let slopes = match interpolation {
+      Interpolation::Cubic => monotone_slopes(&t, &u),
+      _ => Vec::new(),
+    };
+    Self {
+      interpolation,
+      t,
+      u,
+      slopes,
+    }
+  }
+
+  pub fn piecewise_constant(t: Vec<f64>, u: Vec<f64>) -> Self {
+    Self::new(Interpolation::PiecewiseConstant, t, u)
+  }
+
+  pub fn piecewise_linear(t: Vec<f64>, u: Vec<f64>) -> Self {
+    Self::new(Interpolation::PiecewiseLinear, t, u)
+  }
+
+  pub fn cubic(t: Vec<f64>, u: Vec<f64>) -> Self {
+    Self::new(Interpolation::Cubic, t, u)
+  }
+
+  pub fn breakpoints(&self) -> &[f64] {
+    &self.t
+  }
+
+  pub fn value(&self, t: f64) -> f64 {
+    let n = self.t.len();
+    if t <= self.t[0] {
+      return self.u[0];
+    }
+    if t >= self.t[n - 1] {
+      return self.u[n - 1];
+    }
+
+    // t[i] <= t < t[i + 1]
+    let i = self.t.partition_point(|&ti| ti <= t) - 1;
+    let h = self.t[i + 1] - self.t[i];
+    let s = (t - self.t[i]) / h;
+    match self.interpolation {
+      Interpolation::PiecewiseConstant => self.u[i],
+
+      Interpolation::PiecewiseLinear => self.u[i] + s * (self.u[i + 1] - self.u[i]),
+
+      Interpolation::Cubic => {
+        // cubic Hermite basis
+        let h00 = (1.0 + 2.0 * s) * (1.0 - s).powi(2);
+        let h10 = s * (1.0 - s).powi(2);
+        let h01 = s.powi(2) * (3.0 - 2.0 * s);
+        let h11 = s.powi(2) * (s - 1.0);
+        h00 * self.u[i]
+          + h10 * h * self.slopes[i]
+          + h01 * self.u[i + 1]
+          + h11 * h * self.slopes[i + 1]
+      }
+    }
+  }
+
+  // value within the ODE solving from start to end.
+  // the value at end is the limit from the left,
+  // so that a step ending at a breakpoint does not see the next piece.
+  pub(crate) fn value_in(&self, t: f64, (start, end): (f64, f64)) -> f64 {
+    if t < end {
+      self.value(t.max(start))
+    } else if self.interpolation == Interpolation::PiecewiseConstant {
+      let i = self.t.partition_point(|&ti| ti < end);
+      self.u[i.saturating_sub(1)]
+    } else {
+      self.value(end)
+    }
+  }
+}
+
+// slopes at the points of a monotone cubic interpolation
+fn monotone_slopes(t: &[f64], u: &[f64]) -> Vec<f64> {
+  let n = t.len();
+  if n < 2 {
+    return vec![0.0; n];
+  }
+  let delta: Vec<f64> = (0..n - 1)
+    .map(|i| (u[i + 1] - u[i]) / (t[i + 1] - t[i]))
+    .collect();
+
+  let mut slopes = vec![0.0; n];
+  slopes[0] = delta[0];
+  slopes[n - 1] = delta[n - 2];
+  for i in 1..n - 1 {
+    if delta[i - 1] * delta[i] > 0.0 {
+      slopes[i] = 0.5 * (delta[i - 1] + delta[i]);
+    }
+  }
+
+  // limit slopes to keep monotonicity in each interval
+  for i in 0..n - 1 {
+    if delta[i] == 0.0 {
+      slopes[i] = 0.0;
+      slopes[i + 1] = 0.0;
+      continue;
+    }
+    let a = slopes[i] / delta[i];
+    let b = slopes[i + 1] / delta[i];
+    let r = a.powi(2) + b.powi(2);
+    if r > 9.0 {
+      let tau = 3.0 / r.sqrt();
+      slopes[i] = tau * a * delta[i];
+      slopes[i + 1] = tau * b * delta[i];
+    }
+  }
+  slopes
+}
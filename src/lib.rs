@@ -1,9 +1,23 @@
+#![allow(clippy::needless_range_loop)]
+
 mod beat;
+pub mod bootstrap;
+pub mod continuation;
+pub mod covariate;
 pub mod data;
+pub mod dosing;
+pub mod input;
+mod linalg;
+pub mod mcmc;
 pub mod model;
 pub mod objective;
 pub mod optimizer;
+pub mod population;
+pub mod profile;
+pub mod sensitivity;
 pub mod simulator;
+pub mod ssa;
+mod stats;
 pub mod stepper;
 mod utils;
 
@@ -13,17 +27,29 @@ pub mod prelude {
   pub use crate::clock;
 
   // modeling
-  pub use crate::model::{OptModelTrait, SimModelTrait};
+  pub use crate::model::{DdeModelTrait, OptModelTrait, ReactionModelTrait, SimModelTrait};
   pub use core::str::FromStr;
   pub use rust_decimal::Decimal;
 
   // simulation
-  pub use crate::simulator::SimResult;
+  pub use crate::dosing::{Dose, DosingRegimen};
+  pub use crate::input::{Input, Interpolation};
   pub use crate::simulator::Simulator;
+  pub use crate::simulator::{History, SimResult};
+  pub use crate::ssa::{Ssa, SsaOptions, StochasticSimulator};
   pub use crate::stepper::{StepOptions, Stepper};
 
   // optimization
-  pub use crate::data::Data;
-  pub use crate::objective::Objective;
-  pub use crate::optimizer::{OptOptions, Optimizer};
+  pub use crate::covariate::{Covariate, Relation};
+  pub use crate::data::{Censor, CsvFormat, Data, Obs};
+  pub use crate::objective::{Loss, Objective, Prior, Transform};
+  pub use crate::optimizer::{OptOptions, OptResult, Optimizer};
+
+  // analysis
+  pub use crate::bootstrap::{Bootstrap, Resampling};
+  pub use crate::continuation::{BifurcationKind, Continuation, ContinuationMethod};
+  pub use crate::mcmc::{SampleOptions, Sampler};
+  pub use crate::population::{ErrorModel, Population, Saem};
+  pub use crate::profile::ProfileLikelihood;
+  pub use crate::sensitivity::{LocalSensitivity, Morris, Sobol};
 }
@@ -0,0 +1,456 @@
+// small dense linear algebra used by analyses.
+
+use ndarray::{Array1, Array2};
+
+// inverse of a square matrix.
+// None if a is (numerically) singular.
+pub fn inv(a: &Array2<f64>) -> Option<Array2<f64>> {
+  gauss_jordan(a, Array2::eye(a.nrows()))
+}
+
+// solution x of a x = b.
+// None if a is (numerically) singular.
+pub fn solve(a: &Array2<f64>, b: &Array1<f64>) -> Option<Array1<f64>> {
+  let b = b.clone().into_shape((b.len(), 1)).unwrap();
+  gauss_jordan(a, b).map(|x| x.column(0).to_owned())
+}
+
+fn gauss_jordan(a: &Array2<f64>, mut b: Array2<f64>) -> Option<Array2<f64>> {
+  let n = a.nrows();
+  let m = b.ncols();
+  let mut a = a.clone();
+  let scale = a.iter().fold(0.0f64, |acc, v| acc.max(v.abs()));
+
+  for col in 0..n {
+    // pivot
+    let mut pivot = col;
+    for row in col + 1..n {
+      if a[[row, col]].abs() > a[[pivot, col]].abs() {
+        pivot = row;
+      }
+    }
+    if a[[pivot, col]].abs() <= scale * 1e-14 || !a[[pivot, col]].is_finite() {
+      return None;
+    }
+    if pivot != col {
+      for j in 0..n {
+        a.swap([col, j], [pivot, j]);
+      }
+      for j in 0..m {
+        b.swap([col, j], [pivot, j]);
+      }
+    }
+
+    // eliminate
+    let diag = a[[col, col]];
+    for j in 0..n {
+      a[[col, j]] /= diag;
+    }
+    for j in 0..m {
+      b[[col, j]] /= diag;
+    }
+    for row in 0..n {
+      if row != col {
+        let factor = a[[row, col]];
+        if factor != 0.0 {
+          for j in 0..n {
+            a[[row, j]] -= factor * a[[col, j]];
+          }
+          for j in 0..m {
+            b[[row, j]] -= factor * b[[col, j]];
+          }
+        }
+      }
+    }
+  }
+  Some(b)
+}
+
+// LU factorization p a = l u with partial pivoting, which solves
+// a x = b for many b (e.g. Newton iterations) without inverting a.
+pub struct Lu {
+  // l below the diagonal (unit diagonal) and u on and above it
+  lu: Array2<f64>,
+  // row of a in each row of lu
+  perm: Vec<usize>,
+}
+
+// None if a is (numerically) singular.
+pub fn lu(a: &Array2<f64>) -> Option<Lu> {
+  let n = a.nrows();
+  let mut lu = a.clone();
+  let mut perm: Vec<usize> = (0..n).collect();
+  let scale = a.iter().fold(0.0f64, |acc, v| acc.max(v.abs()));
+
+  for col in 0..n {
+    // pivot
+    let mut pivot = col;
+    for row in col + 1..n {
+      if lu[[row, col]].abs() > lu[[pivot, col]].abs() {
+        pivot = row;
+      }
+    }
+    if lu[[pivot, col]].abs() <= scale * 1e-14 || !lu[[pivot, col]].is_finite() {
+      return None;
+    }
+    if pivot != col {
+      for j in 0..n {
+        lu.swap([col, j], [pivot, j]);
+      }
+      perm.swap(col, pivot);
+    }
+
+    // eliminate below the diagonal
+    for row in col + 1..n {
+      let factor = lu[[row, col]] / lu[[col, col]];
+      lu[[row, col]] = factor;
+      if factor != 0.0 {
+        for j in col + 1..n {
+          lu[[row, j]] -= factor * lu[[col, j]];
+        }
+      }
+    }
+  }
+  Some(Lu { lu, perm })
+}
+
+impl Lu {
+  // solution x of a x = b.
+  pub fn solve(&self, b: &Array1<f64>) -> Array1<f64> {
+    let n = self.perm.len();
+    let mut x: Array1<f64> = self.perm.iter().map(|&i| b[i]).collect();
+
+    // forward substitution with l
+    for i in 0..n {
+      for j in 0..i {
+        x[i] -= self.lu[[i, j]] * x[j];
+      }
+    }
+    // back substitution with u
+    for i in (0..n).rev() {
+      for j in i + 1..n {
+        x[i] -= self.lu[[i, j]] * x[j];
+      }
+      x[i] /= self.lu[[i, i]];
+    }
+    x
+  }
+}
+
+// lower triangular l of a = l l^T for a symmetric positive definite matrix.
+// None if a is not positive definite.
+pub fn cholesky(a: &Array2<f64>) -> Option<Array2<f64>> {
+  let n = a.nrows();
+  let mut l = Array2::zeros((n, n));
+  for i in 0..n {
+    for j in 0..=i {
+      let mut sum = a[[i, j]];
+      for k in 0..j {
+        sum -= l[[i, k]] * l[[j, k]];
+      }
+      if i == j {
+        if sum <= 0.0 || !sum.is_finite() {
+          return None;
+        }
+        l[[i, i]] = sum.sqrt();
+      } else {
+        l[[i, j]] = sum / l[[j, j]];
+      }
+    }
+  }
+  Some(l)
+}
+
+// eigenvalues of a symmetric matrix in ascending order (cyclic Jacobi method).
+pub fn sym_eigvals(a: &Array2<f64>) -> Vec<f64> {
+  let n = a.nrows();
+  let mut a = a.clone();
+
+  for _ in 0..100 {
+    let mut off = 0.0;
+    for i in 0..n {
+      for j in i + 1..n {
+        off += a[[i, j]].powi(2);
+      }
+    }
+    if off <= 1e-30 {
+      break;
+    }
+
+    for p in 0..n {
+      for q in p + 1..n {
+        if a[[p, q]].abs() <= 1e-300 {
+          continue;
+        }
+        let theta = (a[[q, q]] - a[[p, p]]) / (2.0 * a[[p, q]]);
+        let t = theta.signum() / (theta.abs() + (theta.powi(2) + 1.0).sqrt());
+        let c = 1.0 / (t.powi(2) + 1.0).sqrt();
+        let s = t * c;
+
+        for k in 0..n {
+          let akp = a[[k, p]];
+          let akq = a[[k, q]];
+          a[[k, p]] = c * akp - s * akq;
+          a[[k, q]] = s * akp + c * akq;
+        }
+        for k in 0..n {
+          let apk = a[[p, k]];
+          let aqk = a[[q, k]];
+          a[[p, k]] = c * apk - s * aqk;
+          a[[q, k]] = s * apk + c * aqk;
+        }
+      }
+    }
+  }
+
+  let mut eigvals: Vec<f64> = (0..n).map(|i| a[[i, i]]).collect();
+  eigvals.sort_by(|a, b| a.partial_cmp(b).unwrap());
+  eigvals
+}
+
+// eigenvalues (re, im) of a general real matrix by the Householder reduction
+// to Hessenberg form and Francis double-shift QR steps (Golub & Van Loan 7.5).
+// complex eigenvalues are in conjugate pairs, and real ones have im = 0.
+// None if the iterations do not converge.
+pub fn eigvals(a: &Array2<f64>) -> Option<Vec<(f64, f64)>> {
+  let n = a.nrows();
+  let mut h = hessenberg(a);
+  let scale = h.iter().fold(0.0f64, |acc, v| acc.max(v.abs()));
+  let mut eigvals = Vec::with_capacity(n);
+
+  // the leading block h[..end, ..end] is not yet reduced,
+  // and h[start..end, start..end] is its unreduced trailing block.
+  let mut end = n;
+  let mut n_iter = 0;
+  while end > 0 {
+    let mut start = end - 1;
+    while start > 0 {
+      let diag = h[[start - 1, start - 1]].abs() + h[[start, start]].abs();
+      let tiny = f64::EPSILON * if diag == 0.0 { scale } else { diag };
+      if h[[start, start - 1]].abs() <= tiny {
+        h[[start, start - 1]] = 0.0;
+        break;
+      }
+      start -= 1;
+    }
+
+    match end - start {
+      1 => {
+        eigvals.push((h[[end - 1, end - 1]], 0.0));
+        end -= 1;
+        n_iter = 0;
+      }
+      2 => {
+        let k = end - 2;
+        let (l1, l2) = eigvals2(h[[k, k]], h[[k, k + 1]], h[[k + 1, k]], h[[k + 1, k + 1]]);
+        // in reverse order as the others
+        eigvals.push(l2);
+        eigvals.push(l1);
+        end -= 2;
+        n_iter = 0;
+      }
+      _ => {
+        if n_iter == MAX_QR_STEPS {
+          return None;
+        }
+        n_iter += 1;
+        // an ad hoc shift breaks cycles of the standard shifts
+        francis_step(&mut h, start, end, n_iter % 10 == 0);
+      }
+    }
+  }
+
+  eigvals.reverse();
+  Some(eigvals)
+}
+
+// QR steps per eigenvalue before giving up.
+const MAX_QR_STEPS: usize = 100;
+
+// eigenvalues of [[a, b], [c, d]].
+fn eigvals2(a: f64, b: f64, c: f64, d: f64) -> ((f64, f64), (f64, f64)) {
+  let mean = 0.5 * (a + d);
+  let disc = (0.5 * (a - d)).powi(2) + b * c;
+  if disc >= 0.0 {
+    // the larger root first, and the other from the determinant
+    // to avoid cancellation.
+    let l1 = mean + disc.sqrt().copysign(mean);
+    let l2 = if l1 != 0.0 {
+      (a * d - b * c) / l1
+    } else {
+      mean - disc.sqrt()
+    };
+    ((l1, 0.0), (l2, 0.0))
+  } else {
+    let im = (-disc).sqrt();
+    ((mean, im), (mean, -im))
+  }
+}
+
+// a Francis double-shift QR step on h[start..end, start..end] (3 rows or more),
+// where the shifts are the eigenvalues of the trailing 2x2 block.
+// the bulge made by the first reflector is chased down the subdiagonal.
+fn francis_step(h: &mut Array2<f64>, start: usize, end: usize, ad_hoc: bool) {
+  let last = end - 1;
+  // sum and product of the shifts
+  let (sum, prod) = if ad_hoc {
+    let w = h[[last, last - 1]].abs() + h[[last - 1, last - 2]].abs();
+    (1.5 * w, w * w)
+  } else {
+    let (a, b) = (h[[last - 1, last - 1]], h[[last - 1, last]]);
+    let (c, d) = (h[[last, last - 1]], h[[last, last]]);
+    (a + d, a * d - b * c)
+  };
+
+  // the first column of (h - s1)(h - s2)
+  let (h00, h01) = (h[[start, start]], h[[start, start + 1]]);
+  let (h10, h11) = (h[[start + 1, start]], h[[start + 1, start + 1]]);
+  let mut x = [
+    h00 * h00 + h01 * h10 - sum * h00 + prod,
+    h10 * (h00 + h11 - sum),
+    h10 * h[[start + 2, start + 1]],
+  ];
+
+  for k in start..end - 2 {
+    let col = if k == start { start } else { k - 1 };
+    reflect(h, &x, k, col, start, end);
+    if k > start {
+      // entries under the subdiagonal are zero by construction
+      h[[k + 1, k - 1]] = 0.0;
+      h[[k + 2, k - 1]] = 0.0;
+    }
+    x[0] = h[[k + 1, k]];
+    x[1] = h[[k + 2, k]];
+    if k + 3 < end {
+      x[2] = h[[k + 3, k]];
+    }
+  }
+  let k = end - 2;
+  reflect(h, &x[..2], k, k - 1, start, end);
+  h[[k + 1, k - 1]] = 0.0;
+}
+
+// h <- P h P with the Householder reflector P acting on rows (columns)
+// k..k + x.len() that maps x onto the first axis.
+// only h[start..end, col..end] and h[start..end, k..] are updated,
+// which is sufficient for the eigenvalues of the block.
+fn reflect(h: &mut Array2<f64>, x: &[f64], k: usize, col: usize, start: usize, end: usize) {
+  let (v, beta) = match householder(x) {
+    Some(reflector) => reflector,
+    None => return,
+  };
+  let m = v.len();
+
+  for j in col..end {
+    let s: f64 = (0..m).map(|i| v[i] * h[[k + i, j]]).sum();
+    for i in 0..m {
+      h[[k + i, j]] -= beta * s * v[i];
+    }
+  }
+  for i in start..end.min(k + m + 1) {
+    let s: f64 = (0..m).map(|j| v[j] * h[[i, k + j]]).sum();
+    for j in 0..m {
+      h[[i, k + j]] -= beta * s * v[j];
+    }
+  }
+}
+
+// Householder vector v and beta with (I - beta v v^T) x = alpha e_1.
+// None if x is zero.
+fn householder(x: &[f64]) -> Option<(Vec<f64>, f64)> {
+  let norm = x.iter().map(|v| v * v).sum::<f64>().sqrt();
+  if norm == 0.0 {
+    return None;
+  }
+  let mut v = x.to_vec();
+  // alpha = -sign(x_0) |x| avoids cancellation in v_0 = x_0 - alpha
+  v[0] += norm.copysign(x[0]);
+  let beta = 2.0 / v.iter().map(|v| v * v).sum::<f64>();
+  Some((v, beta))
+}
+
+// upper Hessenberg matrix similar to a (Householder reflectors).
+fn hessenberg(a: &Array2<f64>) -> Array2<f64> {
+  let n = a.nrows();
+  let mut h = a.clone();
+
+  for k in 0..n.saturating_sub(2) {
+    let x: Vec<f64> = (k + 1..n).map(|i| h[[i, k]]).collect();
+    reflect(&mut h, &x, k + 1, k, 0, n);
+    for i in k + 2..n {
+      h[[i, k]] = 0.0;
+    }
+  }
+  h
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ndarray::arr2;
+
+  #[test]
+  fn lu_solves_with_pivoting() {
+    // a zero leading entry needs a row exchange
+    let a = arr2(&[[0.0, 2.0, 1.0], [1.0, 1.0, 0.0], [3.0, 0.0, 4.0]]);
+    let x = Array1::from(vec![1.0, -2.0, 0.5]);
+    let b = a.dot(&x);
+
+    let solved = lu(&a).unwrap().solve(&b);
+    for i in 0..3 {
+      assert!((solved[i] - x[i]).abs() < 1e-12, "{}", solved);
+    }
+    assert!(lu(&arr2(&[[1.0, 2.0], [2.0, 4.0]])).is_none());
+  }
+
+  // eigenvalues sorted by (re, im) and compared with the expected ones.
+  fn assert_eigvals(a: &Array2<f64>, expected: &[(f64, f64)]) {
+    let mut eigvals = eigvals(a).unwrap();
+    eigvals.sort_by(|x, y| x.0.total_cmp(&y.0).then(x.1.total_cmp(&y.1)));
+    for (l, e) in eigvals.iter().zip(expected.iter()) {
+      assert!(
+        (l.0 - e.0).abs() < 1e-8 && (l.1 - e.1).abs() < 1e-8,
+        "{:?} != {:?}",
+        eigvals,
+        expected
+      );
+    }
+  }
+
+  #[test]
+  fn eigvals_of_companion_matrix() {
+    // (x - 1)(x - 2)(x - 3)(x^2 + 1) = x^5 - 6 x^4 + 12 x^3 - 12 x^2 + 11 x - 6
+    let mut a = Array2::zeros((5, 5));
+    for (j, c) in [6.0, -12.0, 12.0, -11.0, 6.0].iter().enumerate() {
+      a[[0, j]] = *c;
+    }
+    for i in 1..5 {
+      a[[i, i - 1]] = 1.0;
+    }
+    assert_eigvals(
+      &a,
+      &[(0.0, -1.0), (0.0, 1.0), (1.0, 0.0), (2.0, 0.0), (3.0, 0.0)],
+    );
+  }
+
+  #[test]
+  fn eigvals_of_cyclic_permutation() {
+    // the 4th roots of unity, where the unshifted QR iterations stall
+    let a = arr2(&[
+      [0.0, 0.0, 0.0, 1.0],
+      [1.0, 0.0, 0.0, 0.0],
+      [0.0, 1.0, 0.0, 0.0],
+      [0.0, 0.0, 1.0, 0.0],
+    ]);
+    assert_eigvals(&a, &[(-1.0, 0.0), (0.0, -1.0), (0.0, 1.0), (1.0, 0.0)]);
+  }
+
+  #[test]
+  fn sym_eigvals_in_ascending_order() {
+    let a = arr2(&[[2.0, -1.0, 0.0], [-1.0, 2.0, -1.0], [0.0, -1.0, 2.0]]);
+    let expected = [2.0 - 2f64.sqrt(), 2.0, 2.0 + 2f64.sqrt()];
+    for (l, e) in sym_eigvals(&a).iter().zip(expected.iter()) {
+      assert!((l - e).abs() < 1e-10, "{} != {}", l, e);
+    }
+  }
+}
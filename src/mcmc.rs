@@ -0,0 +1,9 @@
+mod base;
+mod ensemble;
+mod metropolis;
+mod result;
+
+pub use crate::mcmc::base::{SampleOptions, Sampler};
+pub use crate::mcmc::ensemble::EnsembleSampler;
+pub use crate::mcmc::metropolis::AdaptiveMetropolis;
+pub use crate::mcmc::result::McmcResult;
@@ -0,0 +1,130 @@
+use super::ensemble::EnsembleSampler;
+use super::metropolis::AdaptiveMetropolis;
+use super::result::McmcResult;
+
+use crate::model::OptModelTrait;
+use crate::objective::Objective;
+use crate::stats;
+
+use ndarray::Array1;
+use rand::rngs::StdRng;
+
+// MCMC samplers of x.
+// the target density is exp(-obj / 2) in the transformed space of the objective
+// (with the Jacobian of the transforms), which is the posterior
+// when the objective is -2 ln L (Loss::Gaussian) plus priors.
+// chains start around the parameter values in a model (e.g. the optimum).
+// n_iter includes burn_in, and every thin-th sample after burn_in is kept.
+// chains are reproducible if seed is given.
+#[derive(Clone)]
+pub enum SampleOptions {
+  Default,
+
+  AdaptiveMetropolis {
+    n_iter: usize,
+    n_chains: usize,
+    burn_in: usize,
+    thin: usize,
+    seed: Option<u64>,
+    verbose: bool,
+  },
+
+  // n_walkers = 0 uses max(2 * len_x + 2, 8) walkers.
+  Ensemble {
+    n_iter: usize,
+    n_walkers: usize,
+    burn_in: usize,
+    thin: usize,
+    stretch: f64,
+    seed: Option<u64>,
+    verbose: bool,
+  },
+}
+
+#[derive(Clone)]
+pub enum Sampler {
+  AdaptiveMetropolis(SampleOptions),
+  Ensemble(SampleOptions),
+}
+
+impl Sampler {
+  pub fn run<M, const LEN_Y: usize, const LEN_P: usize, const LEN_B: usize, const LEN_X: usize>(
+    &self,
+    objective: &mut Objective<M, LEN_Y, LEN_P, LEN_B, LEN_X>,
+  ) -> McmcResult
+  where
+    M: OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X>,
+  {
+    match self {
+      Sampler::AdaptiveMetropolis(options) => {
+        let sampler = AdaptiveMetropolis::new(objective.len_x, options);
+        sampler.run(objective)
+      }
+
+      Sampler::Ensemble(options) => {
+        let sampler = EnsembleSampler::new(objective.len_x, options);
+        sampler.run(objective)
+      }
+    }
+  }
+}
+
+pub trait ConcreteSampler {
+  fn new(len_x: usize, options: &SampleOptions) -> Self;
+
+  fn run<M, const LEN_Y: usize, const LEN_P: usize, const LEN_B: usize, const LEN_X: usize>(
+    &self,
+    objective: &mut Objective<M, LEN_Y, LEN_P, LEN_B, LEN_X>,
+  ) -> McmcResult
+  where
+    M: OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X>;
+}
+
+// every sampler keeps at least one sample of each chain.
+pub fn check_iterations(n_iter: usize, burn_in: usize, thin: usize) {
+  if thin == 0 {
+    panic!("thin must be 1 or more.");
+  }
+  if burn_in >= n_iter {
+    panic!("n_iter must be greater than burn_in.");
+  }
+}
+
+// log of the target density at z.
+pub fn log_post<M, const LEN_Y: usize, const LEN_P: usize, const LEN_B: usize, const LEN_X: usize>(
+  objective: &mut Objective<M, LEN_Y, LEN_P, LEN_B, LEN_X>,
+  z: &Array1<f64>,
+) -> f64
+where
+  M: OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X>,
+{
+  let f = objective.objz(z);
+  if f.is_nan() {
+    f64::NEG_INFINITY
+  } else {
+    -0.5 * f + objective.log_jacobian(z)
+  }
+}
+
+// z of the parameter values in a model.
+pub fn initial_z<
+  M,
+  const LEN_Y: usize,
+  const LEN_P: usize,
+  const LEN_B: usize,
+  const LEN_X: usize,
+>(
+  objective: &Objective<M, LEN_Y, LEN_P, LEN_B, LEN_X>,
+) -> Array1<f64>
+where
+  M: OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X>,
+{
+  let p = objective.simulator.model.getp();
+  let x: Array1<f64> = objective.x_index.iter().map(|&i| p[i]).collect();
+  objective.to_z(&x)
+}
+
+// z scattered around z0 by about 1% to start chains or walkers.
+pub fn scatter(z0: &Array1<f64>, rng: &mut StdRng) -> Array1<f64> {
+  z0.mapv(|z| z + 1e-2 * z.abs().max(1e-2) * stats::randn(rng))
+}
@@ -0,0 +1,241 @@
+use super::base::{check_iterations, initial_z, log_post, scatter, ConcreteSampler, SampleOptions};
+use super::result::McmcResult;
+
+use crate::model::OptModelTrait;
+use crate::objective::Objective;
+use crate::stats;
+
+use ndarray::Array1;
+use rand::Rng;
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+
+// Affine-invariant ensemble sampler with the stretch move (Goodman & Weare, 2010).
+// walkers are updated in two halves, and proposals in a half are evaluated
+// in parallel. each walker is reported as a chain.
+pub struct EnsembleSampler {
+  n_iter: usize,
+  n_walkers: usize,
+  burn_in: usize,
+  thin: usize,
+  stretch: f64,
+  seed: Option<u64>,
+  len_x: usize,
+  verbose: bool,
+}
+
+impl ConcreteSampler for EnsembleSampler {
+  fn new(len_x: usize, options: &SampleOptions) -> Self {
+    let (n_iter, n_walkers, burn_in, thin, stretch, seed, verbose) = match options {
+      SampleOptions::Default => (2000, 0, 1000, 1, 2.0, None, false),
+
+      SampleOptions::Ensemble {
+        n_iter,
+        n_walkers,
+        burn_in,
+        thin,
+        stretch,
+        seed,
+        verbose,
+      } => (
+        *n_iter, *n_walkers, *burn_in, *thin, *stretch, *seed, *verbose,
+      ),
+
+      _ => panic!("Invalid SampleOptions variant."),
+    };
+    check_iterations(n_iter, burn_in, thin);
+    // the density of stretches g(s) ~ 1/sqrt(s) on [1/a, a]
+    if stretch.is_nan() || stretch <= 1.0 {
+      panic!("stretch must be greater than 1 (got {}).", stretch);
+    }
+
+    // even number of walkers to split into two halves
+    let n_walkers = if n_walkers == 0 {
+      (2 * len_x + 2).max(8)
+    } else {
+      n_walkers + n_walkers % 2
+    };
+
+    Self {
+      n_iter,
+      n_walkers,
+      burn_in,
+      thin,
+      stretch,
+      seed,
+      len_x,
+      verbose,
+    }
+  }
+
+  fn run<M, const LEN_Y: usize, const LEN_P: usize, const LEN_B: usize, const LEN_X: usize>(
+    &self,
+    objective: &mut Objective<M, LEN_Y, LEN_P, LEN_B, LEN_X>,
+  ) -> McmcResult
+  where
+    M: OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X>,
+  {
+    let mut rng = stats::make_rng(self.seed, 0);
+    let a = self.stretch;
+    let half = self.n_walkers / 2;
+
+    // initial walkers
+    let z0 = initial_z(objective);
+    let mut walkers: Vec<Array1<f64>> = (0..self.n_walkers)
+      .map(|_| scatter(&z0, &mut rng))
+      .collect();
+    let pool = LogPostPool::new(objective);
+    let mut next_store = self.burn_in;
+    let mut lps = pool.eval(&walkers);
+
+    let mut chains = vec![Vec::new(); self.n_walkers];
+    let mut logposts = vec![Vec::new(); self.n_walkers];
+    let mut n_accept = vec![0; self.n_walkers];
+
+    for n_iter in 0..self.n_iter {
+      for (active, complement) in [(0, half), (half, 0)] {
+        // stretch move of the active half using the complementary half
+        let mut stretches = Vec::new();
+        let mut proposals = Vec::new();
+        for k in active..active + half {
+          let j = complement + rng.gen_range(0..half);
+          let stretch = ((a - 1.0) * rng.gen_range(0.0..1.0) + 1.0).powi(2) / a;
+          proposals.push(&walkers[j] + &(stretch * (&walkers[k] - &walkers[j])));
+          stretches.push(stretch);
+        }
+        let new_lps = pool.eval(&proposals);
+
+        for (i, proposal) in proposals.into_iter().enumerate() {
+          let k = active + i;
+          let log_ratio = (self.len_x as f64 - 1.0) * stretches[i].ln() + new_lps[i] - lps[k];
+          if rng.gen_range(0.0f64..1.0).ln() < log_ratio {
+            walkers[k] = proposal;
+            lps[k] = new_lps[i];
+            n_accept[k] += 1;
+          }
+        }
+      }
+
+      // store
+      if n_iter == next_store {
+        next_store += self.thin;
+        for k in 0..self.n_walkers {
+          chains[k].push(objective.to_x(&walkers[k]));
+          logposts[k].push(lps[k]);
+        }
+      }
+
+      if self.verbose && (n_iter + 1) % (self.n_iter / 10).max(1) == 0 {
+        let lp_best = lps.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        println!("{:7}   lp(best):{:.4e}", n_iter + 1, lp_best);
+      }
+    }
+
+    let acceptance = n_accept
+      .iter()
+      .map(|&n| n as f64 / self.n_iter as f64)
+      .collect();
+
+    McmcResult::new(objective.x_index.clone(), chains, logposts, acceptance)
+  }
+}
+
+// fixed pool of threads evaluating the log target density,
+// each of which has its own copy of the objective.
+// a panic in an evaluation is sent back and resumed in eval,
+// so that a panicking model does not leave eval waiting for its result.
+// the threads are joined when the pool is dropped.
+struct LogPostPool {
+  jobs: Option<mpsc::Sender<(usize, Array1<f64>)>>,
+  results: mpsc::Receiver<(usize, EvalResult)>,
+  handles: Vec<thread::JoinHandle<()>>,
+}
+
+// log target density or the payload of a panic
+type EvalResult = Result<f64, Box<dyn Any + Send>>;
+
+impl LogPostPool {
+  fn new<M, const LEN_Y: usize, const LEN_P: usize, const LEN_B: usize, const LEN_X: usize>(
+    objective: &Objective<M, LEN_Y, LEN_P, LEN_B, LEN_X>,
+  ) -> Self
+  where
+    M: OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X>,
+  {
+    let n_threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let (jobs, job_receiver) = mpsc::channel::<(usize, Array1<f64>)>();
+    let (result_sender, results) = mpsc::channel();
+    let job_receiver = Arc::new(Mutex::new(job_receiver));
+
+    // vector for join-handles
+    let mut handles = Vec::new();
+
+    for _ in 0..n_threads {
+      let mut thread_objective = objective.clone();
+      let thread_jobs = Arc::clone(&job_receiver);
+      let thread_results = result_sender.clone();
+
+      // ===== FORK =====
+      let handle = thread::spawn(move || loop {
+        // the lock is released before the evaluation
+        let job = thread_jobs.lock().unwrap().recv();
+        match job {
+          Ok((k, z)) => {
+            let lp = panic::catch_unwind(AssertUnwindSafe(|| log_post(&mut thread_objective, &z)));
+            // the receiver is gone only while the pool is dropped
+            if thread_results.send((k, lp)).is_err() {
+              break;
+            }
+          }
+          // the pool is dropped
+          Err(_) => break,
+        }
+      });
+      // ================
+
+      handles.push(handle);
+    }
+
+    Self {
+      jobs: Some(jobs),
+      results,
+      handles,
+    }
+  }
+
+  // log target densities at zs in the same order.
+  fn eval(&self, zs: &[Array1<f64>]) -> Vec<f64> {
+    let jobs = self.jobs.as_ref().unwrap();
+    for (k, z) in zs.iter().enumerate() {
+      jobs.send((k, z.clone())).unwrap();
+    }
+
+    // all of the results are received before a panic is resumed,
+    // so that no result of this call is left for the next one
+    let mut lps = vec![f64::NAN; zs.len()];
+    let mut payload = None;
+    for _ in 0..zs.len() {
+      match self.results.recv().unwrap() {
+        (k, Ok(lp)) => lps[k] = lp,
+        (_, Err(err)) => payload = payload.or(Some(err)),
+      }
+    }
+    if let Some(payload) = payload {
+      panic::resume_unwind(payload);
+    }
+    lps
+  }
+}
+
+impl Drop for LogPostPool {
+  fn drop(&mut self) {
+    // ===== JOIN =====
+    // closing the channel stops the threads
+    self.jobs = None;
+    for handle in self.handles.drain(..) {
+      handle.join().unwrap();
+    }
+    // ================
+  }
+}
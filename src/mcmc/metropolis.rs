@@ -0,0 +1,167 @@
+use super::base::{check_iterations, initial_z, log_post, scatter, ConcreteSampler, SampleOptions};
+use super::result::McmcResult;
+
+use crate::linalg;
+use crate::model::OptModelTrait;
+use crate::objective::Objective;
+use crate::stats;
+
+use ndarray::{Array1, Array2};
+use rand::Rng;
+use std::thread;
+
+// Adaptive Metropolis (Haario et al., 2001).
+// the Gaussian proposal is adapted to the covariance of the chain during burn-in,
+// and fixed afterwards. chains run in parallel.
+pub struct AdaptiveMetropolis {
+  n_iter: usize,
+  n_chains: usize,
+  burn_in: usize,
+  thin: usize,
+  seed: Option<u64>,
+  len_x: usize,
+  verbose: bool,
+}
+
+impl ConcreteSampler for AdaptiveMetropolis {
+  fn new(len_x: usize, options: &SampleOptions) -> Self {
+    let (n_iter, n_chains, burn_in, thin, seed, verbose) = match options {
+      SampleOptions::Default => (10000, 4, 5000, 1, None, false),
+
+      SampleOptions::AdaptiveMetropolis {
+        n_iter,
+        n_chains,
+        burn_in,
+        thin,
+        seed,
+        verbose,
+      } => (*n_iter, *n_chains, *burn_in, *thin, *seed, *verbose),
+
+      _ => panic!("Invalid SampleOptions variant."),
+    };
+    check_iterations(n_iter, burn_in, thin);
+
+    Self {
+      n_iter,
+      n_chains,
+      burn_in,
+      thin,
+      seed,
+      len_x,
+      verbose,
+    }
+  }
+
+  fn run<M, const LEN_Y: usize, const LEN_P: usize, const LEN_B: usize, const LEN_X: usize>(
+    &self,
+    objective: &mut Objective<M, LEN_Y, LEN_P, LEN_B, LEN_X>,
+  ) -> McmcResult
+  where
+    M: OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X>,
+  {
+    let z0 = initial_z(objective);
+
+    // vector for join-handles
+    let mut handles = Vec::new();
+
+    for n_chain in 0..self.n_chains {
+      let mut thread_objective = objective.clone();
+      let thread_z0 = z0.clone();
+      let (n_iter, burn_in, thin, len_x, verbose) = (
+        self.n_iter,
+        self.burn_in,
+        self.thin,
+        self.len_x,
+        self.verbose,
+      );
+      let mut rng = stats::make_rng(self.seed, n_chain as u64);
+
+      // ===== FORK =====
+      let handle = thread::spawn(move || {
+        let mut z = scatter(&thread_z0, &mut rng);
+        let mut lp = log_post(&mut thread_objective, &z);
+
+        // initial proposal: about 1% of each parameter
+        let mut chol = Array2::from_diag(&z.mapv(|v| 1e-2 * v.abs().max(1e-2)));
+        let scale = 2.38f64.powi(2) / len_x as f64;
+
+        // running mean and covariance (Welford)
+        let mut mean = Array1::<f64>::zeros(len_x);
+        let mut m2 = Array2::<f64>::zeros((len_x, len_x));
+
+        let mut chain = Vec::new();
+        let mut logpost = Vec::new();
+        let mut n_accept = 0;
+
+        for n_iter_ in 0..n_iter {
+          // propose
+          let noise: Array1<f64> = (0..len_x).map(|_| stats::randn(&mut rng)).collect();
+          let new_z = &z + &chol.dot(&noise);
+          let new_lp = log_post(&mut thread_objective, &new_z);
+
+          // accept or reject
+          if rng.gen_range(0.0f64..1.0).ln() < new_lp - lp {
+            z = new_z;
+            lp = new_lp;
+            n_accept += 1;
+          }
+
+          // adapt the proposal during burn-in
+          let n = (n_iter_ + 1) as f64;
+          let delta = &z - &mean;
+          mean = &mean + &(&delta / n);
+          let delta2 = &z - &mean;
+          for i in 0..len_x {
+            for j in 0..len_x {
+              m2[[i, j]] += delta[i] * delta2[j];
+            }
+          }
+          if n_iter_ < burn_in && n_iter_ >= 2 * len_x && n_iter_ % 10 == 0 {
+            let mut cov = &m2 / (n - 1.0) * scale;
+            for i in 0..len_x {
+              cov[[i, i]] += 1e-10;
+            }
+            if let Some(new_chol) = linalg::cholesky(&cov) {
+              chol = new_chol;
+            }
+          }
+
+          // store
+          if n_iter_ >= burn_in && (n_iter_ - burn_in) % thin == 0 {
+            chain.push(thread_objective.to_x(&z));
+            logpost.push(lp);
+          }
+
+          if verbose && (n_iter_ + 1) % (n_iter / 10).max(1) == 0 {
+            println!(
+              "chain {}: {:7}   lp:{:.4e}   acc:{:.3}",
+              n_chain,
+              n_iter_ + 1,
+              lp,
+              n_accept as f64 / n
+            );
+          }
+        }
+
+        (chain, logpost, n_accept as f64 / n_iter as f64)
+      });
+      // ================
+
+      handles.push(handle);
+    }
+
+    // ===== JOIN =====
+    let mut chains = Vec::new();
+    let mut logposts = Vec::new();
+    let mut acceptance = Vec::new();
+    for handle in handles {
+      let (chain, logpost, rate) = handle.join().unwrap();
+      chains.push(chain);
+      logposts.push(logpost);
+      acceptance.push(rate);
+    }
+    // ================
+
+    McmcResult::new(objective.x_index.clone(), chains, logposts, acceptance)
+  }
+}
@@ -0,0 +1,195 @@
+use std::path::Path;
+
+use crate::simulator::write_file;
+use crate::stats;
+
+use ndarray::Array1;
+
+// samples of x after burn-in and thinning.
+// chains[c][i] is the i-th sample of chain c (a walker for the ensemble sampler).
+// rhat is the split R-hat and ess the effective sample size over all chains
+// for each parameter.
+pub struct McmcResult {
+  pub index: Vec<usize>,
+  pub chains: Vec<Vec<Array1<f64>>>,
+  pub logpost: Vec<Vec<f64>>,
+  pub acceptance: Vec<f64>,
+  pub rhat: Vec<f64>,
+  pub ess: Vec<f64>,
+}
+
+impl McmcResult {
+  pub fn new(
+    index: Vec<usize>,
+    chains: Vec<Vec<Array1<f64>>>,
+    logpost: Vec<Vec<f64>>,
+    acceptance: Vec<f64>,
+  ) -> Self {
+    let mut rhat = Vec::new();
+    let mut ess = Vec::new();
+    for k in 0..index.len() {
+      let traces: Vec<Vec<f64>> = chains
+        .iter()
+        .map(|chain| chain.iter().map(|x| x[k]).collect())
+        .collect();
+      rhat.push(split_rhat(&traces));
+      ess.push(effective_size(&traces));
+    }
+
+    Self {
+      index,
+      chains,
+      logpost,
+      acceptance,
+      rhat,
+      ess,
+    }
+  }
+
+  // samples of all chains
+  pub fn samples(&self) -> Vec<Array1<f64>> {
+    self.chains.iter().flatten().cloned().collect()
+  }
+
+  // samples of the k-th parameter in ascending order
+  fn sorted(&self, k: usize) -> Vec<f64> {
+    let mut values: Vec<f64> = self.chains.iter().flatten().map(|x| x[k]).collect();
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    values
+  }
+
+  pub fn mean(&self) -> Vec<f64> {
+    (0..self.index.len())
+      .map(|k| stats::mean(&self.sorted(k)))
+      .collect()
+  }
+
+  pub fn sd(&self) -> Vec<f64> {
+    (0..self.index.len())
+      .map(|k| stats::sd(&self.sorted(k)))
+      .collect()
+  }
+
+  pub fn quantile(&self, q: f64) -> Vec<f64> {
+    (0..self.index.len())
+      .map(|k| stats::quantile(&self.sorted(k), q))
+      .collect()
+  }
+
+  pub fn save(&self, dir: &str) {
+    let save_dir = Path::new(dir);
+
+    // samples: chain, iteration, log posterior, x
+    let mut str_result = String::new();
+    for (c, (chain, logpost)) in self.chains.iter().zip(self.logpost.iter()).enumerate() {
+      for (i, (x, lp)) in chain.iter().zip(logpost.iter()).enumerate() {
+        str_result.push_str(&format!("{},{},{}", c, i, lp));
+        for v in x.iter() {
+          str_result.push(',');
+          str_result.push_str(&v.to_string());
+        }
+        str_result.push('\n');
+      }
+    }
+    write_file(&save_dir.join("mcmc.csv"), &str_result);
+
+    // summary: index, mean, sd, 2.5%, 50%, 97.5%, R-hat, ESS
+    let mut str_result = String::new();
+    for (k, index) in self.index.iter().enumerate() {
+      let values = self.sorted(k);
+      str_result.push_str(&format!(
+        "{},{},{},{},{},{},{},{}\n",
+        index,
+        stats::mean(&values),
+        stats::sd(&values),
+        stats::quantile(&values, 0.025),
+        stats::quantile(&values, 0.5),
+        stats::quantile(&values, 0.975),
+        self.rhat[k],
+        self.ess[k]
+      ));
+    }
+    write_file(&save_dir.join("mcmc_summary.csv"), &str_result);
+  }
+}
+
+// (mean, variance) of each chain
+fn chain_moments(traces: &[Vec<f64>]) -> Vec<(f64, f64)> {
+  traces
+    .iter()
+    .map(|trace| {
+      let m = stats::mean(trace);
+      (m, stats::sd(trace).powi(2))
+    })
+    .collect()
+}
+
+// (within-chain variance, pooled variance estimate)
+fn variances(traces: &[Vec<f64>]) -> (f64, f64) {
+  let n = traces[0].len() as f64;
+  let moments = chain_moments(traces);
+  let means: Vec<f64> = moments.iter().map(|m| m.0).collect();
+  let w = moments.iter().map(|m| m.1).sum::<f64>() / moments.len() as f64;
+  let b = if means.len() > 1 {
+    n * stats::sd(&means).powi(2)
+  } else {
+    0.0
+  };
+  (w, (n - 1.0) / n * w + b / n)
+}
+
+// potential scale reduction factor with chains split in halves (Gelman et al.).
+fn split_rhat(traces: &[Vec<f64>]) -> f64 {
+  let half = traces.first().map_or(0, |trace| trace.len() / 2);
+  if half < 2 {
+    return f64::NAN;
+  }
+  let mut halves = Vec::new();
+  for trace in traces.iter() {
+    halves.push(trace[..half].to_vec());
+    halves.push(trace[trace.len() - half..].to_vec());
+  }
+  let (w, var) = variances(&halves);
+  (var / w).sqrt()
+}
+
+// effective sample size from autocorrelations combined over chains,
+// truncated by Geyer's initial positive sequence.
+fn effective_size(traces: &[Vec<f64>]) -> f64 {
+  let m = traces.len();
+  let n = traces.first().map_or(0, |trace| trace.len());
+  if n < 4 {
+    return f64::NAN;
+  }
+  let (w, var) = variances(traces);
+  if var <= 0.0 {
+    return f64::NAN;
+  }
+  let means: Vec<f64> = traces.iter().map(|trace| stats::mean(trace)).collect();
+
+  // autocorrelation at lag t
+  let rho = |t: usize| {
+    let mut acov = 0.0;
+    for (trace, mean) in traces.iter().zip(means.iter()) {
+      let mut sum = 0.0;
+      for i in 0..n - t {
+        sum += (trace[i] - mean) * (trace[i + t] - mean);
+      }
+      acov += sum / n as f64;
+    }
+    acov /= m as f64;
+    1.0 - (w - acov) / var
+  };
+
+  let mut tau = -1.0;
+  let mut t = 0;
+  while t + 1 < n {
+    let pair = rho(t) + rho(t + 1);
+    if pair <= 0.0 {
+      break;
+    }
+    tau += 2.0 * pair;
+    t += 2;
+  }
+  (m * n) as f64 / tau
+}
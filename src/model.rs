@@ -1,9 +1,14 @@
+use crate::covariate::Covariate;
+use crate::objective::Transform;
+use crate::simulator::History;
+
 use rust_decimal::Decimal;
 
 pub trait SimModelTrait<const LEN_Y: usize, const LEN_P: usize, const LEN_B: usize> {
   fn new() -> Self;
   fn init(&self) -> (f64, [f64; LEN_Y]);
   fn ode(&self, t: &f64, y: &[f64; LEN_Y], deriv_y: &mut [f64; LEN_Y]);
+
   fn rec(&self, t: &f64, y: &[f64; LEN_Y], delta_y: &mut [f64; LEN_Y], act: &[bool; LEN_B]);
   fn cond(
     &self,
@@ -14,6 +19,41 @@ pub trait SimModelTrait<const LEN_Y: usize, const LEN_P: usize, const LEN_B: usi
   );
   fn beat(&self, t: &f64, y: &[f64; LEN_Y]) -> [[Decimal; 3]; LEN_B];
   fn cre(&self, t: &f64, y: &mut [f64; LEN_Y]);
+
+  // ode_input(&self, t, y, u, deriv_y) {}
+  // ODE with values u of exogenous inputs (Simulator::inputs) at t,
+  // which is called instead of ode when inputs are given. ode by default.
+  fn ode_input(&self, t: &f64, y: &[f64; LEN_Y], _u: &[f64], deriv_y: &mut [f64; LEN_Y]) {
+    self.ode(t, y, deriv_y);
+  }
+
+  // diff(&self, t, y, diff_y) {}
+  // diffusion of the SDE dy = ode dt + diff dW with independent Wiener processes,
+  // which is used by Stepper::EulerMaruyama and Stepper::Milstein. No noise by default.
+  fn diff(&self, _t: &f64, _y: &[f64; LEN_Y], _diff_y: &mut [f64; LEN_Y]) {}
+
+  // mass(&self) -> diagonal of the mass matrix {}
+  // the model is the DAE  mass[i] * y'[i] = deriv_y[i]  solved by Stepper::Radau.
+  // mass[i] = 0 makes y[i] an algebraic state with the constraint 0 = deriv_y[i] of ode,
+  // which is consistent with the integration unlike cre. algebraic states of init are
+  // projected onto their constraints before the first step. Identity by default.
+  fn mass(&self) -> [f64; LEN_Y] {
+    [1.0; LEN_Y]
+  }
+}
+
+pub trait DdeModelTrait<const LEN_Y: usize, const LEN_P: usize, const LEN_B: usize>:
+  SimModelTrait<LEN_Y, LEN_P, LEN_B>
+{
+  // delays(&self) -> delays {}
+  // constant delays (> 0) where discontinuities propagate from the initial time.
+  // states are kept in the history for the largest one.
+  fn delays(&self) -> Vec<f64>;
+
+  // dde(&self, t, y, history, deriv_y) {}
+  // DDE solved by Simulator::run_dde instead of ode.
+  // a delayed state y(t - tau) is given by history.lag(t, tau).
+  fn dde(&self, t: &f64, y: &[f64; LEN_Y], history: &History<LEN_Y>, deriv_y: &mut [f64; LEN_Y]);
 }
 
 pub trait OptModelTrait<
@@ -39,6 +79,21 @@ fn setp(&mut self, index: usize, value: f64) {{
   // In methods using initial values will use the values in p as initial values instead of the bounds.
   fn getx(&self) -> (Vec<usize>, Option<Vec<(f64, f64)>>);
 
+  // getx_transform(&self) -> x_transforms {}
+  // x_transforms indicate the space (same order as x_index) in which optimizers search x.
+  // Logit requires x_bounds. All parameters are searched without transforms by default,
+  // except in GeneticAlgorithm which searches in log10 space.
+  fn getx_transform(&self) -> Option<Vec<Transform>> {
+    None
+  }
+
+  // covariates(&self) -> covariate relationships {}
+  // relationships applied to parameters of each subject by covariates in Data.
+  // No covariate effects by default.
+  fn covariates(&self) -> Vec<Covariate> {
+    Vec::new()
+  }
+
   // setx(&self, index: usize, value: f64) {}
   // set a value to p[index] in a model.
   fn setp(&mut self, _index: usize, _value: f64) {
@@ -51,3 +106,21 @@ fn setp(&mut self, index: usize, value: f64) {{
     );
   }
 }
+
+pub trait ReactionModelTrait<const LEN_Y: usize, const LEN_R: usize>:
+  Clone + Send + 'static
+{
+  fn new() -> Self;
+
+  // init(&self) -> (t0, y0) {}
+  // y are numbers of molecules of species.
+  fn init(&self) -> (f64, [f64; LEN_Y]);
+
+  // propensities(&self, t, y, a) {}
+  // a[j] is the propensity of the j-th reaction at the state y.
+  fn propensities(&self, t: &f64, y: &[f64; LEN_Y], a: &mut [f64; LEN_R]);
+
+  // stoichiometry(&self) -> v {}
+  // v[j][i] is the change of y[i] when the j-th reaction occurs once.
+  fn stoichiometry(&self) -> [[f64; LEN_Y]; LEN_R];
+}
@@ -0,0 +1,302 @@
+use super::loss::Loss;
+use super::prior::Prior;
+use super::transform::Transform;
+
+use crate::data::Data;
+use crate::model::OptModelTrait;
+use crate::simulator::Simulator;
+
+use ndarray::Array1;
+use std::fmt;
+
+// errors in configuring an objective.
+#[derive(Debug)]
+pub enum ObjectiveError {
+  // scale of a robust loss which is not positive
+  InvalidScale(f64),
+  // Gaussian loss for data without yerr of every observation,
+  // where -2 ln L is meaningless with an arbitrary SD (e.g. for AIC and BIC)
+  MissingYerr,
+}
+
+impl fmt::Display for ObjectiveError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      ObjectiveError::InvalidScale(c) => {
+        write!(f, "the scale of a robust loss must be positive (got {})", c)
+      }
+      ObjectiveError::MissingYerr => {
+        write!(f, "the Gaussian loss requires yerr of all observations")
+      }
+    }
+  }
+}
+
+impl std::error::Error for ObjectiveError {}
+
+#[derive(Clone)]
+pub struct Objective<
+  M,
+  const LEN_Y: usize,
+  const LEN_P: usize,
+  const LEN_B: usize,
+  const LEN_X: usize,
+> where
+  M: OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X>,
+{
+  pub simulator: Simulator<M, LEN_Y, LEN_P, LEN_B>,
+  pub data: Data,
+  pub len_x: usize,
+  vec_smp_t: Vec<f64>,
+  arr_obs_y: Array1<f64>,
+  arr_obs_sd: Array1<f64>,
+  ty_index: Vec<(usize, usize)>,
+  pub x_index: Vec<usize>,
+  pub x_bounds: Option<Vec<(f64, f64)>>,
+  pub x_transforms: Vec<Transform>,
+  loss: Loss,
+  pub priors: Vec<(usize, Prior)>,
+}
+
+impl<M, const LEN_Y: usize, const LEN_P: usize, const LEN_B: usize, const LEN_X: usize>
+  Objective<M, LEN_Y, LEN_P, LEN_B, LEN_X>
+where
+  M: OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X>,
+{
+  pub fn new(simulator: Simulator<M, LEN_Y, LEN_P, LEN_B>, data: Data) -> Self {
+    let vec_smp_t = data.make_sampling_time();
+    let arr_obs_y = data.make_arr_obs_y();
+    let arr_obs_sd = data.make_arr_obs_sd();
+    let ty_index = data.make_ty_index(&vec_smp_t);
+    check_states::<LEN_Y>(&data);
+    check_covariates(&simulator.model, &data);
+    let (x_index, x_bounds) = simulator.model.getx();
+    let len_x = x_index.len();
+    let x_transforms = simulator
+      .model
+      .getx_transform()
+      .unwrap_or_else(|| vec![Transform::Identity; len_x]);
+    if x_transforms.len() != len_x {
+      panic!(
+        "getx_transform must have the same length as x_index ({} != {}).",
+        x_transforms.len(),
+        len_x
+      );
+    }
+
+    Self {
+      simulator,
+      data,
+      len_x,
+      vec_smp_t,
+      arr_obs_y,
+      arr_obs_sd,
+      ty_index,
+      x_index,
+      x_bounds,
+      x_transforms,
+      loss: Loss::Ssr,
+      priors: Vec::new(),
+    }
+  }
+
+  pub fn obj(&mut self, new_x: &Array1<f64>) -> f64 {
+    let arr_res_y = self.residuals(new_x);
+
+    // calculate the loss of residuals (SSR by default)
+    // and add prior contributions (MAP objective)
+    self.data_loss(&arr_res_y) + self.penalty(new_x)
+  }
+
+  // data contribution of the objective for given residuals.
+  pub fn data_loss(&self, arr_res_y: &Array1<f64>) -> f64 {
+    self
+      .loss
+      .eval(arr_res_y, &self.arr_obs_sd, self.data.censor())
+  }
+
+  // objective in which x_index[k] is excluded from x
+  // and fixed at the current value in a model.
+  pub fn fix(&self, k: usize) -> Self {
+    let mut objective = self.clone();
+    objective.x_index.remove(k);
+    if let Some(x_bounds) = objective.x_bounds.as_mut() {
+      x_bounds.remove(k);
+    }
+    objective.x_transforms.remove(k);
+    objective.len_x -= 1;
+    objective
+  }
+
+  // objective with the same settings (x, loss, priors) for other data,
+  // which must have yerr of all observations for the Gaussian loss.
+  pub fn with_data(&self, data: Data) -> Self {
+    let vec_smp_t = data.make_sampling_time();
+    check_states::<LEN_Y>(&data);
+    check_covariates(&self.simulator.model, &data);
+    if let Err(err) = check_yerr(&self.loss, &data) {
+      panic!("{}.", err);
+    }
+    let mut objective = self.clone();
+    objective.arr_obs_y = data.make_arr_obs_y();
+    objective.arr_obs_sd = data.make_arr_obs_sd();
+    objective.ty_index = data.make_ty_index(&vec_smp_t);
+    objective.vec_smp_t = vec_smp_t;
+    objective.data = data;
+    objective
+  }
+
+  // loss of residuals (Loss::Ssr by default).
+  pub fn loss(&self) -> &Loss {
+    &self.loss
+  }
+
+  // set the loss of residuals, which is checked against its scale and data.
+  pub fn set_loss(&mut self, loss: Loss) -> Result<(), ObjectiveError> {
+    loss.check_scale()?;
+    check_yerr(&loss, &self.data)?;
+    self.loss = loss;
+    Ok(())
+  }
+
+  // attach a prior to the parameter p[index], which must be in x_index.
+  pub fn add_prior(&mut self, index: usize, prior: Prior) {
+    if !self.x_index.contains(&index) {
+      panic!("p[{}] is not in x_index.", index);
+    }
+    self.priors.push((index, prior));
+  }
+
+  // sum of prior contributions.
+  // parameters not in x_index (e.g. fixed ones) take the values in a model.
+  pub fn penalty(&self, new_x: &Array1<f64>) -> f64 {
+    // getp is required only for priors on parameters not in x_index
+    if self.priors.is_empty() {
+      return 0.0;
+    }
+
+    let mut penalty = 0.0;
+    for (index, prior) in self.priors.iter() {
+      let value = match self.x_index.iter().position(|i| i == index) {
+        Some(k) => new_x[k],
+        None => self.simulator.model.getp()[*index],
+      };
+      penalty += prior.penalty(value);
+    }
+    penalty
+  }
+
+  // objective in the transformed space (z) where optimizers search.
+  pub fn objz(&mut self, new_z: &Array1<f64>) -> f64 {
+    let new_x = self.to_x(new_z);
+    self.obj(&new_x)
+  }
+
+  pub fn to_z(&self, x: &Array1<f64>) -> Array1<f64> {
+    let mut z = x.clone();
+    for (k, transform) in self.x_transforms.iter().enumerate() {
+      z[k] = transform.to_z(x[k], self.bounds(k));
+    }
+    z
+  }
+
+  pub fn to_x(&self, z: &Array1<f64>) -> Array1<f64> {
+    let mut x = z.clone();
+    for (k, transform) in self.x_transforms.iter().enumerate() {
+      x[k] = transform.to_x(z[k], self.bounds(k));
+    }
+    x
+  }
+
+  // ln |dx/dz| of the transforms at z.
+  pub fn log_jacobian(&self, z: &Array1<f64>) -> f64 {
+    let mut log_jacobian = 0.0;
+    for (k, transform) in self.x_transforms.iter().enumerate() {
+      log_jacobian += transform.log_jacobian(z[k], self.bounds(k));
+    }
+    log_jacobian
+  }
+
+  // bounds of x in the transformed space.
+  pub fn z_bounds(&self) -> Vec<(f64, f64)> {
+    let x_bounds = self
+      .x_bounds
+      .as_ref()
+      .expect("please define lower and upper bounds.");
+
+    self
+      .x_transforms
+      .iter()
+      .zip(x_bounds.iter())
+      .map(|(transform, &bounds)| transform.z_bounds(bounds))
+      .collect()
+  }
+
+  fn bounds(&self, k: usize) -> Option<(f64, f64)> {
+    self.x_bounds.as_ref().map(|x_bounds| x_bounds[k])
+  }
+
+  pub fn residuals(&mut self, new_x: &Array1<f64>) -> Array1<f64> {
+    // assign x to the corresponding parameter in a model.
+    self.setx(new_x);
+
+    // simulate (with covariate effects of the subject if any)
+    let simres = if self.simulator.model.covariates().is_empty() {
+      self.simulator.run(&self.vec_smp_t)
+    } else {
+      let simulator = self.simulator.with_covariates(&self.data.covariates);
+      simulator.run(&self.vec_smp_t)
+    };
+
+    // get arr_sim_y from simulation results
+    let mut vec_sim_y = Vec::new();
+    for &(t_index, y_index) in self.ty_index.iter() {
+      vec_sim_y.push(simres.y[t_index][y_index]);
+    }
+    let arr_sim_y = Array1::from(vec_sim_y);
+
+    // residuals in the same order as data.obs
+    &self.arr_obs_y - &arr_sim_y
+  }
+
+  pub fn setx(&mut self, new_x: &Array1<f64>) {
+    for (&x_index, &x_value) in self.x_index.iter().zip(new_x.iter()) {
+      self.simulator.model.setp(x_index, x_value);
+    }
+  }
+}
+
+fn check_yerr(loss: &Loss, data: &Data) -> Result<(), ObjectiveError> {
+  if matches!(loss, Loss::Gaussian) && !data.has_yerr() {
+    Err(ObjectiveError::MissingYerr)
+  } else {
+    Ok(())
+  }
+}
+
+// every observation must refer to a state of a model.
+fn check_states<const LEN_Y: usize>(data: &Data) {
+  if let Some(&(y_index, ..)) = data.obs.iter().find(|&&(y_index, ..)| y_index >= LEN_Y) {
+    panic!("y[{}] in data is out of range.", y_index);
+  }
+}
+
+// every covariate declared by a model must be given in data.
+fn check_covariates<
+  M,
+  const LEN_Y: usize,
+  const LEN_P: usize,
+  const LEN_B: usize,
+  const LEN_X: usize,
+>(
+  model: &M,
+  data: &Data,
+) where
+  M: OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X>,
+{
+  for covariate in model.covariates().iter() {
+    if data.covariate(&covariate.name).is_none() {
+      panic!("covariate '{}' is not given in data.", covariate.name);
+    }
+  }
+}
@@ -0,0 +1,101 @@
+use super::base::ObjectiveError;
+
+use crate::data::Censor;
+use crate::stats;
+
+use ndarray::Array1;
+
+// Loss applied to each residual (obs - sim) in Objective::obj.
+// Robust losses are applied to residuals scaled by yerr (r / yerr, or r if
+// yerr is not given), so that c (or delta) is the scaled residual where
+// down-weighting begins. Every robust loss behaves like r^2 for small
+// residuals, so that the objective value is comparable with SSR when no
+// outlier exists. The scale must be positive, which is checked by
+// Objective::set_loss.
+// Censored observations contribute nothing to every loss except Gaussian,
+// i.e. they are dropped from the fit; use Gaussian for censored data.
+#[derive(Clone)]
+pub enum Loss {
+  // sum of squared residuals (default)
+  Ssr,
+
+  // quadratic within |r| <= delta, linear outside
+  Huber { delta: f64 },
+
+  // c^2 * ln(1 + (r/c)^2)
+  Cauchy { c: f64 },
+
+  // 2 * c^2 * (sqrt(1 + (r/c)^2) - 1)
+  SoftL1 { c: f64 },
+
+  // Tukey biweight: constant (c^2 / 3) for |r| > c
+  Tukey { c: f64 },
+
+  // -2 ln L of Gaussian residuals with SD given by yerr (required).
+  // censored observations contribute the probability of being
+  // below LLOQ or above ULOQ (M3 method).
+  Gaussian,
+}
+
+impl Loss {
+  pub fn rho(&self, r: f64) -> f64 {
+    match self {
+      Loss::Ssr => r.powi(2),
+
+      Loss::Huber { delta } => {
+        if r.abs() <= *delta {
+          r.powi(2)
+        } else {
+          2.0 * delta * r.abs() - delta.powi(2)
+        }
+      }
+
+      Loss::Cauchy { c } => c.powi(2) * (1.0 + (r / c).powi(2)).ln(),
+
+      Loss::SoftL1 { c } => 2.0 * c.powi(2) * ((1.0 + (r / c).powi(2)).sqrt() - 1.0),
+
+      Loss::Tukey { c } => {
+        if r.abs() <= *c {
+          c.powi(2) / 3.0 * (1.0 - (1.0 - (r / c).powi(2)).powi(3))
+        } else {
+          c.powi(2) / 3.0
+        }
+      }
+
+      Loss::Gaussian => r.powi(2) + (2.0 * std::f64::consts::PI).ln(),
+    }
+  }
+
+  // a non-positive scale gives NaN (0/0) or a meaningless loss.
+  pub(crate) fn check_scale(&self) -> Result<(), ObjectiveError> {
+    match self {
+      Loss::Huber { delta: c } | Loss::Cauchy { c } | Loss::SoftL1 { c } | Loss::Tukey { c }
+        if c.is_nan() || *c <= 0.0 =>
+      {
+        Err(ObjectiveError::InvalidScale(*c))
+      }
+      _ => Ok(()),
+    }
+  }
+
+  pub fn eval(&self, arr_res_y: &Array1<f64>, arr_obs_sd: &Array1<f64>, censor: &[Censor]) -> f64 {
+    if arr_res_y.len() != arr_obs_sd.len() || arr_res_y.len() != censor.len() {
+      panic!("residuals, SDs and censoring must have the same length.");
+    }
+
+    let mut loss = 0.0;
+    for ((&r, &sd), c) in arr_res_y.iter().zip(arr_obs_sd.iter()).zip(censor.iter()) {
+      loss += match (self, c) {
+        (Loss::Gaussian, Censor::Quantified) => self.rho(r / sd) + 2.0 * sd.ln(),
+        // r = LLOQ - sim
+        (Loss::Gaussian, Censor::Below(_)) => -2.0 * stats::ln_norm_cdf(r / sd),
+        // r = ULOQ - sim
+        (Loss::Gaussian, Censor::Above(_)) => -2.0 * stats::ln_norm_cdf(-r / sd),
+        (Loss::Ssr, Censor::Quantified) => self.rho(r),
+        (_, Censor::Quantified) => self.rho(r / sd),
+        (_, _) => 0.0,
+      };
+    }
+    loss
+  }
+}
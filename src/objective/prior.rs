@@ -0,0 +1,49 @@
+// Prior (or penalty) on a parameter in x_index.
+// penalty() returns -2 ln p(x) without constant terms, so that
+// it is on the same scale as SSR (= -2 ln L for unit residual SD)
+// and Objective::obj becomes a MAP objective.
+#[derive(Clone)]
+pub enum Prior {
+  // x ~ N(mean, sd^2)
+  Normal { mean: f64, sd: f64 },
+
+  // ln(x) ~ N(mu, sigma^2)
+  LogNormal { mu: f64, sigma: f64 },
+
+  // x ~ U(lb, ub), infinite penalty outside the bounds
+  Uniform { lb: f64, ub: f64 },
+
+  // lambda * |x - center|
+  L1 { center: f64, lambda: f64 },
+
+  // lambda * (x - center)^2
+  L2 { center: f64, lambda: f64 },
+}
+
+impl Prior {
+  pub fn penalty(&self, x: f64) -> f64 {
+    match self {
+      Prior::Normal { mean, sd } => ((x - mean) / sd).powi(2),
+
+      Prior::LogNormal { mu, sigma } => {
+        if x > 0.0 {
+          ((x.ln() - mu) / sigma).powi(2) + 2.0 * x.ln()
+        } else {
+          f64::INFINITY
+        }
+      }
+
+      Prior::Uniform { lb, ub } => {
+        if *lb <= x && x <= *ub {
+          0.0
+        } else {
+          f64::INFINITY
+        }
+      }
+
+      Prior::L1 { center, lambda } => lambda * (x - center).abs(),
+
+      Prior::L2 { center, lambda } => lambda * (x - center).powi(2),
+    }
+  }
+}
@@ -0,0 +1,69 @@
+// Transform of a parameter x into the space z where optimizers search.
+// Logit maps the bounded interval (lb, ub) given by getx onto the real line.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Transform {
+  Identity,
+  Log,
+  Log10,
+  Logit,
+}
+
+impl Transform {
+  // search range of logit-transformed parameters in optimizers using bounds,
+  // corresponding to 0.1% - 99.9% of the bounded interval.
+  const LOGIT_RANGE: f64 = 6.906754778648554; // ln(999)
+
+  pub fn to_z(&self, x: f64, bounds: Option<(f64, f64)>) -> f64 {
+    match self {
+      Transform::Identity => x,
+      Transform::Log => x.ln(),
+      Transform::Log10 => x.log10(),
+      Transform::Logit => {
+        let (lb, ub) = bounds.expect("please define lower and upper bounds for logit transform.");
+        // x at (or beyond) the bounds is clamped to the search range
+        let u = ((x - lb) / (ub - lb)).clamp(0.0, 1.0);
+        (u / (1.0 - u))
+          .ln()
+          .clamp(-Self::LOGIT_RANGE, Self::LOGIT_RANGE)
+      }
+    }
+  }
+
+  pub fn to_x(&self, z: f64, bounds: Option<(f64, f64)>) -> f64 {
+    match self {
+      Transform::Identity => z,
+      Transform::Log => z.exp(),
+      Transform::Log10 => 10f64.powf(z),
+      Transform::Logit => {
+        let (lb, ub) = bounds.expect("please define lower and upper bounds for logit transform.");
+        lb + (ub - lb) / (1.0 + (-z).exp())
+      }
+    }
+  }
+
+  // ln |dx/dz| for densities in the transformed space.
+  pub fn log_jacobian(&self, z: f64, bounds: Option<(f64, f64)>) -> f64 {
+    match self {
+      Transform::Identity => 0.0,
+      Transform::Log => z,
+      Transform::Log10 => z * std::f64::consts::LN_10 + std::f64::consts::LN_10.ln(),
+      Transform::Logit => {
+        let (lb, ub) = bounds.expect("please define lower and upper bounds for logit transform.");
+        // ln((ub - lb) * s * (1 - s)) with s = 1 / (1 + exp(-z))
+        (ub - lb).ln() - softplus(z) - softplus(-z)
+      }
+    }
+  }
+
+  pub fn z_bounds(&self, (lb, ub): (f64, f64)) -> (f64, f64) {
+    match self {
+      Transform::Logit => (-Self::LOGIT_RANGE, Self::LOGIT_RANGE),
+      _ => (self.to_z(lb, None), self.to_z(ub, None)),
+    }
+  }
+}
+
+// ln(1 + exp(z)) without overflow
+fn softplus(z: f64) -> f64 {
+  z.max(0.0) + (-z.abs()).exp().ln_1p()
+}
@@ -1,9 +1,13 @@
 mod base;
 mod genetic_algorithm;
 mod neldermead;
+mod report;
 mod result;
+mod uncertainty;
 
 pub use crate::optimizer::base::{OptOptions, Optimizer};
 pub use crate::optimizer::genetic_algorithm::GeneticAlgorithm;
 pub use crate::optimizer::neldermead::NelderMead;
+pub use crate::optimizer::report::{FitReport, StateStats};
 pub use crate::optimizer::result::OptResult;
+pub use crate::optimizer::uncertainty::Uncertainty;
@@ -4,6 +4,7 @@ use crate::model::OptModelTrait;
 use crate::objective::Objective;
 use crate::optimizer::OptResult;
 
+#[derive(Clone)]
 pub enum OptOptions {
   Default,
 
@@ -23,6 +24,7 @@ pub enum OptOptions {
   },
 }
 
+#[derive(Clone)]
 pub enum Optimizer {
   NelderMead(OptOptions),
   GeneticAlgorithm(OptOptions),
@@ -36,7 +38,7 @@ impl Optimizer {
   where
     M: OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X>,
   {
-    match self {
+    let mut optres = match self {
       Optimizer::NelderMead(options) => {
         let opt = NelderMead::new(objective.len_x, options);
         opt.run(objective)
@@ -46,7 +48,14 @@ impl Optimizer {
         let opt = GeneticAlgorithm::new(objective.len_x, options);
         opt.run(objective)
       }
-    }
+    };
+
+    // split f into the data and the prior contributions,
+    // each evaluated at x since f - f_prior is NaN for an infinite penalty
+    let arr_res_y = objective.residuals(&optres.x);
+    optres.f_data = objective.data_loss(&arr_res_y);
+    optres.f_prior = objective.penalty(&optres.x);
+    optres
   }
 }
 
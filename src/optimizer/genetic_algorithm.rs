@@ -2,7 +2,7 @@ use super::base::{ConcreteOptimizer, OptOptions};
 use super::result::OptResult;
 
 use crate::model::OptModelTrait;
-use crate::objective::Objective;
+use crate::objective::{Objective, Transform};
 
 use ndarray::Array1;
 use rand::distributions::{Distribution, WeightedIndex};
@@ -60,11 +60,23 @@ impl ConcreteOptimizer for GeneticAlgorithm {
     let mut fcall: u64 = 0;
     let mut rng = thread_rng();
 
-    // bounds
-    let log10_bounds = self.get_bounds(objective);
+    // the GA searches in log10 space by default
+    // unless a model declares transforms with getx_transform.
+    let mut log10_objective;
+    let objective = if objective.simulator.model.getx_transform().is_none() {
+      log10_objective = objective.clone();
+      log10_objective.x_transforms = vec![Transform::Log10; objective.len_x];
+      &mut log10_objective
+    } else {
+      objective
+    };
+
+    // bounds in the transformed space of the objective
+    // (individuals are in the transformed space)
+    let z_bounds = objective.z_bounds();
 
     // make initial population
-    let mut pop = self.make_initial_pop(&log10_bounds, &mut rng);
+    let mut pop = self.make_initial_pop(&z_bounds, &mut rng);
     let mut next_pop = pop.clone();
 
     for n_gen in 0..self.max_gen {
@@ -80,8 +92,7 @@ impl ConcreteOptimizer for GeneticAlgorithm {
           // if individual is an elite, the fitness has already been
           // evaluated in the previous generation.
           if thread_ind.0 == f64::INFINITY {
-            let thread_f = thread_objective.obj(&thread_ind.1);
-            thread_f
+            thread_objective.objz(&thread_ind.1)
           } else {
             thread_ind.0
           }
@@ -103,13 +114,16 @@ impl ConcreteOptimizer for GeneticAlgorithm {
 
       // print
       if self.verbose {
-        println!("{:5}:   f:{:.4e}   x:{:10.8}", n_gen, pop[0].0, pop[0].1);
+        println!(
+          "{:5}:   f:{:.4e}   x:{:10.8}",
+          n_gen,
+          pop[0].0,
+          objective.to_x(&pop[0].1)
+        );
       }
 
       // choose elite
-      for i in 0..self.n_elite {
-        next_pop[i] = pop[i].clone();
-      }
+      next_pop[..self.n_elite].clone_from_slice(&pop[..self.n_elite]);
 
       // crossover
       for i in self.n_elite..self.n_pop {
@@ -122,7 +136,12 @@ impl ConcreteOptimizer for GeneticAlgorithm {
       // mutate
       // TODO: in parallel
       for i in 1..self.n_pop {
-        self.mutate(&mut next_pop[i], &log10_bounds, &mut rng);
+        self.mutate(
+          &mut next_pop[i],
+          &z_bounds,
+          &objective.x_transforms,
+          &mut rng,
+        );
       }
 
       // alternate
@@ -130,38 +149,22 @@ impl ConcreteOptimizer for GeneticAlgorithm {
     }
 
     println!("Finished. fcall = {}", fcall);
-    OptResult::new(pop[0].1.clone(), objective.x_index.clone(), pop[0].0)
+    OptResult::new(
+      objective.to_x(&pop[0].1),
+      objective.x_index.clone(),
+      pop[0].0,
+    )
   }
 }
 
 impl GeneticAlgorithm {
-  fn get_bounds<M, const LEN_Y: usize, const LEN_P: usize, const LEN_B: usize, const LEN_X: usize>(
-    &self,
-    objective: &Objective<M, LEN_Y, LEN_P, LEN_B, LEN_X>,
-  ) -> Vec<(f64, f64)>
-  where
-    M: OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X>,
-  {
-    let mut log10_bounds: Vec<(f64, f64)> = Vec::new();
-    let x_bounds = objective
-      .x_bounds
-      .as_ref()
-      .expect("please define lower and upper bounds.");
-
-    for &(lb, ub) in x_bounds.iter() {
-      log10_bounds.push((f64::log10(lb), f64::log10(ub)));
-    }
-
-    log10_bounds
-  }
-
-  fn make_initial_pop(&self, log10_bounds: &Vec<(f64, f64)>, rng: &mut ThreadRng) -> Population {
+  fn make_initial_pop(&self, z_bounds: &[(f64, f64)], rng: &mut ThreadRng) -> Population {
     let mut pop = Vec::new();
     let mut ind: Individual = (f64::INFINITY, Array1::zeros(self.len_x));
 
     for _ in 0..self.n_pop {
-      for (i, &(log10_lb, log10_ub)) in log10_bounds.iter().enumerate() {
-        ind.1[i] = 10f64.powf(rng.gen_range(log10_lb..log10_ub));
+      for (i, &(lb, ub)) in z_bounds.iter().enumerate() {
+        ind.1[i] = rng.gen_range(lb..ub);
       }
       pop.push(ind.clone());
     }
@@ -202,24 +205,35 @@ impl GeneticAlgorithm {
     child
   }
 
-  fn mutate(&self, ind: &mut Individual, log10_bounds: &Vec<(f64, f64)>, rng: &mut ThreadRng) {
+  fn mutate(
+    &self,
+    ind: &mut Individual,
+    z_bounds: &[(f64, f64)],
+    transforms: &[Transform],
+    rng: &mut ThreadRng,
+  ) {
     let mut f_rand_0_1: f64;
-    let mut new_x: f64;
+    let mut new_z: f64;
 
     for i in 0..self.len_x {
       f_rand_0_1 = rng.gen_range(0.0..1.0);
 
       if f_rand_0_1 < self.mutation_rate {
-        new_x = ind.1[i] * rng.gen_range(0.8..1.25);
-        let lb = 10f64.powf(log10_bounds[i].0);
-        let ub = 10f64.powf(log10_bounds[i].1);
-
-        if new_x < lb {
+        // log-transformed parameters are multiplied by 0.8 - 1.25,
+        // and the others are shifted by up to 2.5% of the range.
+        let (lb, ub) = z_bounds[i];
+        new_z = match transforms[i] {
+          Transform::Log => ind.1[i] + rng.gen_range(0.8f64..1.25).ln(),
+          Transform::Log10 => ind.1[i] + rng.gen_range(0.8f64..1.25).log10(),
+          _ => ind.1[i] + (ub - lb) * rng.gen_range(-0.025..0.025),
+        };
+
+        if new_z < lb {
           ind.1[i] = lb;
-        } else if new_x > ub {
+        } else if new_z > ub {
           ind.1[i] = ub;
         } else {
-          ind.1[i] = new_x;
+          ind.1[i] = new_z;
         }
       }
     }
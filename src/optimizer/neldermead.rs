@@ -100,26 +100,29 @@ impl ConcreteOptimizer for NelderMead {
     let mut str_proc = "--";
 
     // make initial simplex
+    // (vertices are in the transformed space of the objective)
     let x_initial = self.make_initial_x(objective);
-    simplex.push((objective.obj(&x_initial), x_initial.clone()));
+    simplex.push((objective.objz(&x_initial), x_initial.clone()));
     fcall += 1;
 
     for k in 0..self.len_x {
       let mut x = x_initial.clone();
       if x[k] != 0.0 {
-        x[k] = (1.0 + self.nonzero_delta) * x[k];
+        x[k] *= 1.0 + self.nonzero_delta;
       } else {
         x[k] = self.zero_delta;
       }
 
-      simplex.push((objective.obj(&x), x));
+      simplex.push((objective.objz(&x), x));
       fcall += 1;
     }
 
     if self.verbose {
       println!(
         "   {}:   f:{:.4e}    x{:10.8}",
-        str_proc, &simplex[0].0, &simplex[0].1
+        str_proc,
+        &simplex[0].0,
+        objective.to_x(&simplex[0].1)
       );
     }
 
@@ -130,7 +133,9 @@ impl ConcreteOptimizer for NelderMead {
       if self.verbose {
         println!(
           "   {}:   f:{:.4e}    x{:10.8}",
-          str_proc, &simplex[0].0, &simplex[0].1
+          str_proc,
+          &simplex[0].0,
+          objective.to_x(&simplex[0].1)
         );
       }
       let f_best = simplex[0].0;
@@ -204,7 +209,7 @@ impl ConcreteOptimizer for NelderMead {
     simplex.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
 
     OptResult::new(
-      simplex[0].1.clone(),
+      objective.to_x(&simplex[0].1),
       objective.x_index.clone(),
       simplex[0].0,
     )
@@ -232,7 +237,7 @@ impl NelderMead {
       ini_x[i] = p[x_index];
     }
 
-    ini_x
+    objective.to_z(&ini_x)
   }
 
   fn centroid(&self, simplex: &Simplex) -> Array1<f64> {
@@ -253,7 +258,7 @@ impl NelderMead {
     M: OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X>,
   {
     let x_reflect = self.rho * (x_centroid - x_worst) + x_centroid;
-    let f_reflect = objective.obj(&x_reflect);
+    let f_reflect = objective.objz(&x_reflect);
     (f_reflect, x_reflect)
   }
 
@@ -267,7 +272,7 @@ impl NelderMead {
     M: OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X>,
   {
     let x_expand = self.rho * self.chi * (x_centroid - x_worst);
-    let f_expand = objective.obj(&x_expand);
+    let f_expand = objective.objz(&x_expand);
     (f_expand, x_expand)
   }
 
@@ -281,7 +286,7 @@ impl NelderMead {
     M: OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X>,
   {
     let x_outside = self.psi * self.rho * (x_centroid - x_worst) + x_centroid;
-    let f_outside = objective.obj(&x_outside);
+    let f_outside = objective.objz(&x_outside);
     (f_outside, x_outside)
   }
 
@@ -295,7 +300,7 @@ impl NelderMead {
     M: OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X>,
   {
     let x_inside = self.psi * (x_worst - x_centroid);
-    let f_inside = objective.obj(&x_inside);
+    let f_inside = objective.objz(&x_inside);
     (f_inside, x_inside)
   }
 
@@ -308,7 +313,7 @@ impl NelderMead {
   {
     for i in 1..self.len_x + 1 {
       let x_shrink = &simplex[0].1 + &(self.sigma * (&simplex[i].1 - &simplex[0].1));
-      let f_shrink = objective.obj(&x_shrink);
+      let f_shrink = objective.objz(&x_shrink);
       simplex[i] = (f_shrink, x_shrink);
     }
   }
@@ -322,7 +327,7 @@ impl NelderMead {
     let best_x = &simplex[0].1;
     for (not_best_f, not_best_x) in simplex.iter().skip(1) {
       let f_dif = (best_f - not_best_f).abs();
-      let x_dif = *&(best_x - not_best_x)
+      let x_dif = (best_x - not_best_x)
         .mapv(f64::abs)
         .iter()
         .fold(0.0, |a, b| b.max(a));
@@ -336,10 +341,6 @@ impl NelderMead {
     }
 
     // converged or not
-    if max_x_dif <= self.x_abstol && max_f_dif <= self.f_abstol {
-      true
-    } else {
-      false
-    }
+    max_x_dif <= self.x_abstol && max_f_dif <= self.f_abstol
   }
 }
@@ -0,0 +1,174 @@
+use std::path::Path;
+
+use crate::data::Censor;
+use crate::model::OptModelTrait;
+use crate::objective::{Loss, Objective};
+use crate::simulator::write_file;
+use crate::stats;
+
+use ndarray::Array1;
+
+// residual statistics of an observed state.
+// runs test counts sign changes of residuals ordered by time,
+// and a small p_runs suggests autocorrelated (systematic) residuals.
+pub struct StateStats {
+  pub index: usize,
+  pub n_obs: usize,
+  pub mean: f64,
+  pub sd: f64,
+  pub n_runs: usize,
+  pub z_runs: f64,
+  pub p_runs: f64,
+}
+
+// goodness-of-fit and model selection statistics.
+// log-likelihood assumes Gaussian residuals with SD given by yerr in Data
+// (with censored observations by the M3 method, see Loss::Gaussian).
+// if yerr is missing in any observation (not allowed for the Gaussian loss),
+// a common SD is estimated by maximum likelihood from quantified observations
+// and counted as a parameter.
+// residual statistics use quantified observations only.
+pub struct FitReport {
+  pub n_obs: usize,
+  pub n_par: usize,
+  pub loglik: f64,
+  pub aic: f64,
+  pub aicc: f64,
+  pub bic: f64,
+  pub states: Vec<StateStats>,
+}
+
+impl FitReport {
+  pub fn new<M, const LEN_Y: usize, const LEN_P: usize, const LEN_B: usize, const LEN_X: usize>(
+    x: &Array1<f64>,
+    objective: &mut Objective<M, LEN_Y, LEN_P, LEN_B, LEN_X>,
+  ) -> Self
+  where
+    M: OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X>,
+  {
+    let arr_res_y = objective.residuals(x);
+    let arr_obs_sd = objective.data.make_arr_obs_sd();
+    let obs = &objective.data.obs;
+    let censor = objective.data.censor();
+
+    // residuals of quantified observations
+    let quantified: Vec<(usize, f64, f64)> = obs
+      .iter()
+      .zip(censor.iter())
+      .zip(arr_res_y.iter())
+      .filter(|((_, &c), _)| c == Censor::Quantified)
+      .map(|((o, _), &r)| (o.0, o.1, r))
+      .collect();
+
+    // log-likelihood
+    let (loglik, n_obs, n_par) = if objective.data.has_yerr() {
+      let loglik = -0.5 * Loss::Gaussian.eval(&arr_res_y, &arr_obs_sd, censor);
+      (loglik, obs.len(), objective.len_x)
+    } else {
+      let n = quantified.len() as f64;
+      let var = quantified.iter().map(|q| q.2.powi(2)).sum::<f64>() / n;
+      let loglik = -0.5 * n * ((2.0 * std::f64::consts::PI * var).ln() + 1.0);
+      (loglik, quantified.len(), objective.len_x + 1)
+    };
+
+    // information criteria
+    let n = n_obs as f64;
+    let k = n_par as f64;
+    let aic = 2.0 * k - 2.0 * loglik;
+    let aicc = if n - k - 1.0 > 0.0 {
+      aic + 2.0 * k * (k + 1.0) / (n - k - 1.0)
+    } else {
+      f64::INFINITY
+    };
+    let bic = k * n.ln() - 2.0 * loglik;
+
+    // residual statistics for each observed state
+    let mut y_indices: Vec<usize> = quantified.iter().map(|q| q.0).collect();
+    y_indices.sort_unstable();
+    y_indices.dedup();
+
+    let mut states = Vec::new();
+    for &y_index in y_indices.iter() {
+      let mut tr: Vec<(f64, f64)> = quantified
+        .iter()
+        .filter(|q| q.0 == y_index)
+        .map(|q| (q.1, q.2))
+        .collect();
+      tr.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+      let res: Vec<f64> = tr.iter().map(|&(_, r)| r).collect();
+      states.push(StateStats::new(y_index, &res));
+    }
+
+    Self {
+      n_obs,
+      n_par,
+      loglik,
+      aic,
+      aicc,
+      bic,
+      states,
+    }
+  }
+
+  pub fn save(&self, dir: &str) {
+    let save_dir = Path::new(dir);
+    let mut str_result = String::new();
+
+    str_result.push_str(&format!("n_obs,{}\n", self.n_obs));
+    str_result.push_str(&format!("n_par,{}\n", self.n_par));
+    str_result.push_str(&format!("loglik,{}\n", self.loglik));
+    str_result.push_str(&format!("aic,{}\n", self.aic));
+    str_result.push_str(&format!("aicc,{}\n", self.aicc));
+    str_result.push_str(&format!("bic,{}\n", self.bic));
+
+    str_result.push_str("index,n_obs,mean,sd,n_runs,z_runs,p_runs\n");
+    for s in self.states.iter() {
+      str_result.push_str(&format!(
+        "{},{},{},{},{},{},{}\n",
+        s.index, s.n_obs, s.mean, s.sd, s.n_runs, s.z_runs, s.p_runs
+      ));
+    }
+
+    let file_name = String::from("fitreport.csv");
+    let save_path = save_dir.join(file_name);
+    write_file(&save_path, &str_result);
+  }
+}
+
+impl StateStats {
+  fn new(index: usize, res: &[f64]) -> Self {
+    // Wald-Wolfowitz runs test on the signs of residuals (zeros are skipped).
+    let signs: Vec<bool> = res
+      .iter()
+      .filter(|&&r| r != 0.0)
+      .map(|&r| r > 0.0)
+      .collect();
+    let n_pos = signs.iter().filter(|&&s| s).count() as f64;
+    let n_neg = signs.len() as f64 - n_pos;
+    let n_runs = if signs.is_empty() {
+      0
+    } else {
+      1 + signs.windows(2).filter(|w| w[0] != w[1]).count()
+    };
+
+    let n = n_pos + n_neg;
+    let mu = 2.0 * n_pos * n_neg / n + 1.0;
+    let var = 2.0 * n_pos * n_neg * (2.0 * n_pos * n_neg - n) / (n.powi(2) * (n - 1.0));
+    let (z_runs, p_runs) = if var > 0.0 {
+      let z = (n_runs as f64 - mu) / var.sqrt();
+      (z, 2.0 * (1.0 - stats::norm_cdf(z.abs())))
+    } else {
+      (f64::NAN, f64::NAN)
+    };
+
+    Self {
+      index,
+      n_obs: res.len(),
+      mean: stats::mean(res),
+      sd: stats::sd(res),
+      n_runs,
+      z_runs,
+      p_runs,
+    }
+  }
+}
@@ -1,18 +1,71 @@
-use std::fs::File;
-use std::io::Write;
 use std::path::Path;
 
+use super::report::FitReport;
+use super::uncertainty::Uncertainty;
+
+use crate::model::OptModelTrait;
+use crate::objective::Objective;
+use crate::simulator::write_file;
+
 use ndarray::Array1;
 
 pub struct OptResult {
   pub x: Array1<f64>,
   pub index: Vec<usize>,
   pub f: f64,
+  pub f_data: f64,
+  pub f_prior: f64,
+  pub report: Option<FitReport>,
+  pub uncertainty: Option<Uncertainty>,
 }
 
 impl OptResult {
   pub fn new(x: Array1<f64>, index: Vec<usize>, f: f64) -> Self {
-    Self { x, index, f }
+    Self {
+      x,
+      index,
+      f,
+      f_data: f,
+      f_prior: 0.0,
+      report: None,
+      uncertainty: None,
+    }
+  }
+
+  // compute goodness-of-fit statistics at x and keep them in the result.
+  pub fn fit_report<
+    M,
+    const LEN_Y: usize,
+    const LEN_P: usize,
+    const LEN_B: usize,
+    const LEN_X: usize,
+  >(
+    &mut self,
+    objective: &mut Objective<M, LEN_Y, LEN_P, LEN_B, LEN_X>,
+  ) -> &FitReport
+  where
+    M: OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X>,
+  {
+    self.report.insert(FitReport::new(&self.x, objective))
+  }
+
+  // compute asymptotic uncertainty of x and keep it in the result.
+  pub fn estimate_uncertainty<
+    M,
+    const LEN_Y: usize,
+    const LEN_P: usize,
+    const LEN_B: usize,
+    const LEN_X: usize,
+  >(
+    &mut self,
+    objective: &mut Objective<M, LEN_Y, LEN_P, LEN_B, LEN_X>,
+  ) -> &Uncertainty
+  where
+    M: OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X>,
+  {
+    self
+      .uncertainty
+      .insert(Uncertainty::new(&self.x, objective))
   }
 
   pub fn save(&self, dir: &str) {
@@ -26,12 +79,13 @@ impl OptResult {
       str_result.push('\n');
     }
 
+    // objective values after the parameters
+    str_result.push_str(&format!("f,{}\n", self.f));
+    str_result.push_str(&format!("f_data,{}\n", self.f_data));
+    str_result.push_str(&format!("f_prior,{}\n", self.f_prior));
+
     let file_name = String::from("optres.csv");
     let save_path = save_dir.join(file_name);
-
-    // Write string into a file
-    let mut file = File::create(save_path).unwrap();
-    write!(file, "{}", str_result).unwrap();
-    file.flush().unwrap();
+    write_file(&save_path, &str_result);
   }
 }
@@ -0,0 +1,177 @@
+use std::path::Path;
+
+use crate::data::Censor;
+use crate::linalg;
+use crate::model::OptModelTrait;
+use crate::objective::{Loss, Objective};
+use crate::simulator::write_file;
+
+use ndarray::{Array1, Array2};
+
+// asymptotic uncertainty of x from the Hessian of Objective::obj at the optimum.
+// the Hessian is evaluated by central finite differences with relative step h,
+// so the tolerance of the stepper should be much smaller than h.
+// for the Gaussian loss the objective is -2 ln L and fisher = hessian / 2.
+// other losses have no likelihood, so that hessian is the one of the SSR of
+// uncensored observations (plus priors) at the optimum, i.e. that of the
+// objective itself for Loss::Ssr. its data term of fisher is scaled by the
+// residual variance SSR / (n_uncensored - len_x), and the prior term
+// (-2 ln prior) is not. for robust losses, this is the covariance of least
+// squares at the robust optimum, which is not valid with outliers.
+pub struct Uncertainty {
+  pub index: Vec<usize>,
+  pub hessian: Array2<f64>,
+  pub fisher: Array2<f64>,
+  pub cov: Array2<f64>,
+  pub se: Array1<f64>,
+  pub rse: Array1<f64>,
+  pub corr: Array2<f64>,
+  pub eigvals: Vec<f64>,
+  pub condition: f64,
+}
+
+impl Uncertainty {
+  const H: f64 = 1e-3;
+
+  pub fn new<M, const LEN_Y: usize, const LEN_P: usize, const LEN_B: usize, const LEN_X: usize>(
+    x: &Array1<f64>,
+    objective: &mut Objective<M, LEN_Y, LEN_P, LEN_B, LEN_X>,
+  ) -> Self
+  where
+    M: OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X>,
+  {
+    let len_x = objective.len_x;
+    let hessian_prior = Self::hessian(x, |new_x| objective.penalty(new_x));
+
+    // Fisher information
+    let (hessian, fisher) = match objective.loss() {
+      Loss::Gaussian => {
+        let hessian = Self::hessian(x, |new_x| objective.obj(new_x));
+        let fisher = &hessian / 2.0;
+        (hessian, fisher)
+      }
+      _ => {
+        let mut ssr_objective = objective.clone();
+        ssr_objective.set_loss(Loss::Ssr).unwrap();
+        let hessian = Self::hessian(x, |new_x| ssr_objective.obj(new_x));
+
+        let arr_res_y = ssr_objective.residuals(x);
+        let ssr = ssr_objective.data_loss(&arr_res_y);
+        let n_uncensored = objective
+          .data
+          .censor()
+          .iter()
+          .filter(|&&c| c == Censor::Quantified)
+          .count();
+        let dof = n_uncensored as f64 - len_x as f64;
+        let scale = if dof > 0.0 { ssr / dof } else { 1.0 };
+        let fisher = (&hessian - &hessian_prior) / (2.0 * scale) + &hessian_prior / 2.0;
+        (hessian, fisher)
+      }
+    };
+
+    // covariance (NaN if the Fisher information is singular)
+    let cov = linalg::inv(&fisher).unwrap_or_else(|| Array2::from_elem((len_x, len_x), f64::NAN));
+
+    let se = cov.diag().mapv(f64::sqrt);
+    let rse = &se / &x.mapv(f64::abs);
+
+    let mut corr = Array2::zeros((len_x, len_x));
+    for i in 0..len_x {
+      for j in 0..len_x {
+        corr[[i, j]] = cov[[i, j]] / (se[i] * se[j]);
+      }
+    }
+
+    // condition number of the Fisher information
+    let eigvals = linalg::sym_eigvals(&fisher);
+    let condition = if len_x == 0 {
+      1.0
+    } else if eigvals[0] > 0.0 {
+      eigvals[len_x - 1] / eigvals[0]
+    } else {
+      f64::INFINITY
+    };
+
+    Self {
+      index: objective.x_index.clone(),
+      hessian,
+      fisher,
+      cov,
+      se,
+      rse,
+      corr,
+      eigvals,
+      condition,
+    }
+  }
+
+  // Hessian of f at x.
+  fn hessian<F>(x: &Array1<f64>, mut f: F) -> Array2<f64>
+  where
+    F: FnMut(&Array1<f64>) -> f64,
+  {
+    let len_x = x.len();
+    let h: Vec<f64> = x
+      .iter()
+      .map(|&v| if v != 0.0 { Self::H * v.abs() } else { Self::H })
+      .collect();
+
+    let mut f_at = |shifts: &[(usize, f64)]| {
+      let mut new_x = x.clone();
+      for &(i, shift) in shifts.iter() {
+        new_x[i] += shift * h[i];
+      }
+      f(&new_x)
+    };
+
+    let f0 = f_at(&[]);
+    let mut hessian = Array2::zeros((len_x, len_x));
+    for i in 0..len_x {
+      let f_pos = f_at(&[(i, 1.0)]);
+      let f_neg = f_at(&[(i, -1.0)]);
+      hessian[[i, i]] = (f_pos - 2.0 * f0 + f_neg) / h[i].powi(2);
+
+      for j in 0..i {
+        let f_pp = f_at(&[(i, 1.0), (j, 1.0)]);
+        let f_pn = f_at(&[(i, 1.0), (j, -1.0)]);
+        let f_np = f_at(&[(i, -1.0), (j, 1.0)]);
+        let f_nn = f_at(&[(i, -1.0), (j, -1.0)]);
+        hessian[[i, j]] = (f_pp - f_pn - f_np + f_nn) / (4.0 * h[i] * h[j]);
+        hessian[[j, i]] = hessian[[i, j]];
+      }
+    }
+    hessian
+  }
+
+  // parameters (indices in a model) whose relative standard error exceeds max_rse
+  // or whose standard error is not available.
+  pub fn poorly_determined(&self, max_rse: f64) -> Vec<usize> {
+    self
+      .index
+      .iter()
+      .zip(self.rse.iter())
+      .filter(|(_, &rse)| rse.is_nan() || rse > max_rse)
+      .map(|(&index, _)| index)
+      .collect()
+  }
+
+  pub fn save(&self, dir: &str) {
+    let save_dir = Path::new(dir);
+    let mut str_result = String::new();
+
+    // index, se, rse, correlations with each parameter
+    for (i, index) in self.index.iter().enumerate() {
+      str_result.push_str(&format!("{},{},{}", index, self.se[i], self.rse[i]));
+      for j in 0..self.index.len() {
+        str_result.push(',');
+        str_result.push_str(&self.corr[[i, j]].to_string());
+      }
+      str_result.push('\n');
+    }
+
+    let file_name = String::from("uncertainty.csv");
+    let save_path = save_dir.join(file_name);
+    write_file(&save_path, &str_result);
+  }
+}
@@ -0,0 +1,9 @@
+mod base;
+mod nlme;
+mod result;
+mod saem;
+
+pub use crate::population::base::Population;
+pub use crate::population::nlme::{ErrorModel, NlmeResult};
+pub use crate::population::result::PopulationResult;
+pub use crate::population::saem::Saem;
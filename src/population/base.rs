@@ -0,0 +1,114 @@
+use super::result::PopulationResult;
+
+use crate::linalg;
+use crate::model::OptModelTrait;
+use crate::simulator::{EnsembleResult, Simulator};
+use crate::stats;
+
+use ndarray::{s, Array1, Array2};
+
+// Virtual population with inter-individual variability.
+// p[index[k]] of a subject is the typical value in a model times exp(eta[k]),
+// where eta ~ N(0, omega), i.e. log-normal random effects with covariance omega.
+// subjects are reproducible if seed is given.
+// covariates[i] are (name, value) of the i-th subject (none if empty),
+// whose typical values include the effects in OptModelTrait::covariates.
+pub struct Population {
+  pub index: Vec<usize>,
+  pub omega: Array2<f64>,
+  pub n_subjects: usize,
+  pub seed: Option<u64>,
+  pub covariates: Vec<Vec<(String, f64)>>,
+}
+
+impl Population {
+  pub fn new(index: Vec<usize>, omega: Array2<f64>, n_subjects: usize, seed: Option<u64>) -> Self {
+    if omega.nrows() != index.len() || omega.ncols() != index.len() {
+      panic!("omega must be a square matrix of the size of index.");
+    }
+    Self {
+      index,
+      omega,
+      n_subjects,
+      seed,
+      covariates: Vec::new(),
+    }
+  }
+
+  // population whose subjects have covariates (one set per subject).
+  pub fn with_covariates(mut self, covariates: Vec<Vec<(String, f64)>>) -> Self {
+    if covariates.len() != self.n_subjects {
+      panic!("covariates must be given for each of n_subjects subjects.");
+    }
+    self.covariates = covariates;
+    self
+  }
+
+  // random effects of n_subjects subjects
+  pub fn sample_eta(&self) -> Vec<Array1<f64>> {
+    let chol = linalg::cholesky(&self.omega).expect("omega must be positive definite.");
+    let mut rng = stats::make_rng(self.seed, 0);
+    (0..self.n_subjects)
+      .map(|_| {
+        let z: Array1<f64> = (0..self.index.len())
+          .map(|_| stats::randn(&mut rng))
+          .collect();
+        chol.dot(&z)
+      })
+      .collect()
+  }
+
+  // simulate every subject in parallel.
+  pub fn run<M, const LEN_Y: usize, const LEN_P: usize, const LEN_B: usize, const LEN_X: usize>(
+    &self,
+    simulator: &Simulator<M, LEN_Y, LEN_P, LEN_B>,
+    smp_t: &[f64],
+  ) -> PopulationResult<LEN_Y>
+  where
+    M: OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X>,
+  {
+    // parameters set for each subject:
+    // index followed by the other parameters with covariate effects
+    let mut sample_index = self.index.clone();
+    if !self.covariates.is_empty() {
+      for covariate in simulator.model.covariates().iter() {
+        if !sample_index.contains(&covariate.index) {
+          sample_index.push(covariate.index);
+        }
+      }
+    }
+
+    // typical values of each subject
+    let typical: Vec<Array1<f64>> = (0..self.n_subjects)
+      .map(|i| {
+        let p = match self.covariates.get(i) {
+          Some(covariates) => *simulator.with_covariates(covariates).model.getp(),
+          None => *simulator.model.getp(),
+        };
+        sample_index.iter().map(|&k| p[k]).collect()
+      })
+      .collect();
+
+    let eta = self.sample_eta();
+    let samples: Vec<Array1<f64>> = typical
+      .iter()
+      .zip(eta.iter())
+      .map(|(typical, eta)| {
+        let mut sample = typical.clone();
+        for (k, &e) in eta.iter().enumerate() {
+          sample[k] *= e.exp();
+        }
+        sample
+      })
+      .collect();
+
+    let subjects = simulator.simulate_samples(smp_t, &sample_index, &samples);
+    let params: Vec<Array1<f64>> = samples
+      .iter()
+      .map(|sample| sample.slice(s![..self.index.len()]).to_owned())
+      .collect();
+    let summary = EnsembleResult::new(&subjects);
+
+    PopulationResult::new(self.index.clone(), eta, params, subjects, summary)
+  }
+}
@@ -0,0 +1,109 @@
+use std::path::Path;
+
+use crate::simulator::write_file;
+use crate::stats;
+
+use ndarray::Array1;
+
+// residual error model: SD of an observation for the simulated value f.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ErrorModel {
+  // sigma
+  Additive,
+  // sigma * |f|
+  Proportional,
+}
+
+impl ErrorModel {
+  pub fn sd(&self, sigma: f64, f: f64) -> f64 {
+    match self {
+      ErrorModel::Additive => sigma,
+      ErrorModel::Proportional => sigma * f.abs().max(1e-12),
+    }
+  }
+}
+
+// estimates of a mixed-effects model.
+// theta: typical values, omega2: variances of random effects (ln x),
+// eta and params: random effects and parameters of each subject (EBEs),
+// shrinkage: 1 - sd(eta) / omega, history: (ln theta, omega2, sigma) of iterations.
+pub struct NlmeResult {
+  pub index: Vec<usize>,
+  pub theta: Array1<f64>,
+  pub omega2: Array1<f64>,
+  pub sigma: f64,
+  pub error_model: ErrorModel,
+  pub eta: Vec<Array1<f64>>,
+  pub params: Vec<Array1<f64>>,
+  pub shrinkage: Vec<f64>,
+  pub history: Vec<(Array1<f64>, Array1<f64>, f64)>,
+}
+
+impl NlmeResult {
+  pub fn new(
+    index: Vec<usize>,
+    (mu, omega2, sigma): (Array1<f64>, Array1<f64>, f64),
+    error_model: ErrorModel,
+    eta: Vec<Array1<f64>>,
+    history: Vec<(Array1<f64>, Array1<f64>, f64)>,
+  ) -> Self {
+    let params = eta.iter().map(|eta| (&mu + eta).mapv(f64::exp)).collect();
+    let shrinkage = (0..index.len())
+      .map(|k| {
+        let values: Vec<f64> = eta.iter().map(|eta| eta[k]).collect();
+        1.0 - stats::sd(&values) / omega2[k].sqrt()
+      })
+      .collect();
+
+    Self {
+      index,
+      theta: mu.mapv(f64::exp),
+      omega2,
+      sigma,
+      error_model,
+      eta,
+      params,
+      shrinkage,
+      history,
+    }
+  }
+
+  pub fn save(&self, dir: &str) {
+    let save_dir = Path::new(dir);
+
+    // population parameters: index, theta, omega2, shrinkage
+    let mut str_result = String::new();
+    for (k, index) in self.index.iter().enumerate() {
+      str_result.push_str(&format!(
+        "{},{},{},{}\n",
+        index, self.theta[k], self.omega2[k], self.shrinkage[k]
+      ));
+    }
+    str_result.push_str(&format!("sigma,{}\n", self.sigma));
+    write_file(&save_dir.join("nlme.csv"), &str_result);
+
+    // individual parameters: subject, params..., eta...
+    let mut str_result = String::new();
+    for (n, (params, eta)) in self.params.iter().zip(self.eta.iter()).enumerate() {
+      str_result.push_str(&n.to_string());
+      for v in params.iter().chain(eta.iter()) {
+        str_result.push(',');
+        str_result.push_str(&v.to_string());
+      }
+      str_result.push('\n');
+    }
+    write_file(&save_dir.join("nlme_ebe.csv"), &str_result);
+
+    // iterations: iteration, theta..., omega2..., sigma
+    let mut str_result = String::new();
+    for (n, (mu, omega2, sigma)) in self.history.iter().enumerate() {
+      str_result.push_str(&n.to_string());
+      for v in mu.mapv(f64::exp).iter().chain(omega2.iter()) {
+        str_result.push(',');
+        str_result.push_str(&v.to_string());
+      }
+      str_result.push_str(&format!(",{}\n", sigma));
+    }
+    write_file(&save_dir.join("nlme_history.csv"), &str_result);
+  }
+}
@@ -0,0 +1,65 @@
+use std::path::Path;
+
+use crate::simulator::{write_file, EnsembleResult, SimResult};
+
+use ndarray::Array1;
+
+// random effects, parameters p[index] and simulation of each subject,
+// and the summary over subjects.
+pub struct PopulationResult<const LEN_Y: usize> {
+  pub index: Vec<usize>,
+  pub eta: Vec<Array1<f64>>,
+  pub params: Vec<Array1<f64>>,
+  pub subjects: Vec<SimResult<LEN_Y>>,
+  pub summary: EnsembleResult<LEN_Y>,
+}
+
+impl<const LEN_Y: usize> PopulationResult<LEN_Y> {
+  pub fn new(
+    index: Vec<usize>,
+    eta: Vec<Array1<f64>>,
+    params: Vec<Array1<f64>>,
+    subjects: Vec<SimResult<LEN_Y>>,
+    summary: EnsembleResult<LEN_Y>,
+  ) -> Self {
+    Self {
+      index,
+      eta,
+      params,
+      subjects,
+      summary,
+    }
+  }
+
+  pub fn save(&self, dir: &str) {
+    let save_dir = Path::new(dir);
+
+    // parameters: subject, p[index]..., eta...
+    let mut str_result = String::new();
+    for (n, (params, eta)) in self.params.iter().zip(self.eta.iter()).enumerate() {
+      str_result.push_str(&n.to_string());
+      for v in params.iter().chain(eta.iter()) {
+        str_result.push(',');
+        str_result.push_str(&v.to_string());
+      }
+      str_result.push('\n');
+    }
+    write_file(&save_dir.join("population.csv"), &str_result);
+
+    // simulations: subject, t, y...
+    let mut str_result = String::new();
+    for (n, simres) in self.subjects.iter().enumerate() {
+      for (t, y) in simres.t.iter().zip(simres.y.iter()) {
+        str_result.push_str(&format!("{},{}", n, t));
+        for j in 0..LEN_Y {
+          str_result.push(',');
+          str_result.push_str(&y[j].to_string());
+        }
+        str_result.push('\n');
+      }
+    }
+    write_file(&save_dir.join("population_simres.csv"), &str_result);
+
+    self.summary.save(dir);
+  }
+}
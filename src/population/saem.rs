@@ -0,0 +1,357 @@
+use super::nlme::{ErrorModel, NlmeResult};
+
+use crate::data::{Censor, Data};
+use crate::model::OptModelTrait;
+use crate::objective::Objective;
+use crate::stats;
+
+use ndarray::Array1;
+use rand::rngs::StdRng;
+use rand::Rng;
+use std::thread;
+
+// Nonlinear mixed-effects estimation by SAEM (Kuhn and Lavielle, 2005).
+// every parameter in x_index has a log-normal random effect:
+//   ln x_ik = mu_k + eta_ik, eta_ik ~ N(0, omega_k^2),
+// and observations of subject i (subjects[i]) have the residual error model.
+// typical values exp(mu) start from the values in a model.
+// the first n_explore iterations explore with the step size 1
+// (variances decrease at most 5% per iteration), and the next n_smooth
+// iterations converge with the step size 1 / k.
+// individual parameters (EBEs) are the conditional means during smoothing.
+// censored observations are not supported (the likelihood would be biased
+// by ignoring them), and subjects must have at least one observation in total.
+// the objective gives the model, x_index and covariate relationships;
+// its loss, priors and transforms are not used, since the likelihood is
+// given by error_model and the random effects.
+pub struct Saem {
+  pub n_explore: usize,
+  pub n_smooth: usize,
+  pub error_model: ErrorModel,
+  pub seed: Option<u64>,
+  pub verbose: bool,
+}
+
+// state of the Markov chain of a subject
+struct Subject<M, const LEN_Y: usize, const LEN_P: usize, const LEN_B: usize, const LEN_X: usize>
+where
+  M: OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X>,
+{
+  objective: Objective<M, LEN_Y, LEN_P, LEN_B, LEN_X>,
+  quantified: Vec<usize>,
+  phi: Array1<f64>,
+  // (obs - sim, sim) of quantified observations at phi
+  res: (Array1<f64>, Array1<f64>),
+  delta: Array1<f64>,
+  sum_phi: Array1<f64>,
+  rng: StdRng,
+}
+
+// (mu, omega^2, sigma) of an iteration
+type PopParams = (Array1<f64>, Array1<f64>, f64);
+
+impl Saem {
+  // number of MCMC steps for each kernel per iteration
+  const N_KERNEL: usize = 2;
+
+  pub fn new(
+    n_explore: usize,
+    n_smooth: usize,
+    error_model: ErrorModel,
+    seed: Option<u64>,
+    verbose: bool,
+  ) -> Self {
+    Self {
+      n_explore,
+      n_smooth,
+      error_model,
+      seed,
+      verbose,
+    }
+  }
+
+  pub fn run<M, const LEN_Y: usize, const LEN_P: usize, const LEN_B: usize, const LEN_X: usize>(
+    &self,
+    objective: &Objective<M, LEN_Y, LEN_P, LEN_B, LEN_X>,
+    subjects: &[Data],
+  ) -> NlmeResult
+  where
+    M: OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X>,
+  {
+    let len_x = objective.len_x;
+    let n_subjects = subjects.len() as f64;
+
+    if subjects
+      .iter()
+      .any(|data| data.censor().iter().any(|&c| c != Censor::Quantified))
+    {
+      panic!("censored observations are not supported in Saem.");
+    }
+    if subjects.iter().all(|data| data.obs.is_empty()) {
+      panic!("subjects have no observations.");
+    }
+    let error_model = self.error_model;
+
+    // initial population parameters
+    let p = objective.simulator.model.getp();
+    let x0: Array1<f64> = objective.x_index.iter().map(|&i| p[i]).collect();
+    if x0.iter().any(|&x| x <= 0.0) {
+      panic!("parameters with log-normal random effects must be positive.");
+    }
+    let mut mu = x0.mapv(f64::ln);
+    let mut omega2 = Array1::from_elem(len_x, 1.0);
+
+    // chains of subjects start at the typical values
+    let mut states = Vec::new();
+    for (n, data) in subjects.iter().enumerate() {
+      let mut subject_objective = objective.with_data(data.clone());
+      let quantified: Vec<usize> = (0..data.obs.len())
+        .filter(|&j| data.censor()[j] == Censor::Quantified)
+        .collect();
+      let res = residuals(&mut subject_objective, &quantified, &mu);
+      states.push(Subject {
+        objective: subject_objective,
+        quantified,
+        phi: mu.clone(),
+        res,
+        delta: Array1::from_elem(len_x, 0.3),
+        sum_phi: Array1::zeros(len_x),
+        rng: stats::make_rng(self.seed, n as u64),
+      });
+    }
+
+    let n_obs: usize = states.iter().map(|s| s.quantified.len()).sum();
+    let mut sigma = (residual_stat(error_model, &states) / n_obs as f64).sqrt();
+
+    // sufficient statistics
+    let mut s1 = mu.clone();
+    let mut s2 = &omega2 + &mu.mapv(|v| v * v);
+    let mut s3 = sigma.powi(2) * n_obs as f64;
+
+    let n_threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let mut history: Vec<PopParams> = Vec::new();
+
+    for n_iter in 0..self.n_explore + self.n_smooth {
+      let smoothing = n_iter >= self.n_explore;
+
+      // ===== simulation step =====
+      // distribute subjects to threads
+      let mut chunks: Vec<Vec<(usize, Subject<M, LEN_Y, LEN_P, LEN_B, LEN_X>)>> =
+        (0..n_threads).map(|_| Vec::new()).collect();
+      for (n, state) in states.into_iter().enumerate() {
+        chunks[n % n_threads].push((n, state));
+      }
+
+      // vector for join-handles
+      let mut handles = Vec::new();
+
+      for chunk in chunks.into_iter() {
+        let (thread_mu, thread_omega2) = (mu.clone(), omega2.clone());
+
+        // ===== FORK =====
+        let handle = thread::spawn(move || {
+          let mut chunk = chunk;
+          for (_, state) in chunk.iter_mut() {
+            sample_subject(state, (&thread_mu, &thread_omega2, sigma), error_model);
+            if smoothing {
+              state.sum_phi = &state.sum_phi + &state.phi;
+            }
+          }
+          chunk
+        });
+        // ================
+
+        handles.push(handle);
+      }
+
+      // ===== JOIN =====
+      let mut joined = Vec::new();
+      for handle in handles {
+        joined.extend(handle.join().unwrap());
+      }
+      // ================
+
+      joined.sort_by_key(|(n, _)| *n);
+      states = joined.into_iter().map(|(_, state)| state).collect();
+
+      // ===== stochastic approximation =====
+      let gamma = if smoothing {
+        1.0 / (n_iter - self.n_explore + 1) as f64
+      } else {
+        1.0
+      };
+      let mut new_s1 = Array1::<f64>::zeros(len_x);
+      let mut new_s2 = Array1::<f64>::zeros(len_x);
+      for state in states.iter() {
+        new_s1 = &new_s1 + &state.phi;
+        new_s2 = &new_s2 + &state.phi.mapv(|v| v * v);
+      }
+      s1 = &s1 + &((&(new_s1 / n_subjects) - &s1) * gamma);
+      s2 = &s2 + &((&(new_s2 / n_subjects) - &s2) * gamma);
+      s3 += gamma * (residual_stat(error_model, &states) - s3);
+
+      // ===== maximization step =====
+      mu = s1.clone();
+      let new_omega2 = (&s2 - &mu.mapv(|v| v * v)).mapv(|v| v.max(1e-10));
+      let new_sigma = (s3 / n_obs as f64).sqrt();
+      if smoothing {
+        omega2 = new_omega2;
+        sigma = new_sigma;
+      } else {
+        // simulated annealing to avoid an early collapse of variances
+        for k in 0..len_x {
+          omega2[k] = new_omega2[k].max(0.95 * omega2[k]);
+        }
+        sigma = new_sigma.max(0.95 * sigma);
+      }
+
+      if self.verbose {
+        println!(
+          "{:5}   theta:{:?}   omega2:{:?}   sigma:{:.4e}",
+          n_iter + 1,
+          mu.mapv(f64::exp).to_vec(),
+          omega2.to_vec(),
+          sigma
+        );
+      }
+      history.push((mu.clone(), omega2.clone(), sigma));
+    }
+
+    // empirical Bayes estimates (conditional means of ln x)
+    let n_smooth = self.n_smooth.max(1) as f64;
+    let eta: Vec<Array1<f64>> = states
+      .iter()
+      .map(|state| {
+        if self.n_smooth > 0 {
+          &state.sum_phi / n_smooth - &mu
+        } else {
+          &state.phi - &mu
+        }
+      })
+      .collect();
+
+    NlmeResult::new(
+      objective.x_index.clone(),
+      (mu, omega2, sigma),
+      error_model,
+      eta,
+      history,
+    )
+  }
+}
+
+// residuals and simulated values of quantified observations at phi
+fn residuals<M, const LEN_Y: usize, const LEN_P: usize, const LEN_B: usize, const LEN_X: usize>(
+  objective: &mut Objective<M, LEN_Y, LEN_P, LEN_B, LEN_X>,
+  quantified: &[usize],
+  phi: &Array1<f64>,
+) -> (Array1<f64>, Array1<f64>)
+where
+  M: OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X>,
+{
+  let res = objective.residuals(&phi.mapv(f64::exp));
+  let obs: Vec<f64> = quantified
+    .iter()
+    .map(|&j| objective.data.obs[j].2)
+    .collect();
+  let res: Array1<f64> = quantified.iter().map(|&j| res[j]).collect();
+  let sim = &Array1::from(obs) - &res;
+  (res, sim)
+}
+
+// ln p(y | phi) without constant terms
+fn loglik(error_model: ErrorModel, sigma: f64, (res, sim): &(Array1<f64>, Array1<f64>)) -> f64 {
+  let mut loglik = 0.0;
+  for (&r, &f) in res.iter().zip(sim.iter()) {
+    let sd = error_model.sd(sigma, f);
+    loglik -= sd.ln() + 0.5 * (r / sd).powi(2);
+  }
+  if loglik.is_nan() {
+    f64::NEG_INFINITY
+  } else {
+    loglik
+  }
+}
+
+// sum of squared standardized residuals (sigma = 1) over subjects
+fn residual_stat<
+  M,
+  const LEN_Y: usize,
+  const LEN_P: usize,
+  const LEN_B: usize,
+  const LEN_X: usize,
+>(
+  error_model: ErrorModel,
+  states: &[Subject<M, LEN_Y, LEN_P, LEN_B, LEN_X>],
+) -> f64
+where
+  M: OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X>,
+{
+  let mut stat = 0.0;
+  for state in states.iter() {
+    let (res, sim) = &state.res;
+    for (&r, &f) in res.iter().zip(sim.iter()) {
+      stat += (r / error_model.sd(1.0, f)).powi(2);
+    }
+  }
+  stat
+}
+
+// Metropolis-Hastings steps of a subject:
+// proposals from the population distribution, then component-wise random walks
+// with step sizes adapted toward the acceptance rate of 0.4.
+fn sample_subject<
+  M,
+  const LEN_Y: usize,
+  const LEN_P: usize,
+  const LEN_B: usize,
+  const LEN_X: usize,
+>(
+  state: &mut Subject<M, LEN_Y, LEN_P, LEN_B, LEN_X>,
+  (mu, omega2, sigma): (&Array1<f64>, &Array1<f64>, f64),
+  error_model: ErrorModel,
+) where
+  M: OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X>,
+{
+  let len_x = mu.len();
+  let omega = omega2.mapv(f64::sqrt);
+  let log_prior = |phi: &Array1<f64>| -> f64 {
+    (0..len_x)
+      .map(|k| -0.5 * (phi[k] - mu[k]).powi(2) / omega2[k])
+      .sum()
+  };
+  let mut ll = loglik(error_model, sigma, &state.res);
+
+  // kernel 1: independent proposals from N(mu, omega^2)
+  for _ in 0..Saem::N_KERNEL {
+    let new_phi: Array1<f64> = (0..len_x)
+      .map(|k| mu[k] + omega[k] * stats::randn(&mut state.rng))
+      .collect();
+    let new_res = residuals(&mut state.objective, &state.quantified, &new_phi);
+    let new_ll = loglik(error_model, sigma, &new_res);
+    if state.rng.gen_range(0.0f64..1.0).ln() < new_ll - ll {
+      state.phi = new_phi;
+      state.res = new_res;
+      ll = new_ll;
+    }
+  }
+
+  // kernel 2: component-wise random walks
+  for _ in 0..Saem::N_KERNEL {
+    for k in 0..len_x {
+      let mut new_phi = state.phi.clone();
+      new_phi[k] += state.delta[k] * omega[k] * stats::randn(&mut state.rng);
+      let new_res = residuals(&mut state.objective, &state.quantified, &new_phi);
+      let new_ll = loglik(error_model, sigma, &new_res);
+      let log_ratio = new_ll + log_prior(&new_phi) - ll - log_prior(&state.phi);
+      let accepted = state.rng.gen_range(0.0f64..1.0).ln() < log_ratio;
+      if accepted {
+        state.phi = new_phi;
+        state.res = new_res;
+        ll = new_ll;
+      }
+      let rate = if accepted { 1.0 } else { 0.0 };
+      state.delta[k] *= 1.0 + 0.4 * (rate - 0.4);
+    }
+  }
+}
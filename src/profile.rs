@@ -0,0 +1,5 @@
+mod base;
+mod result;
+
+pub use crate::profile::base::ProfileLikelihood;
+pub use crate::profile::result::{Profile, ProfileResult};
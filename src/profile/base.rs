@@ -0,0 +1,169 @@
+use super::result::{Profile, ProfileResult};
+
+use crate::model::OptModelTrait;
+use crate::objective::{Loss, Objective, Transform};
+use crate::optimizer::{OptResult, Optimizer};
+use crate::stats;
+
+use ndarray::Array1;
+use std::thread;
+
+// Profile likelihood of each parameter in x_index.
+// A parameter is stepped away from the optimum in both directions,
+// and the others are re-optimized by the optimizer at each step.
+// step is relative to the optimum for untransformed parameters
+// and in the transformed units (e.g. log10) otherwise.
+// Stepping stops after n_steps or when the profile crosses the threshold
+// of the likelihood-ratio confidence interval at the confidence level.
+pub struct ProfileLikelihood {
+  pub n_steps: usize,
+  pub step: f64,
+  pub level: f64,
+}
+
+impl ProfileLikelihood {
+  pub fn new(n_steps: usize, step: f64, level: f64) -> Self {
+    Self {
+      n_steps,
+      step,
+      level,
+    }
+  }
+
+  pub fn run<M, const LEN_Y: usize, const LEN_P: usize, const LEN_B: usize, const LEN_X: usize>(
+    &self,
+    objective: &mut Objective<M, LEN_Y, LEN_P, LEN_B, LEN_X>,
+    optimizer: &Optimizer,
+    optres: &OptResult,
+  ) -> ProfileResult
+  where
+    M: OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X>,
+  {
+    // set the optimum to a model
+    objective.setx(&optres.x);
+
+    let threshold = self.threshold(objective, optres);
+
+    // vector for join-handles
+    let mut handles = Vec::new();
+
+    for k in 0..objective.len_x {
+      for direction in [-1.0, 1.0] {
+        let thread_objective = objective.fix(k);
+        let thread_optimizer = optimizer.clone();
+        let (x_hat, f_hat) = (optres.x[k], optres.f);
+        let index = objective.x_index[k];
+        let transform = objective.x_transforms[k];
+        let bounds = objective.x_bounds.as_ref().map(|x_bounds| x_bounds[k]);
+        let (n_steps, step) = (self.n_steps, self.step);
+
+        // ===== FORK =====
+        let handle = thread::spawn(move || {
+          profile_one_side(
+            thread_objective,
+            &thread_optimizer,
+            (index, x_hat, f_hat),
+            (transform, bounds),
+            (direction, n_steps, step, threshold),
+          )
+        });
+        // ================
+
+        handles.push(handle);
+      }
+    }
+
+    // ===== JOIN =====
+    let mut sides = Vec::new();
+    for handle in handles {
+      sides.push(handle.join().unwrap());
+    }
+    // ================
+
+    let mut profiles = Vec::new();
+    for k in 0..objective.len_x {
+      let lower_side = &sides[2 * k];
+      let upper_side = &sides[2 * k + 1];
+      profiles.push(Profile::new(
+        objective.x_index[k],
+        (optres.x[k], optres.f),
+        lower_side,
+        upper_side,
+        threshold,
+      ));
+    }
+
+    ProfileResult::new(profiles, optres.f, threshold)
+  }
+
+  fn threshold<M, const LEN_Y: usize, const LEN_P: usize, const LEN_B: usize, const LEN_X: usize>(
+    &self,
+    objective: &Objective<M, LEN_Y, LEN_P, LEN_B, LEN_X>,
+    optres: &OptResult,
+  ) -> f64
+  where
+    M: OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X>,
+  {
+    // the objective is -2 ln L for the Gaussian loss.
+    // otherwise, it is scaled by the residual variance estimated at the optimum.
+    let chi2 = stats::chi2_1_ppf(self.level);
+    let dof = objective.data.obs.len() as f64 - objective.len_x as f64;
+
+    match objective.loss() {
+      Loss::Gaussian => chi2,
+      _ if dof > 0.0 => chi2 * optres.f_data / dof,
+      _ => chi2,
+    }
+  }
+}
+
+// step one parameter away from the optimum in a direction,
+// and return (value, f) at each step.
+fn profile_one_side<
+  M,
+  const LEN_Y: usize,
+  const LEN_P: usize,
+  const LEN_B: usize,
+  const LEN_X: usize,
+>(
+  mut objective: Objective<M, LEN_Y, LEN_P, LEN_B, LEN_X>,
+  optimizer: &Optimizer,
+  (index, x_hat, f_hat): (usize, f64, f64),
+  (transform, bounds): (Transform, Option<(f64, f64)>),
+  (direction, n_steps, step, threshold): (f64, usize, f64, f64),
+) -> Vec<(f64, f64)>
+where
+  M: OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X>,
+{
+  let z_hat = transform.to_z(x_hat, bounds);
+  let h = match transform {
+    Transform::Identity if x_hat != 0.0 => step * x_hat.abs(),
+    _ => step,
+  };
+
+  let mut side = Vec::new();
+  for i in 1..=n_steps {
+    let x = transform.to_x(z_hat + direction * (i as f64) * h, bounds);
+    if let Some((lb, ub)) = bounds {
+      if x < lb || ub < x {
+        break;
+      }
+    }
+    objective.simulator.model.setp(index, x);
+
+    // re-optimize the others starting from the previous step
+    let f = if objective.len_x == 0 {
+      objective.obj(&Array1::zeros(0))
+    } else {
+      let optres = optimizer.run(&mut objective);
+      objective.setx(&optres.x);
+      optres.f
+    };
+    side.push((x, f));
+
+    if f - f_hat > threshold {
+      break;
+    }
+  }
+  side
+}
@@ -0,0 +1,96 @@
+use std::path::Path;
+
+use crate::simulator::write_file;
+
+pub struct Profile {
+  pub index: usize,
+  pub x: Vec<f64>,
+  pub f: Vec<f64>,
+  pub estimate: f64,
+  pub lower: Option<f64>,
+  pub upper: Option<f64>,
+}
+
+impl Profile {
+  pub fn new(
+    index: usize,
+    (x_hat, f_hat): (f64, f64),
+    lower_side: &[(f64, f64)],
+    upper_side: &[(f64, f64)],
+    threshold: f64,
+  ) -> Self {
+    // confidence limits are interpolated where the profile crosses the threshold.
+    // None means the profile did not cross it (not identifiable in the range).
+    let lower = Self::crossing((x_hat, f_hat), lower_side, f_hat + threshold);
+    let upper = Self::crossing((x_hat, f_hat), upper_side, f_hat + threshold);
+
+    // x in ascending order
+    let mut points: Vec<(f64, f64)> = lower_side.iter().rev().cloned().collect();
+    points.push((x_hat, f_hat));
+    points.extend(upper_side.iter().cloned());
+
+    Self {
+      index,
+      x: points.iter().map(|p| p.0).collect(),
+      f: points.iter().map(|p| p.1).collect(),
+      estimate: x_hat,
+      lower,
+      upper,
+    }
+  }
+
+  fn crossing(optimum: (f64, f64), side: &[(f64, f64)], f_limit: f64) -> Option<f64> {
+    let mut prev = optimum;
+    for &(x, f) in side.iter() {
+      if f > f_limit {
+        let ratio = (f_limit - prev.1) / (f - prev.1);
+        return Some(prev.0 + ratio * (x - prev.0));
+      }
+      prev = (x, f);
+    }
+    None
+  }
+}
+
+pub struct ProfileResult {
+  pub profiles: Vec<Profile>,
+  pub f: f64,
+  pub threshold: f64,
+}
+
+impl ProfileResult {
+  pub fn new(profiles: Vec<Profile>, f: f64, threshold: f64) -> Self {
+    Self {
+      profiles,
+      f,
+      threshold,
+    }
+  }
+
+  pub fn save(&self, dir: &str) {
+    let save_dir = Path::new(dir);
+
+    // profiles: index, x, f, f - f_opt
+    let mut str_result = String::new();
+    for profile in self.profiles.iter() {
+      for (x, f) in profile.x.iter().zip(profile.f.iter()) {
+        str_result.push_str(&format!("{},{},{},{}\n", profile.index, x, f, f - self.f));
+      }
+    }
+    write_file(&save_dir.join("profile.csv"), &str_result);
+
+    // confidence intervals: index, estimate, lower, upper
+    let mut str_result = String::new();
+    let to_string = |limit: Option<f64>| limit.map_or(String::new(), |v| v.to_string());
+    for profile in self.profiles.iter() {
+      str_result.push_str(&format!(
+        "{},{},{},{}\n",
+        profile.index,
+        profile.estimate,
+        to_string(profile.lower),
+        to_string(profile.upper)
+      ));
+    }
+    write_file(&save_dir.join("profile_ci.csv"), &str_result);
+  }
+}
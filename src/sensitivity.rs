@@ -0,0 +1,11 @@
+mod base;
+mod local;
+mod morris;
+mod result;
+mod sequence;
+mod sobol;
+
+pub use crate::sensitivity::local::LocalSensitivity;
+pub use crate::sensitivity::morris::Morris;
+pub use crate::sensitivity::result::{LocalResult, MorrisResult, SobolResult};
+pub use crate::sensitivity::sobol::Sobol;
@@ -0,0 +1,133 @@
+use crate::model::OptModelTrait;
+use crate::objective::Transform;
+use crate::simulator::Simulator;
+
+use ndarray::Array1;
+use std::thread;
+
+// parameters in getx of a model and their ranges in the transformed space
+// (getx_transform), where parameters are sampled uniformly.
+pub struct ParameterSpace {
+  pub x_index: Vec<usize>,
+  x_bounds: Vec<(f64, f64)>,
+  x_transforms: Vec<Transform>,
+  z_bounds: Vec<(f64, f64)>,
+}
+
+impl ParameterSpace {
+  pub fn new<M, const LEN_Y: usize, const LEN_P: usize, const LEN_B: usize, const LEN_X: usize>(
+    model: &M,
+  ) -> Self
+  where
+    M: OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X>,
+  {
+    let (x_index, x_bounds) = model.getx();
+    let x_bounds = x_bounds.expect("please define lower and upper bounds.");
+    let x_transforms = model
+      .getx_transform()
+      .unwrap_or_else(|| vec![Transform::Identity; x_index.len()]);
+    let z_bounds = x_transforms
+      .iter()
+      .zip(x_bounds.iter())
+      .map(|(transform, &bounds)| transform.z_bounds(bounds))
+      .collect();
+
+    Self {
+      x_index,
+      x_bounds,
+      x_transforms,
+      z_bounds,
+    }
+  }
+
+  pub fn len_x(&self) -> usize {
+    self.x_index.len()
+  }
+
+  // x at a point u in the unit hypercube
+  pub fn to_x(&self, u: &[f64]) -> Array1<f64> {
+    (0..self.len_x())
+      .map(|k| {
+        let (lb, ub) = self.z_bounds[k];
+        self.x_transforms[k].to_x(lb + u[k] * (ub - lb), Some(self.x_bounds[k]))
+      })
+      .collect()
+  }
+}
+
+// outputs y[y_index] at t of the simulator for each x, evaluated in parallel.
+// outputs[i] = (y_index, t), and the result is [x][output].
+pub fn simulate_outputs<
+  M,
+  const LEN_Y: usize,
+  const LEN_P: usize,
+  const LEN_B: usize,
+  const LEN_X: usize,
+>(
+  simulator: &Simulator<M, LEN_Y, LEN_P, LEN_B>,
+  x_index: &[usize],
+  xs: Vec<Array1<f64>>,
+  outputs: &[(usize, f64)],
+) -> Vec<Vec<f64>>
+where
+  M: OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X>,
+{
+  // sampling times and (t_index, y_index) of outputs
+  let mut vec_smp_t: Vec<f64> = outputs.iter().map(|&(_, t)| t).collect();
+  vec_smp_t.sort_by(|a, b| a.partial_cmp(b).unwrap());
+  vec_smp_t.dedup();
+  let ty_index: Vec<(usize, usize)> = outputs
+    .iter()
+    .map(|&(y_index, t)| (vec_smp_t.iter().position(|&s| s == t).unwrap(), y_index))
+    .collect();
+
+  let n_threads = thread::available_parallelism().map_or(1, |n| n.get());
+  let n_x = xs.len();
+
+  // vector for join-handles
+  let mut handles = Vec::new();
+
+  for n_thread in 0..n_threads {
+    let mut thread_simulator = simulator.clone();
+    let thread_xs: Vec<(usize, Array1<f64>)> = xs
+      .iter()
+      .cloned()
+      .enumerate()
+      .skip(n_thread)
+      .step_by(n_threads)
+      .collect();
+    let (thread_x_index, thread_smp_t, thread_ty_index) =
+      (x_index.to_vec(), vec_smp_t.clone(), ty_index.clone());
+
+    // ===== FORK =====
+    let handle = thread::spawn(move || {
+      let mut values = Vec::new();
+      for (n, x) in thread_xs.into_iter() {
+        for (&index, &value) in thread_x_index.iter().zip(x.iter()) {
+          thread_simulator.model.setp(index, value);
+        }
+        let simres = thread_simulator.run(&thread_smp_t);
+        let y: Vec<f64> = thread_ty_index
+          .iter()
+          .map(|&(t_index, y_index)| simres.y[t_index][y_index])
+          .collect();
+        values.push((n, y));
+      }
+      values
+    });
+    // ================
+
+    handles.push(handle);
+  }
+
+  // ===== JOIN =====
+  let mut values = vec![Vec::new(); n_x];
+  for handle in handles {
+    for (n, y) in handle.join().unwrap() {
+      values[n] = y;
+    }
+  }
+  // ================
+
+  values
+}
@@ -0,0 +1,112 @@
+use super::result::LocalResult;
+
+use crate::model::SimModelTrait;
+use crate::simulator::Simulator;
+
+use std::thread;
+
+// Normalized local sensitivity coefficients d ln y / d ln p of every state
+// at each sampling time, for the parameters p[index] of a model.
+// scale(model, index, factor) multiplies p[index] of a model by factor,
+// so that any SimModelTrait model can be analyzed without getp/setp.
+// each parameter is perturbed by p * (1 +- rel_step) around the values in a model
+// (central differences), and parameters are evaluated in parallel.
+// coefficients are 0 where y does not change (e.g. for parameters at 0),
+// and NaN where y is 0 but changes, since ln y is undefined there.
+pub struct LocalSensitivity {
+  pub rel_step: f64,
+  pub index: Vec<usize>,
+}
+
+impl LocalSensitivity {
+  pub fn new(rel_step: f64, index: Vec<usize>) -> Self {
+    Self { rel_step, index }
+  }
+
+  pub fn run<M, F, const LEN_Y: usize, const LEN_P: usize, const LEN_B: usize>(
+    &self,
+    simulator: &Simulator<M, LEN_Y, LEN_P, LEN_B>,
+    smp_t: &[f64],
+    scale: F,
+  ) -> LocalResult<LEN_Y>
+  where
+    M: SimModelTrait<LEN_Y, LEN_P, LEN_B> + Clone + Send + 'static,
+    F: Fn(&mut M, usize, f64) + Clone + Send + 'static,
+  {
+    if let Some(&index) = self.index.iter().find(|&&index| index >= LEN_P) {
+      panic!("p[{}] is out of range.", index);
+    }
+
+    let simres = simulator.run(smp_t);
+
+    // the parameters are distributed among a fixed number of threads
+    let n_threads = thread::available_parallelism().map_or(1, |n| n.get());
+
+    // vector for join-handles
+    let mut handles = Vec::new();
+
+    for n_thread in 0..n_threads {
+      let thread_simulator = simulator.clone();
+      let thread_index: Vec<(usize, usize)> = self
+        .index
+        .iter()
+        .copied()
+        .enumerate()
+        .skip(n_thread)
+        .step_by(n_threads)
+        .collect();
+      let thread_smp_t = smp_t.to_vec();
+      let thread_scale = scale.clone();
+      let rel_step = self.rel_step;
+
+      // ===== FORK =====
+      let handle = thread::spawn(move || {
+        let mut y_pert = Vec::new();
+        for (k, index) in thread_index {
+          let mut y_k = Vec::new();
+          for factor in [1.0 + rel_step, 1.0 - rel_step] {
+            let mut pert_simulator = thread_simulator.clone();
+            thread_scale(&mut pert_simulator.model, index, factor);
+            y_k.push(pert_simulator.run(&thread_smp_t).y);
+          }
+          y_pert.push((k, y_k));
+        }
+        y_pert
+      });
+      // ================
+
+      handles.push(handle);
+    }
+
+    // ===== JOIN =====
+    let mut y_pert = Vec::new();
+    for handle in handles {
+      y_pert.extend(handle.join().unwrap());
+    }
+    y_pert.sort_by_key(|&(k, _)| k);
+    // ================
+
+    let mut coef = Vec::new();
+    for (_, y_k) in y_pert.iter() {
+      let mut coef_p = Vec::new();
+      for (i, y) in simres.y.iter().enumerate() {
+        let mut coef_t = [0f64; LEN_Y];
+        for j in 0..LEN_Y {
+          let diff = y_k[0][i][j] - y_k[1][i][j];
+          // unchanged outputs (e.g. parameters at 0) have no sensitivity
+          coef_t[j] = if diff == 0.0 {
+            0.0
+          } else if y[j] == 0.0 {
+            f64::NAN
+          } else {
+            diff / (2.0 * self.rel_step * y[j])
+          };
+        }
+        coef_p.push(coef_t);
+      }
+      coef.push(coef_p);
+    }
+
+    LocalResult::new(simres.t, self.index.clone(), coef)
+  }
+}
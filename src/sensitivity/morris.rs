@@ -0,0 +1,120 @@
+use super::base::{simulate_outputs, ParameterSpace};
+use super::result::MorrisResult;
+
+use crate::model::OptModelTrait;
+use crate::simulator::Simulator;
+use crate::stats;
+
+use ndarray::Array2;
+use rand::seq::SliceRandom;
+
+// Morris elementary effects of outputs y[y_index] at t for screening.
+// each trajectory starts at a random point of a grid with n_levels levels
+// over the bounds in the transformed space, and moves each parameter by
+// delta = n_levels / (2 * (n_levels - 1)) of its range in random order,
+// which needs n_trajectories * (len_x + 1) simulations.
+// a parameter starts only at levels from which it can move by delta within
+// its range (e.g. 0 and 1 for n_levels = 3), so every point is in the bounds.
+// effects are per unit range, so they are comparable between parameters.
+pub struct Morris {
+  pub n_trajectories: usize,
+  pub n_levels: usize,
+  pub outputs: Vec<(usize, f64)>,
+  pub seed: Option<u64>,
+}
+
+impl Morris {
+  pub fn new(
+    n_trajectories: usize,
+    n_levels: usize,
+    outputs: Vec<(usize, f64)>,
+    seed: Option<u64>,
+  ) -> Self {
+    Self {
+      n_trajectories,
+      n_levels,
+      outputs,
+      seed,
+    }
+  }
+
+  pub fn run<M, const LEN_Y: usize, const LEN_P: usize, const LEN_B: usize, const LEN_X: usize>(
+    &self,
+    simulator: &Simulator<M, LEN_Y, LEN_P, LEN_B>,
+  ) -> MorrisResult
+  where
+    M: OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X>,
+  {
+    if self.n_levels < 2 {
+      panic!("n_levels must be 2 or more.");
+    }
+
+    let space = ParameterSpace::new(&simulator.model);
+    let len_x = space.len_x();
+    let mut rng = stats::make_rng(self.seed, 0);
+
+    let levels = (self.n_levels - 1) as f64;
+    let delta = self.n_levels as f64 / (2.0 * levels);
+
+    // levels where u + delta or u - delta is in [0, 1]
+    let tol = 1e-12;
+    let base_levels: Vec<f64> = (0..self.n_levels)
+      .map(|i| i as f64 / levels)
+      .filter(|&u| u + delta <= 1.0 + tol || u - delta >= -tol)
+      .collect();
+
+    // trajectories: points and (moved parameter, signed step) of each move
+    let mut xs = Vec::new();
+    let mut moves = Vec::new();
+    for _ in 0..self.n_trajectories {
+      let mut u: Vec<f64> = (0..len_x)
+        .map(|_| *base_levels.choose(&mut rng).unwrap())
+        .collect();
+      xs.push(space.to_x(&u));
+
+      let mut order: Vec<usize> = (0..len_x).collect();
+      order.shuffle(&mut rng);
+      for k in order {
+        let step = if u[k] + delta <= 1.0 + tol {
+          delta
+        } else {
+          -delta
+        };
+        u[k] = (u[k] + step).clamp(0.0, 1.0);
+        xs.push(space.to_x(&u));
+        moves.push((k, step));
+      }
+    }
+
+    let values = simulate_outputs(simulator, &space.x_index, xs, &self.outputs);
+
+    // elementary effects [output][parameter][trajectory]
+    let n_outputs = self.outputs.len();
+    let mut effects = vec![vec![Vec::new(); len_x]; n_outputs];
+    for r in 0..self.n_trajectories {
+      for j in 0..len_x {
+        let (k, step) = moves[r * len_x + j];
+        let prev = &values[r * (len_x + 1) + j];
+        let next = &values[r * (len_x + 1) + j + 1];
+        for o in 0..n_outputs {
+          effects[o][k].push((next[o] - prev[o]) / step);
+        }
+      }
+    }
+
+    let mut mu = Array2::zeros((n_outputs, len_x));
+    let mut mu_star = Array2::zeros((n_outputs, len_x));
+    let mut sigma = Array2::zeros((n_outputs, len_x));
+    for o in 0..n_outputs {
+      for k in 0..len_x {
+        let ee = &effects[o][k];
+        let abs_ee: Vec<f64> = ee.iter().map(|e| e.abs()).collect();
+        mu[[o, k]] = stats::mean(ee);
+        mu_star[[o, k]] = stats::mean(&abs_ee);
+        sigma[[o, k]] = stats::sd(ee);
+      }
+    }
+
+    MorrisResult::new(space.x_index, self.outputs.clone(), mu, mu_star, sigma)
+  }
+}
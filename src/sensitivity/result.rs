@@ -0,0 +1,132 @@
+use std::path::Path;
+
+use crate::simulator::write_file;
+
+use ndarray::Array2;
+
+// Sobol indices [output][parameter] and the variance of each output.
+pub struct SobolResult {
+  pub index: Vec<usize>,
+  pub outputs: Vec<(usize, f64)>,
+  pub variance: Vec<f64>,
+  pub first: Array2<f64>,
+  pub total: Array2<f64>,
+}
+
+impl SobolResult {
+  pub fn new(
+    index: Vec<usize>,
+    outputs: Vec<(usize, f64)>,
+    variance: Vec<f64>,
+    first: Array2<f64>,
+    total: Array2<f64>,
+  ) -> Self {
+    Self {
+      index,
+      outputs,
+      variance,
+      first,
+      total,
+    }
+  }
+
+  pub fn save(&self, dir: &str) {
+    let save_dir = Path::new(dir);
+
+    // y_index, t, index, first, total
+    let mut str_result = String::new();
+    for (o, (y_index, t)) in self.outputs.iter().enumerate() {
+      for (k, index) in self.index.iter().enumerate() {
+        str_result.push_str(&format!(
+          "{},{},{},{},{}\n",
+          y_index,
+          t,
+          index,
+          self.first[[o, k]],
+          self.total[[o, k]]
+        ));
+      }
+    }
+    write_file(&save_dir.join("sobol.csv"), &str_result);
+  }
+}
+
+// statistics of elementary effects [output][parameter]:
+// mean (mu), mean of absolute values (mu_star) and standard deviation (sigma).
+pub struct MorrisResult {
+  pub index: Vec<usize>,
+  pub outputs: Vec<(usize, f64)>,
+  pub mu: Array2<f64>,
+  pub mu_star: Array2<f64>,
+  pub sigma: Array2<f64>,
+}
+
+impl MorrisResult {
+  pub fn new(
+    index: Vec<usize>,
+    outputs: Vec<(usize, f64)>,
+    mu: Array2<f64>,
+    mu_star: Array2<f64>,
+    sigma: Array2<f64>,
+  ) -> Self {
+    Self {
+      index,
+      outputs,
+      mu,
+      mu_star,
+      sigma,
+    }
+  }
+
+  pub fn save(&self, dir: &str) {
+    let save_dir = Path::new(dir);
+
+    // y_index, t, index, mu, mu_star, sigma
+    let mut str_result = String::new();
+    for (o, (y_index, t)) in self.outputs.iter().enumerate() {
+      for (k, index) in self.index.iter().enumerate() {
+        str_result.push_str(&format!(
+          "{},{},{},{},{},{}\n",
+          y_index,
+          t,
+          index,
+          self.mu[[o, k]],
+          self.mu_star[[o, k]],
+          self.sigma[[o, k]]
+        ));
+      }
+    }
+    write_file(&save_dir.join("morris.csv"), &str_result);
+  }
+}
+
+// normalized sensitivity coefficients [parameter][time][state].
+pub struct LocalResult<const LEN_Y: usize> {
+  pub t: Vec<f64>,
+  pub index: Vec<usize>,
+  pub coef: Vec<Vec<[f64; LEN_Y]>>,
+}
+
+impl<const LEN_Y: usize> LocalResult<LEN_Y> {
+  pub fn new(t: Vec<f64>, index: Vec<usize>, coef: Vec<Vec<[f64; LEN_Y]>>) -> Self {
+    Self { t, index, coef }
+  }
+
+  pub fn save(&self, dir: &str) {
+    let save_dir = Path::new(dir);
+
+    // index, t, coefficients of each state
+    let mut str_result = String::new();
+    for (index, coef_p) in self.index.iter().zip(self.coef.iter()) {
+      for (t, coef_t) in self.t.iter().zip(coef_p.iter()) {
+        str_result.push_str(&format!("{},{}", index, t));
+        for j in 0..LEN_Y {
+          str_result.push(',');
+          str_result.push_str(&coef_t[j].to_string());
+        }
+        str_result.push('\n');
+      }
+    }
+    write_file(&save_dir.join("local_sensitivity.csv"), &str_result);
+  }
+}
@@ -0,0 +1,112 @@
+// Sobol low-discrepancy sequence in the unit hypercube
+// with direction numbers of Joe and Kuo (new-joe-kuo-6.21201).
+pub struct SobolSequence {
+  dim: usize,
+  index: u64,
+  directions: Vec<[u32; SobolSequence::BITS]>,
+  state: Vec<u32>,
+}
+
+impl SobolSequence {
+  const BITS: usize = 32;
+  pub const MAX_DIM: usize = 1 + JOE_KUO.len();
+
+  pub fn new(dim: usize) -> Self {
+    if dim > Self::MAX_DIM {
+      panic!(
+        "Sobol sequence supports up to {} dimensions.",
+        Self::MAX_DIM
+      );
+    }
+
+    let mut directions = Vec::new();
+
+    // the first dimension is the van der Corput sequence
+    let mut v = [0u32; Self::BITS];
+    for (i, v_i) in v.iter_mut().enumerate() {
+      *v_i = 1 << (Self::BITS - 1 - i);
+    }
+    directions.push(v);
+
+    for &(s, a, m) in JOE_KUO.iter().take(dim.saturating_sub(1)) {
+      let mut v = [0u32; Self::BITS];
+      for i in 0..s.min(Self::BITS) {
+        v[i] = m[i] << (Self::BITS - 1 - i);
+      }
+      for i in s..Self::BITS {
+        v[i] = v[i - s] ^ (v[i - s] >> s);
+        for k in 1..s {
+          if (a >> (s - 1 - k)) & 1 == 1 {
+            v[i] ^= v[i - k];
+          }
+        }
+      }
+      directions.push(v);
+    }
+
+    Self {
+      dim,
+      index: 0,
+      directions,
+      state: vec![0; dim],
+    }
+  }
+
+  // next point (the first point at the origin is skipped).
+  pub fn next_point(&mut self) -> Vec<f64> {
+    // position of the lowest zero bit of the index (Gray code order)
+    let c = (!self.index).trailing_zeros() as usize;
+    self.index += 1;
+    for j in 0..self.dim {
+      self.state[j] ^= self.directions[j][c];
+    }
+    self
+      .state
+      .iter()
+      .map(|&s| s as f64 / 2f64.powi(Self::BITS as i32))
+      .collect()
+  }
+}
+
+// (degree, coefficients, initial direction numbers) from the second dimension
+const JOE_KUO: [(usize, u32, &[u32]); 39] = [
+  (1, 0, &[1]),
+  (2, 1, &[1, 3]),
+  (3, 1, &[1, 3, 1]),
+  (3, 2, &[1, 1, 1]),
+  (4, 1, &[1, 1, 3, 3]),
+  (4, 4, &[1, 3, 5, 13]),
+  (5, 2, &[1, 1, 5, 5, 17]),
+  (5, 4, &[1, 1, 5, 5, 5]),
+  (5, 7, &[1, 1, 7, 11, 19]),
+  (5, 11, &[1, 1, 5, 1, 1]),
+  (5, 13, &[1, 1, 1, 3, 11]),
+  (5, 14, &[1, 3, 5, 5, 31]),
+  (6, 1, &[1, 3, 3, 9, 7, 49]),
+  (6, 13, &[1, 1, 1, 15, 21, 21]),
+  (6, 16, &[1, 3, 1, 13, 27, 49]),
+  (6, 19, &[1, 1, 1, 15, 7, 5]),
+  (6, 22, &[1, 3, 1, 15, 13, 25]),
+  (6, 25, &[1, 1, 5, 5, 19, 61]),
+  (7, 1, &[1, 3, 7, 11, 23, 15, 103]),
+  (7, 4, &[1, 3, 7, 13, 13, 15, 69]),
+  (7, 7, &[1, 1, 3, 13, 7, 35, 63]),
+  (7, 8, &[1, 3, 5, 9, 1, 25, 53]),
+  (7, 14, &[1, 3, 1, 13, 9, 35, 107]),
+  (7, 19, &[1, 3, 1, 5, 27, 61, 31]),
+  (7, 21, &[1, 1, 5, 11, 19, 41, 61]),
+  (7, 28, &[1, 3, 5, 3, 3, 13, 69]),
+  (7, 31, &[1, 1, 7, 13, 1, 19, 1]),
+  (7, 32, &[1, 3, 7, 5, 13, 19, 59]),
+  (7, 37, &[1, 1, 3, 9, 25, 29, 41]),
+  (7, 41, &[1, 3, 5, 13, 23, 1, 55]),
+  (7, 42, &[1, 3, 7, 3, 13, 59, 17]),
+  (7, 50, &[1, 3, 1, 3, 5, 53, 69]),
+  (7, 55, &[1, 1, 5, 5, 23, 33, 13]),
+  (7, 56, &[1, 1, 7, 7, 1, 61, 123]),
+  (7, 59, &[1, 1, 7, 9, 13, 61, 49]),
+  (7, 62, &[1, 3, 3, 5, 3, 55, 33]),
+  (8, 14, &[1, 3, 1, 15, 31, 13, 49, 245]),
+  (8, 21, &[1, 3, 5, 15, 31, 59, 63, 97]),
+  (8, 22, &[1, 3, 1, 11, 11, 11, 77, 249]),
+];
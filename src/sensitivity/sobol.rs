@@ -0,0 +1,87 @@
+use super::base::{simulate_outputs, ParameterSpace};
+use super::result::SobolResult;
+use super::sequence::SobolSequence;
+
+use crate::model::OptModelTrait;
+use crate::simulator::Simulator;
+
+use ndarray::Array2;
+
+// Variance-based global sensitivity (Sobol indices) of outputs y[y_index] at t.
+// parameters in getx are sampled uniformly over the bounds in the transformed space
+// by the Saltelli scheme on a Sobol sequence, which needs n_samples * (len_x + 2)
+// simulations. first-order indices are estimated by Saltelli (2010),
+// and total-order indices by Jansen (1999).
+pub struct Sobol {
+  pub n_samples: usize,
+  pub outputs: Vec<(usize, f64)>,
+}
+
+impl Sobol {
+  pub fn new(n_samples: usize, outputs: Vec<(usize, f64)>) -> Self {
+    Self { n_samples, outputs }
+  }
+
+  pub fn run<M, const LEN_Y: usize, const LEN_P: usize, const LEN_B: usize, const LEN_X: usize>(
+    &self,
+    simulator: &Simulator<M, LEN_Y, LEN_P, LEN_B>,
+  ) -> SobolResult
+  where
+    M: OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X>,
+  {
+    let space = ParameterSpace::new(&simulator.model);
+    let len_x = space.len_x();
+    let n = self.n_samples;
+
+    // matrices A and B from the two halves of a 2 * len_x dimensional sequence,
+    // and AB_k (A with the k-th column from B)
+    let mut sequence = SobolSequence::new(2 * len_x);
+    let mut xs = Vec::new();
+    let mut ab = Vec::new();
+    for _ in 0..n {
+      let u = sequence.next_point();
+      let (a, b) = u.split_at(len_x);
+      xs.push(space.to_x(a));
+      xs.push(space.to_x(b));
+      for k in 0..len_x {
+        let mut a_k = a.to_vec();
+        a_k[k] = b[k];
+        ab.push(space.to_x(&a_k));
+      }
+    }
+    xs.extend(ab);
+
+    let values = simulate_outputs(simulator, &space.x_index, xs, &self.outputs);
+    let f_a = |i: usize, o: usize| values[2 * i][o];
+    let f_b = |i: usize, o: usize| values[2 * i + 1][o];
+    let f_ab = |i: usize, k: usize, o: usize| values[2 * n + i * len_x + k][o];
+
+    let n_outputs = self.outputs.len();
+    let mut first = Array2::zeros((n_outputs, len_x));
+    let mut total = Array2::zeros((n_outputs, len_x));
+    let mut variance = vec![0.0; n_outputs];
+
+    for o in 0..n_outputs {
+      // variance of outputs over A and B
+      let mean = (0..n).map(|i| f_a(i, o) + f_b(i, o)).sum::<f64>() / (2 * n) as f64;
+      let var = (0..n)
+        .map(|i| (f_a(i, o) - mean).powi(2) + (f_b(i, o) - mean).powi(2))
+        .sum::<f64>()
+        / (2 * n - 1) as f64;
+      variance[o] = var;
+
+      for k in 0..len_x {
+        let mut sum_first = 0.0;
+        let mut sum_total = 0.0;
+        for i in 0..n {
+          sum_first += f_b(i, o) * (f_ab(i, k, o) - f_a(i, o));
+          sum_total += (f_a(i, o) - f_ab(i, k, o)).powi(2);
+        }
+        first[[o, k]] = sum_first / n as f64 / var;
+        total[[o, k]] = 0.5 * sum_total / n as f64 / var;
+      }
+    }
+
+    SobolResult::new(space.x_index, self.outputs.clone(), variance, first, total)
+  }
+}
@@ -1,5 +1,16 @@
+mod covariate;
+mod delay;
+mod ensemble;
 mod fixed;
+mod history;
+mod periodic;
 mod result;
+mod steady;
 
+pub use crate::simulator::ensemble::EnsembleResult;
 pub use crate::simulator::fixed::Simulator;
+pub use crate::simulator::history::History;
+pub use crate::simulator::periodic::PeriodicResult;
 pub use crate::simulator::result::SimResult;
+
+pub(crate) use crate::simulator::result::write_file;
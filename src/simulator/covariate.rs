@@ -0,0 +1,31 @@
+use super::fixed::Simulator;
+
+use crate::model::{OptModelTrait, SimModelTrait};
+
+impl<M, const LEN_Y: usize, const LEN_P: usize, const LEN_B: usize>
+  Simulator<M, LEN_Y, LEN_P, LEN_B>
+where
+  M: SimModelTrait<LEN_Y, LEN_P, LEN_B>,
+{
+  // simulator of a subject with covariates (name, value), in which
+  // the parameters in OptModelTrait::covariates are multiplied by the effects.
+  pub fn with_covariates<const LEN_X: usize>(&self, covariates: &[(String, f64)]) -> Self
+  where
+    M: OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X>,
+  {
+    let mut simulator = self.clone();
+    for covariate in self.model.covariates().iter() {
+      let value = covariates
+        .iter()
+        .find(|(name, _)| *name == covariate.name)
+        .map(|&(_, value)| value)
+        .unwrap_or_else(|| panic!("covariate '{}' is not given.", covariate.name));
+
+      let p = simulator.model.getp()[covariate.index];
+      simulator
+        .model
+        .setp(covariate.index, p * covariate.effect(value));
+    }
+    simulator
+  }
+}
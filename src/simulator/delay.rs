@@ -0,0 +1,57 @@
+use super::fixed::Simulator;
+use super::history::History;
+use super::result::SimResult;
+
+use crate::model::DdeModelTrait;
+
+use std::cell::RefCell;
+
+// order of discontinuities tracked,
+// since discontinuities are smoothed as they propagate by delays.
+const DISCONTINUITY_ORDER: usize = 5;
+
+impl<M, const LEN_Y: usize, const LEN_P: usize, const LEN_B: usize>
+  Simulator<M, LEN_Y, LEN_P, LEN_B>
+where
+  M: DdeModelTrait<LEN_Y, LEN_P, LEN_B>,
+{
+  // simulation of DdeModelTrait::dde.
+  // the history is initialized by the constant state of init.
+  pub fn run_dde(&self, smp_t: &[f64]) -> SimResult<LEN_Y> {
+    let (ini_t, ini_y) = self.model.init();
+    let delays = self.model.delays();
+    if delays.iter().any(|&tau| tau <= 0.0) {
+      panic!("delays must be positive.");
+    }
+    let max_delay = delays.iter().cloned().fold(0.0, f64::max);
+    let history = RefCell::new(History::new(ini_t, ini_y, max_delay));
+
+    self.simulate(
+      ini_t,
+      ini_y,
+      smp_t,
+      |t, y, _, dy| self.model.dde(t, y, &history.borrow(), dy),
+      &discontinuities(ini_t, &delays),
+      Some(|t: &f64, y: &[f64; LEN_Y], dy: &[f64; LEN_Y]| {
+        history.borrow_mut().push(*t, *y, *dy);
+      }),
+    )
+  }
+}
+
+// discontinuities propagated from ini_t by sums of delays.
+fn discontinuities(ini_t: f64, delays: &[f64]) -> Vec<f64> {
+  let mut discontinuities = Vec::new();
+  let mut level = vec![ini_t];
+  for _ in 0..DISCONTINUITY_ORDER {
+    let mut next_level: Vec<f64> = level
+      .iter()
+      .flat_map(|t| delays.iter().map(move |tau| t + tau))
+      .collect();
+    next_level.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    next_level.dedup();
+    discontinuities.extend_from_slice(&next_level);
+    level = next_level;
+  }
+  discontinuities
+}
@@ -0,0 +1,146 @@
+use super::fixed::Simulator;
+use super::result::SimResult;
+
+use crate::model::{OptModelTrait, SimModelTrait};
+use crate::stats;
+
+use ndarray::Array1;
+use std::thread;
+
+// summary of simulations over parameter samples at each sampling time.
+// trajectories with non-finite values (e.g. failed simulations) are dropped
+// and counted in n_dropped.
+pub struct EnsembleResult<const LEN_Y: usize> {
+  pub n_samples: usize,
+  pub n_dropped: usize,
+  pub mean: SimResult<LEN_Y>,
+  pub q05: SimResult<LEN_Y>,
+  pub q50: SimResult<LEN_Y>,
+  pub q95: SimResult<LEN_Y>,
+}
+
+impl<const LEN_Y: usize> EnsembleResult<LEN_Y> {
+  pub fn new(trajectories: &[SimResult<LEN_Y>]) -> Self {
+    let finite: Vec<&SimResult<LEN_Y>> = trajectories
+      .iter()
+      .filter(|simres| simres.y.iter().flatten().all(|v| v.is_finite()))
+      .collect();
+
+    // statistics over samples at each time and state
+    let t = trajectories[0].t.clone();
+    let n_t = trajectories[0].y.len();
+    let mut mean = vec![[0f64; LEN_Y]; n_t];
+    let mut q05 = vec![[0f64; LEN_Y]; n_t];
+    let mut q50 = vec![[0f64; LEN_Y]; n_t];
+    let mut q95 = vec![[0f64; LEN_Y]; n_t];
+    for i in 0..n_t {
+      for j in 0..LEN_Y {
+        let mut values: Vec<f64> = finite.iter().map(|simres| simres.y[i][j]).collect();
+        values.sort_by(|a, b| a.total_cmp(b));
+        mean[i][j] = stats::mean(&values);
+        q05[i][j] = stats::quantile(&values, 0.05);
+        q50[i][j] = stats::quantile(&values, 0.5);
+        q95[i][j] = stats::quantile(&values, 0.95);
+      }
+    }
+
+    Self {
+      n_samples: finite.len(),
+      n_dropped: trajectories.len() - finite.len(),
+      mean: SimResult::new(t.clone(), mean),
+      q05: SimResult::new(t.clone(), q05),
+      q50: SimResult::new(t.clone(), q50),
+      q95: SimResult::new(t, q95),
+    }
+  }
+
+  pub fn save(&self, dir: &str) {
+    self.mean.save_as(dir, "ensemble_mean.csv");
+    self.q05.save_as(dir, "ensemble_q05.csv");
+    self.q50.save_as(dir, "ensemble_q50.csv");
+    self.q95.save_as(dir, "ensemble_q95.csv");
+  }
+}
+
+impl<M, const LEN_Y: usize, const LEN_P: usize, const LEN_B: usize>
+  Simulator<M, LEN_Y, LEN_P, LEN_B>
+where
+  M: SimModelTrait<LEN_Y, LEN_P, LEN_B>,
+{
+  // simulate with each parameter sample (values of p[index], e.g. bootstrap
+  // or MCMC samples of x) in parallel, and summarize the trajectories by
+  // the mean and the 5, 50 and 95 percentiles of every state.
+  pub fn ensemble<const LEN_X: usize>(
+    &self,
+    smp_t: &[f64],
+    index: &[usize],
+    samples: &[Array1<f64>],
+  ) -> EnsembleResult<LEN_Y>
+  where
+    M: OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X>,
+  {
+    let trajectories = self.simulate_samples(smp_t, index, samples);
+    EnsembleResult::new(&trajectories)
+  }
+
+  // simulate with each parameter sample in parallel.
+  // trajectories are in the same order as samples.
+  // SDE steppers use the n-th stream of their seed for sample n.
+  pub(crate) fn simulate_samples<const LEN_X: usize>(
+    &self,
+    smp_t: &[f64],
+    index: &[usize],
+    samples: &[Array1<f64>],
+  ) -> Vec<SimResult<LEN_Y>>
+  where
+    M: OptModelTrait<LEN_Y, LEN_P, LEN_B, LEN_X>,
+  {
+    if samples.is_empty() {
+      panic!("please give one or more parameter samples.");
+    }
+
+    let n_threads = thread::available_parallelism().map_or(1, |n| n.get());
+
+    // vector for join-handles
+    let mut handles = Vec::new();
+
+    for n_thread in 0..n_threads {
+      let mut thread_simulator = self.clone();
+      let thread_samples: Vec<(usize, Array1<f64>)> = samples
+        .iter()
+        .cloned()
+        .enumerate()
+        .skip(n_thread)
+        .step_by(n_threads)
+        .collect();
+      let (thread_index, thread_smp_t) = (index.to_vec(), smp_t.to_vec());
+      let stepper = self.stepper.clone();
+
+      // ===== FORK =====
+      let handle = thread::spawn(move || {
+        let mut trajectories = Vec::new();
+        for (n, x) in thread_samples.iter() {
+          for (&index, &value) in thread_index.iter().zip(x.iter()) {
+            thread_simulator.model.setp(index, value);
+          }
+          thread_simulator.stepper = stepper.with_stream(*n as u64);
+          trajectories.push((*n, thread_simulator.run(&thread_smp_t)));
+        }
+        trajectories
+      });
+      // ================
+
+      handles.push(handle);
+    }
+
+    // ===== JOIN =====
+    let mut trajectories = Vec::new();
+    for handle in handles {
+      trajectories.extend(handle.join().unwrap());
+    }
+    // ================
+
+    trajectories.sort_by_key(|(n, _)| *n);
+    trajectories.into_iter().map(|(_, simres)| simres).collect()
+  }
+}
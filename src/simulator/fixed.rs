@@ -1,13 +1,18 @@
 use super::result::SimResult;
 
+use crate::dosing::DosingRegimen;
+use crate::input::Input;
 use crate::model::SimModelTrait;
 use crate::stepper::{ConcreteStepper, Stepper};
 
 use core::str::FromStr;
-use num_traits::ToPrimitive;
 use rust_decimal::Decimal;
+use std::cell::Cell;
 use std::collections::VecDeque;
 
+// (ini_t, end_t, stopped, next_t) of beats in Decimal
+type DecTimes<const LEN_B: usize> = (Decimal, Decimal, Decimal, [Decimal; LEN_B]);
+
 #[derive(Clone)]
 pub struct Simulator<M, const LEN_Y: usize, const LEN_P: usize, const LEN_B: usize>
 where
@@ -15,6 +20,8 @@ where
 {
   pub model: M,
   pub stepper: Stepper,
+  pub regimen: DosingRegimen,
+  pub inputs: Vec<Input>,
 }
 
 impl<M, const LEN_Y: usize, const LEN_P: usize, const LEN_B: usize>
@@ -23,12 +30,76 @@ where
   M: SimModelTrait<LEN_Y, LEN_P, LEN_B>,
 {
   pub fn new(model: M, stepper: Stepper) -> Self {
-    Self { model, stepper }
+    Self {
+      model,
+      stepper,
+      regimen: DosingRegimen::new(),
+      inputs: Vec::new(),
+    }
   }
 
-  pub fn run(&self, smp_t: &Vec<f64>) -> SimResult<LEN_Y> {
-    // initialize
+  // simulator which applies doses of the regimen.
+  pub fn with_regimen(mut self, regimen: DosingRegimen) -> Self {
+    self.regimen = regimen;
+    self
+  }
+
+  // simulator which gives values of the inputs to SimModelTrait::ode_input.
+  pub fn with_inputs(mut self, inputs: Vec<Input>) -> Self {
+    self.inputs = inputs;
+    self
+  }
+
+  // simulation from init of a model.
+  // beats started before the initial time keep their phases, i.e. the first beat
+  // is at the first start + n * interval at or after it, and beats which stop
+  // before the initial time do not beat at all.
+  pub fn run(&self, smp_t: &[f64]) -> SimResult<LEN_Y> {
     let (ini_t, ini_y) = self.model.init();
+    self.run_from(ini_t, ini_y, smp_t)
+  }
+
+  // simulation from (ini_t, ini_y) instead of init of a model,
+  // e.g. from a steady state.
+  pub fn run_from(&self, ini_t: f64, ini_y: [f64; LEN_Y], smp_t: &[f64]) -> SimResult<LEN_Y> {
+    self.simulate(
+      ini_t,
+      ini_y,
+      smp_t,
+      |t, y, segment, dy| {
+        if self.inputs.is_empty() {
+          self.model.ode(t, y, dy);
+        } else {
+          let u: Vec<f64> = self
+            .inputs
+            .iter()
+            .map(|input| input.value_in(*t, segment))
+            .collect();
+          self.model.ode_input(t, y, &u, dy);
+        }
+      },
+      &[],
+      None::<fn(&f64, &[f64; LEN_Y], &[f64; LEN_Y])>,
+    )
+  }
+
+  // simulation with the derivative function deriv(t, y, segment, dy).
+  // the ODE solving also ends at extra_breakpoints.
+  // on_state(t, y, dy) is called for every calculated state if given.
+  pub(crate) fn simulate<D, S>(
+    &self,
+    ini_t: f64,
+    ini_y: [f64; LEN_Y],
+    smp_t: &[f64],
+    deriv: D,
+    extra_breakpoints: &[f64],
+    mut on_state: Option<S>,
+  ) -> SimResult<LEN_Y>
+  where
+    D: Fn(&f64, &[f64; LEN_Y], (f64, f64), &mut [f64; LEN_Y]),
+    S: FnMut(&f64, &[f64; LEN_Y], &[f64; LEN_Y]),
+  {
+    // initialize
     let beats = self.model.beat(&ini_t, &ini_y);
     let (end_t, mut vdq_smp_t, mut dec_times) = self.initialize_times(&ini_t, smp_t, &beats);
 
@@ -39,8 +110,35 @@ where
     let mut cur_t = ini_t;
     let mut cur_y = ini_y;
 
+    // infusion rates of the regimen added to the ODE,
+    // which are constant between breakpoints.
+    // segment is (start, end) of the current ODE solving for inputs.
+    let rates = Cell::new([0f64; LEN_Y]);
+    let segment = Cell::new((ini_t, ini_t));
+    let breakpoints = self.breakpoints(extra_breakpoints);
+
+    let derivative = |t: &f64, y: &[f64; LEN_Y], dy: &mut [f64; LEN_Y]| {
+      deriv(t, y, segment.get(), dy);
+      for (d, r) in dy.iter_mut().zip(rates.get().iter()) {
+        *d += r;
+      }
+    };
+
+    // pass the state and its derivative to on_state
+    let mut record = |t: &f64, y: &[f64; LEN_Y]| {
+      if let Some(on_state) = on_state.as_mut() {
+        let mut dy = [0f64; LEN_Y];
+        derivative(t, y, &mut dy);
+        on_state(t, y, &dy);
+      }
+    };
+
     // construct ConcreteStepper instance
-    let mut stepper = self.stepper.new(|t, y, dy| self.model.ode(t, y, dy));
+    let mut stepper = self.stepper.new(
+      &derivative,
+      |t, y, g| self.model.diff(t, y, g),
+      &self.model.mass(),
+    );
 
     // derivative of y for ODE
     // difference of y for REC
@@ -53,22 +151,60 @@ where
 
     // next_t indicates the next earliest discrete time point
     // for determining the end time of ODE solving in each loop.
+    // dec_cur_t and dec_next_t are cur_t and next_t in Decimal,
+    // which are compared with discrete time points of beats.
     let mut next_t: f64;
+    let mut dec_cur_t = dec_times.0;
+    let mut dec_next_t: Decimal;
+
+    // cond and REC are evaluated only at discrete time points of beats
+    // (and the initial and end times), not at breakpoints of doses and inputs.
+    let mut at_beat = true;
+    // the previous cur_t for giving each bolus once
+    let mut prev_t: Option<f64> = None;
 
     loop {
+      record(&cur_t, &cur_y);
+
       if LEN_B == 0 {
         // if no beat, calculate ode to the end.
         next_t = end_t;
+        dec_next_t = dec_times.1;
       } else {
-        // update act to be used in REC calculation
-        // update dec_next_t in dec_times for next loop
+        if at_beat {
+          // update act to be used in REC calculation
+          // update dec_next_t in dec_times for next loop
+          self.evaluate_condition(&dec_cur_t, &cur_y, &beats, &mut act, &mut dec_times);
+
+          // calculate REC
+          self.solve_rec(&cur_t, &mut cur_y, &mut delta_y, &act);
+        }
+
         // next_t is the end of the ODE solving
-        next_t = self.evaluate_condition(&cur_t, &cur_y, &beats, &mut act, &mut dec_times);
+        (next_t, dec_next_t) = self.next_discrete_t(&dec_times, end_t);
+      }
 
-        // calculate REC
-        self.solve_rec(&cur_t, &mut cur_y, &mut delta_y, &act);
+      if !self.regimen.is_empty() {
+        // give boluses and update infusion rates
+        self.regimen.bolus_at(prev_t, cur_t, &mut cur_y);
+        self.model.cre(&cur_t, &mut cur_y);
+        rates.set(self.regimen.rates_at(cur_t));
       }
 
+      // the state may have jumped by REC and boluses
+      record(&cur_t, &cur_y);
+
+      // the ODE solving also ends at the next breakpoint of doses and inputs
+      at_beat = true;
+      if let Some(&breakpoint) = breakpoints.iter().find(|&&t| t > cur_t) {
+        if breakpoint < next_t {
+          next_t = breakpoint;
+          dec_next_t = Decimal::from_str(&breakpoint.to_string()).unwrap();
+          at_beat = false;
+        }
+      }
+      segment.set((cur_t, next_t));
+
       // judge end
       if cur_t >= end_t {
         break;
@@ -83,29 +219,40 @@ where
         &mut cur_y,
         &mut deriv_y,
         &mut res_y,
+        &mut record,
       );
 
       // make a progress to the next loop
+      prev_t = Some(cur_t);
       cur_t = next_t;
+      dec_cur_t = dec_next_t;
     }
 
     // store the last values
     res_y.push(cur_y);
 
-    SimResult::new(smp_t.clone(), res_y)
+    SimResult::new(smp_t.to_vec(), res_y)
+  }
+
+  // times of doses, inputs and extra ones where the ODE solving is restarted.
+  fn breakpoints(&self, extra_breakpoints: &[f64]) -> Vec<f64> {
+    let mut breakpoints = self.regimen.breakpoints();
+    breakpoints.extend_from_slice(extra_breakpoints);
+    for input in self.inputs.iter() {
+      breakpoints.extend_from_slice(input.breakpoints());
+    }
+    breakpoints.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    breakpoints.dedup();
+    breakpoints
   }
 
   fn initialize_times(
     &self,
     ini_t: &f64,
-    smp_t: &Vec<f64>,
+    smp_t: &[f64],
     beats: &[[Decimal; 3]; LEN_B],
-  ) -> (
-    f64,
-    VecDeque<f64>,
-    (Decimal, Decimal, Decimal, [Decimal; LEN_B]),
-  ) {
-    let mut vec_smp_t = smp_t.clone();
+  ) -> (f64, VecDeque<f64>, DecTimes<LEN_B>) {
+    let mut vec_smp_t = smp_t.to_vec();
     // sort
     vec_smp_t.sort_by(|a, b| a.partial_cmp(b).unwrap());
     // remove duplication
@@ -130,9 +277,7 @@ where
     // set the first discrete time point for each beat.
     let mut dec_first_t = [dec_ini_t; LEN_B];
     for i in 0..LEN_B {
-      if dec_ini_t < beats[i][0] {
-        dec_first_t[i] = beats[i][0];
-      }
+      dec_first_t[i] = first_beat_t(&beats[i], dec_ini_t).unwrap_or(dec_stopped);
     }
 
     (
@@ -144,15 +289,13 @@ where
 
   fn evaluate_condition(
     &self,
-    cur_t: &f64,
+    dec_cur_t: &Decimal,
     cur_y: &[f64; LEN_Y],
     beats: &[[Decimal; 3]; LEN_B],
     act: &mut [bool; LEN_B],
-    (_, dec_end_t, dec_stopped, dec_next_t): &mut (Decimal, Decimal, Decimal, [Decimal; LEN_B]),
-  ) -> f64 {
-    let dec_cur_t = Decimal::from_str(&cur_t.to_string()).unwrap();
-
-    self.model.cond(&dec_cur_t, act, dec_next_t, cur_y);
+    (_, _, dec_stopped, dec_next_t): &mut DecTimes<LEN_B>,
+  ) {
+    self.model.cond(dec_cur_t, act, dec_next_t, cur_y);
 
     let mut tmp_dec_next_t: Decimal;
     for (i, &is_active) in act.iter().enumerate() {
@@ -164,13 +307,20 @@ where
         // otherwise, next_time is set to be end_time + 1 (stopped),
         // so that the corresponding beat will never beat again.
         if tmp_dec_next_t <= beats[i][1] {
-          dec_next_t[i] = dec_next_t[i] + beats[i][2];
+          dec_next_t[i] += beats[i][2];
         } else {
           dec_next_t[i] = *dec_stopped;
         }
       }
     }
+  }
 
+  // the next end of the ODE solving in f64 and Decimal.
+  fn next_discrete_t(
+    &self,
+    (_, dec_end_t, _, dec_next_t): &DecTimes<LEN_B>,
+    end_t: f64,
+  ) -> (f64, Decimal) {
     // the earliest next discrete time point will be used
     // as the next end of the ODE solving.
     let mut dec_earliest = dec_next_t[0];
@@ -183,9 +333,12 @@ where
     // if the next earliest discrete time is greater than end_time
     // next_t will be end_t, meaning this is the last rec solving.
     if dec_earliest < *dec_end_t {
-      dec_earliest.to_string().parse::<f64>().unwrap()
+      (
+        dec_earliest.to_string().parse::<f64>().unwrap(),
+        dec_earliest,
+      )
     } else {
-      dec_end_t.to_f64().unwrap()
+      (end_t, *dec_end_t)
     }
   }
 
@@ -204,22 +357,26 @@ where
     self.model.cre(cur_t, cur_y);
   }
 
-  fn solve_ode<ODE>(
+  #[allow(clippy::too_many_arguments)]
+  fn solve_ode<ODE, DIFF, R>(
     &self,
-    stepper: &mut ConcreteStepper<ODE, LEN_Y>,
+    stepper: &mut ConcreteStepper<ODE, DIFF, LEN_Y>,
     ini_t: &f64,
     end_t: &f64,
     vdq_smp_t: &mut VecDeque<f64>,
     cur_y: &mut [f64; LEN_Y],
     deriv_y: &mut [f64; LEN_Y],
     res_y: &mut Vec<[f64; LEN_Y]>,
+    record: &mut R,
   ) where
     ODE: Fn(&f64, &[f64; LEN_Y], &mut [f64; LEN_Y]),
+    DIFF: Fn(&f64, &[f64; LEN_Y], &mut [f64; LEN_Y]),
+    R: FnMut(&f64, &[f64; LEN_Y]),
   {
-    let mut cur_t = ini_t.clone();
+    let mut cur_t = *ini_t;
 
     let mut new_t: f64;
-    let mut new_y = cur_y.clone();
+    let mut new_y = *cur_y;
 
     let mut out_t: f64;
     let mut out_y = [0f64; LEN_Y];
@@ -230,10 +387,11 @@ where
 
       // keep constant relation (cre)
       self.model.cre(&new_t, &mut new_y);
+      record(&new_t, &new_y);
 
       // store results
       loop {
-        if vdq_smp_t.len() == 0 {
+        if vdq_smp_t.is_empty() {
           println!("All sample points have been collected.");
           break;
         }
@@ -268,7 +426,27 @@ where
 
     // store results at the end_t
     for i in 0..LEN_Y {
-      cur_y[i] = cur_y[i] + (end_t - cur_t) * deriv_y[i];
+      cur_y[i] += (end_t - cur_t) * deriv_y[i];
     }
   }
 }
+
+// the first discrete time point of a beat at or after dec_t.
+// a beat started before dec_t keeps its phase (e.g. in Simulator::run_from),
+// and a beat without interval beats at dec_t.
+// None if the beat stops before dec_t.
+pub(super) fn first_beat_t(beat: &[Decimal; 3], dec_t: Decimal) -> Option<Decimal> {
+  let [start, stop, interval] = *beat;
+  if dec_t <= start {
+    return Some(start);
+  }
+  if interval <= Decimal::ZERO {
+    return Some(dec_t);
+  }
+  let first_t = start + ((dec_t - start) / interval).ceil() * interval;
+  if first_t <= stop {
+    Some(first_t)
+  } else {
+    None
+  }
+}
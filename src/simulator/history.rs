@@ -0,0 +1,81 @@
+use std::collections::VecDeque;
+
+// history of states for delay differential equations.
+// states between calculated points are given by cubic Hermite interpolation
+// of (t, y, dy), and states before the initial time are the initial state.
+pub struct History<const LEN_Y: usize> {
+  ini_t: f64,
+  ini_y: [f64; LEN_Y],
+  max_delay: f64,
+  knots: VecDeque<(f64, [f64; LEN_Y], [f64; LEN_Y])>,
+}
+
+impl<const LEN_Y: usize> History<LEN_Y> {
+  pub(crate) fn new(ini_t: f64, ini_y: [f64; LEN_Y], max_delay: f64) -> Self {
+    Self {
+      ini_t,
+      ini_y,
+      max_delay,
+      knots: VecDeque::new(),
+    }
+  }
+
+  // delayed state y(t - tau)
+  pub fn lag(&self, t: &f64, tau: f64) -> [f64; LEN_Y] {
+    self.at(t - tau)
+  }
+
+  // state y(t)
+  pub fn at(&self, t: f64) -> [f64; LEN_Y] {
+    if t <= self.ini_t || self.knots.is_empty() {
+      return self.ini_y;
+    }
+
+    // k is the first knot after t
+    let k = self.knots.partition_point(|knot| knot.0 <= t);
+    if k == 0 {
+      return self.knots[0].1;
+    }
+
+    let (t0, y0, dy0) = &self.knots[k - 1];
+    let mut y = [0f64; LEN_Y];
+
+    if k == self.knots.len() {
+      // extrapolation from the last knot
+      for i in 0..LEN_Y {
+        y[i] = y0[i] + (t - t0) * dy0[i];
+      }
+      return y;
+    }
+
+    let (t1, y1, dy1) = &self.knots[k];
+    let h = t1 - t0;
+    let s = (t - t0) / h;
+    let h00 = (1.0 + 2.0 * s) * (1.0 - s).powi(2);
+    let h10 = s * (1.0 - s).powi(2);
+    let h01 = s * s * (3.0 - 2.0 * s);
+    let h11 = s * s * (s - 1.0);
+    for i in 0..LEN_Y {
+      y[i] = h00 * y0[i] + h10 * h * dy0[i] + h01 * y1[i] + h11 * h * dy1[i];
+    }
+    y
+  }
+
+  pub(crate) fn push(&mut self, t: f64, y: [f64; LEN_Y], dy: [f64; LEN_Y]) {
+    // discard knots after t (a step beyond the end of the ODE solving)
+    while self.knots.back().is_some_and(|knot| knot.0 > t) {
+      self.knots.pop_back();
+    }
+
+    // two knots at the same t represent a jump of the state
+    match self.knots.back_mut() {
+      Some(knot) if knot.0 == t && knot.1 == y => knot.2 = dy,
+      _ => self.knots.push_back((t, y, dy)),
+    }
+
+    // knots older than the max delay are no longer needed
+    while self.knots.len() > 2 && self.knots[1].0 < t - self.max_delay {
+      self.knots.pop_front();
+    }
+  }
+}
@@ -0,0 +1,250 @@
+use super::fixed::{first_beat_t, Simulator};
+use super::result::{write_file, SimResult};
+use super::steady::norm;
+
+use crate::linalg;
+use crate::model::SimModelTrait;
+
+use core::str::FromStr;
+use ndarray::{Array1, Array2};
+use rust_decimal::Decimal;
+use std::path::Path;
+
+// cycles of the first dose and the periodic steady state over a dosing interval.
+// the first sample of a cycle is just after the dose (peak of a bolus)
+// and the last one is just before the next dose (trough).
+// accumulation ratios are those of the steady state to the first dose,
+// which are NaN for states at 0 in the first cycle (e.g. not reached by the dose).
+pub struct PeriodicResult<const LEN_Y: usize> {
+  pub period: f64,
+  pub first: SimResult<LEN_Y>,
+  pub steady: SimResult<LEN_Y>,
+  pub trough: [f64; LEN_Y],
+  pub peak: [f64; LEN_Y],
+  pub auc: [f64; LEN_Y],
+  pub accumulation_trough: [f64; LEN_Y],
+  pub accumulation_peak: [f64; LEN_Y],
+  pub accumulation_auc: [f64; LEN_Y],
+}
+
+impl<const LEN_Y: usize> PeriodicResult<LEN_Y> {
+  pub fn new(period: f64, first: SimResult<LEN_Y>, steady: SimResult<LEN_Y>) -> Self {
+    let (first_trough, first_peak, first_auc) = cycle_summary(&first);
+    let (trough, peak, auc) = cycle_summary(&steady);
+
+    let mut accumulation_trough = [0f64; LEN_Y];
+    let mut accumulation_peak = [0f64; LEN_Y];
+    let mut accumulation_auc = [0f64; LEN_Y];
+    for i in 0..LEN_Y {
+      accumulation_trough[i] = ratio(trough[i], first_trough[i]);
+      accumulation_peak[i] = ratio(peak[i], first_peak[i]);
+      accumulation_auc[i] = ratio(auc[i], first_auc[i]);
+    }
+
+    Self {
+      period,
+      first,
+      steady,
+      trough,
+      peak,
+      auc,
+      accumulation_trough,
+      accumulation_peak,
+      accumulation_auc,
+    }
+  }
+
+  pub fn save(&self, dir: &str) {
+    let save_dir = Path::new(dir);
+
+    self.first.save_as(dir, "periodic_first.csv");
+    self.steady.save_as(dir, "periodic_steady.csv");
+
+    // y_index, trough, peak, auc, accumulation_trough, accumulation_peak, accumulation_auc
+    let mut str_result = String::new();
+    for i in 0..LEN_Y {
+      str_result.push_str(&format!(
+        "{},{},{},{},{},{},{}\n",
+        i,
+        self.trough[i],
+        self.peak[i],
+        self.auc[i],
+        self.accumulation_trough[i],
+        self.accumulation_peak[i],
+        self.accumulation_auc[i]
+      ));
+    }
+    write_file(&save_dir.join("periodic_summary.csv"), &str_result);
+  }
+}
+
+impl<M, const LEN_Y: usize, const LEN_P: usize, const LEN_B: usize>
+  Simulator<M, LEN_Y, LEN_P, LEN_B>
+where
+  M: SimModelTrait<LEN_Y, LEN_P, LEN_B>,
+{
+  // periodic steady state over the interval of beats[beat] (e.g. repeated doses),
+  // where the state just before a dose returns to itself after the interval.
+  // the cycle starts at the first beat and is sampled at n_points.
+  // the pre-dose state is found by the shooting method (Newton iterations),
+  // and by repeating the cycle up to max_periods times if they do not converge.
+  // states which do not change over the cycle are kept.
+  // other beats must have the same (or divisor) interval,
+  // so that they beat at the same times in every cycle.
+  // None if ||y(t + period) - y(t)|| < tol is not reached.
+  pub fn periodic_steady_state(
+    &self,
+    beat: usize,
+    n_points: usize,
+    tol: f64,
+    max_periods: usize,
+  ) -> Option<PeriodicResult<LEN_Y>> {
+    const MAX_NEWTON: usize = 20;
+
+    if beat >= LEN_B {
+      panic!("beat {} is not defined.", beat);
+    }
+    if n_points == 0 {
+      panic!("n_points must be positive.");
+    }
+
+    let (ini_t, ini_y) = self.model.init();
+    let beats = self.model.beat(&ini_t, &ini_y);
+    let dec_ini_t = Decimal::from_str(&ini_t.to_string()).unwrap();
+    let dec_start_t =
+      first_beat_t(&beats[beat], dec_ini_t).expect("the beat stops before the initial time.");
+    for (i, other) in beats.iter().enumerate() {
+      let active = first_beat_t(other, dec_start_t).is_some();
+      if i != beat && active && other[2] > Decimal::ZERO && !(beats[beat][2] % other[2]).is_zero() {
+        panic!(
+          "the interval of beat {} does not divide that of beat {}.",
+          i, beat
+        );
+      }
+    }
+    let period = beats[beat][2].to_string().parse::<f64>().unwrap();
+    let start_t = dec_start_t.to_string().parse::<f64>().unwrap();
+
+    // the end of a cycle is just before the next beat
+    let eps = period * 1e-9;
+    let mut smp_t: Vec<f64> = (0..n_points)
+      .map(|k| start_t + period * k as f64 / n_points as f64)
+      .collect();
+    smp_t.push(start_t + period - eps);
+
+    let cycle = |y0: [f64; LEN_Y]| self.run_from(start_t, y0, &smp_t);
+    let shoot = |y0: [f64; LEN_Y]| *cycle(y0).y.last().unwrap();
+
+    // pre-dose state of the first beat
+    let first_y = if start_t > ini_t {
+      *self
+        .run_from(ini_t, ini_y, &[start_t - eps])
+        .y
+        .last()
+        .unwrap()
+    } else {
+      ini_y
+    };
+    let first = cycle(first_y);
+
+    // shooting method
+    let mut y0 = first_y;
+    let mut converged = false;
+    for _ in 0..MAX_NEWTON {
+      let end_y = shoot(y0);
+      let residual = difference(&end_y, &y0);
+      let residual_norm = norm(&residual);
+      if residual_norm < tol {
+        converged = true;
+        break;
+      }
+
+      // Jacobian of end_y - y0 by finite differences
+      let mut jac = Array2::<f64>::zeros((LEN_Y, LEN_Y));
+      for j in 0..LEN_Y {
+        let mut wk = y0;
+        let dx = 1e-6 * y0[j].abs().max(1.0);
+        wk[j] += dx;
+        let wk_end_y = shoot(wk);
+        for i in 0..LEN_Y {
+          jac[[i, j]] = (wk_end_y[i] - end_y[i]) / dx - if i == j { 1.0 } else { 0.0 };
+        }
+      }
+
+      // states not changing over the cycle are excluded
+      let active: Vec<usize> = (0..LEN_Y)
+        .filter(|&i| jac.row(i).iter().any(|v| v.abs() > 1e-10) || residual[i].abs() >= tol)
+        .collect();
+      let n = active.len();
+      let sub_jac = Array2::from_shape_fn((n, n), |(a, b)| jac[[active[a], active[b]]]);
+      let sub_residual = Array1::from_shape_fn(n, |a| -residual[active[a]]);
+      let delta = match linalg::solve(&sub_jac, &sub_residual) {
+        Some(delta) => delta,
+        None => break,
+      };
+
+      let mut new_y0 = y0;
+      for (a, &i) in active.iter().enumerate() {
+        new_y0[i] += delta[a];
+      }
+      if norm(&difference(&shoot(new_y0), &new_y0)) >= residual_norm {
+        break;
+      }
+      y0 = new_y0;
+    }
+
+    // repeat the cycle
+    if !converged {
+      y0 = first_y;
+      for _ in 0..max_periods {
+        let end_y = shoot(y0);
+        if norm(&difference(&end_y, &y0)) < tol {
+          converged = true;
+          break;
+        }
+        y0 = end_y;
+      }
+    }
+
+    if converged {
+      Some(PeriodicResult::new(period, first, cycle(y0)))
+    } else {
+      None
+    }
+  }
+}
+
+// (trough, peak, auc) of a cycle, where auc is by the trapezoidal rule.
+fn cycle_summary<const LEN_Y: usize>(
+  simres: &SimResult<LEN_Y>,
+) -> ([f64; LEN_Y], [f64; LEN_Y], [f64; LEN_Y]) {
+  let trough = *simres.y.last().unwrap();
+  let mut peak = [f64::NEG_INFINITY; LEN_Y];
+  let mut auc = [0f64; LEN_Y];
+  for k in 0..simres.y.len() {
+    for i in 0..LEN_Y {
+      peak[i] = peak[i].max(simres.y[k][i]);
+      if k > 0 {
+        auc[i] += 0.5 * (simres.t[k] - simres.t[k - 1]) * (simres.y[k][i] + simres.y[k - 1][i]);
+      }
+    }
+  }
+  (trough, peak, auc)
+}
+
+fn difference<const LEN_Y: usize>(a: &[f64; LEN_Y], b: &[f64; LEN_Y]) -> [f64; LEN_Y] {
+  let mut d = [0f64; LEN_Y];
+  for i in 0..LEN_Y {
+    d[i] = a[i] - b[i];
+  }
+  d
+}
+
+// a / b, NaN if b is 0.
+fn ratio(a: f64, b: f64) -> f64 {
+  if b == 0.0 {
+    f64::NAN
+  } else {
+    a / b
+  }
+}
@@ -13,6 +13,10 @@ impl<const LEN_Y: usize> SimResult<LEN_Y> {
   }
 
   pub fn save(&self, dir: &str) {
+    self.save_as(dir, "simres.csv");
+  }
+
+  pub(crate) fn save_as(&self, dir: &str, file_name: &str) {
     let save_dir = Path::new(dir);
     let mut str_result = String::new();
 
@@ -25,12 +29,14 @@ impl<const LEN_Y: usize> SimResult<LEN_Y> {
       str_result.push('\n');
     }
 
-    let file_name = String::from("simres.csv");
     let save_path = save_dir.join(file_name);
-
-    // Write string into a file
-    let mut file = File::create(save_path).unwrap();
-    write!(file, "{}", str_result).unwrap();
-    file.flush().unwrap();
+    write_file(&save_path, &str_result);
   }
 }
+
+pub(crate) fn write_file(save_path: &Path, str_result: &str) {
+  // Write string into a file
+  let mut file = File::create(save_path).unwrap();
+  write!(file, "{}", str_result).unwrap();
+  file.flush().unwrap();
+}
@@ -0,0 +1,140 @@
+use super::fixed::Simulator;
+
+use crate::linalg;
+use crate::model::SimModelTrait;
+
+use ndarray::{Array1, Array2};
+
+impl<M, const LEN_Y: usize, const LEN_P: usize, const LEN_B: usize>
+  Simulator<M, LEN_Y, LEN_P, LEN_B>
+where
+  M: SimModelTrait<LEN_Y, LEN_P, LEN_B>,
+{
+  // steady state y where ||dy/dt|| < tol without beats and doses,
+  // starting from init of a model.
+  // f(y) = 0 is solved by Newton iterations with line search,
+  // and the ODE is integrated by the stepper up to max_t
+  // if they do not converge (e.g. a singular Jacobian by conservation).
+  // None if the steady state is not reached.
+  // with a mass matrix, f(y) = 0 is also the steady state of the DAE
+  // (algebraic states satisfy their constraints). Newton solutions which
+  // cre would change (e.g. out of the bounds clamped by cre) are rejected,
+  // and the steady state is searched by the integration with cre instead.
+  // the steady state can be the initial state of Simulator::run_from.
+  pub fn steady_state(&self, tol: f64, max_t: f64) -> Option<[f64; LEN_Y]> {
+    let (ini_t, ini_y) = self.model.init();
+
+    if let Some(y) = self.newton(&ini_t, &ini_y, tol) {
+      if self.keeps_cre(&ini_t, &y) {
+        return Some(y);
+      }
+    }
+
+    // long-time integration
+    let mut stepper = self.stepper.new(
+      |t, y, dy| self.model.ode(t, y, dy),
+      |_, _, _| {},
+      &self.model.mass(),
+    );
+    let mut cur_t = ini_t;
+    let mut cur_y = ini_y;
+    let mut deriv_y = [0f64; LEN_Y];
+    while cur_t < ini_t + max_t {
+      cur_t = stepper.run(&cur_t, &mut cur_y, &mut deriv_y);
+      self.model.cre(&cur_t, &mut cur_y);
+
+      if norm(&deriv_y) < tol && self.residual(&ini_t, &cur_y) < tol {
+        // polish the state by Newton iterations if possible
+        let polished = self.newton(&ini_t, &cur_y, tol);
+        return Some(
+          polished
+            .filter(|y| self.keeps_cre(&ini_t, y))
+            .unwrap_or(cur_y),
+        );
+      }
+    }
+
+    None
+  }
+
+  fn newton(&self, t: &f64, y: &[f64; LEN_Y], tol: f64) -> Option<[f64; LEN_Y]> {
+    const MAX_ITER: usize = 50;
+    const MAX_HALVING: usize = 20;
+
+    let mut cur_y = *y;
+    let mut f = self.derivative(t, &cur_y);
+    for _ in 0..MAX_ITER {
+      let f_norm = norm(&f);
+      if f_norm < tol {
+        return Some(cur_y);
+      }
+
+      // Newton direction
+      let jac = self.jacobian(t, &cur_y, &f);
+      let direction = linalg::solve(&jac, &-Array1::from(f.to_vec()))?;
+
+      // backtracking line search on ||f||
+      let mut lambda = 1.0;
+      let mut accepted = false;
+      for _ in 0..MAX_HALVING {
+        let mut new_y = cur_y;
+        for i in 0..LEN_Y {
+          new_y[i] += lambda * direction[i];
+        }
+        let new_f = self.derivative(t, &new_y);
+        if norm(&new_f) <= (1.0 - 1e-4 * lambda) * f_norm {
+          cur_y = new_y;
+          f = new_f;
+          accepted = true;
+          break;
+        }
+        lambda *= 0.5;
+      }
+      if !accepted {
+        return None;
+      }
+    }
+
+    if norm(&f) < tol {
+      Some(cur_y)
+    } else {
+      None
+    }
+  }
+
+  // whether cre leaves y unchanged.
+  fn keeps_cre(&self, t: &f64, y: &[f64; LEN_Y]) -> bool {
+    let mut cre_y = *y;
+    self.model.cre(t, &mut cre_y);
+    cre_y == *y
+  }
+
+  fn derivative(&self, t: &f64, y: &[f64; LEN_Y]) -> [f64; LEN_Y] {
+    let mut deriv_y = [0f64; LEN_Y];
+    self.model.ode(t, y, &mut deriv_y);
+    deriv_y
+  }
+
+  fn residual(&self, t: &f64, y: &[f64; LEN_Y]) -> f64 {
+    norm(&self.derivative(t, y))
+  }
+
+  // finite difference Jacobian of the ODE at y where the derivative is f.
+  fn jacobian(&self, t: &f64, y: &[f64; LEN_Y], f: &[f64; LEN_Y]) -> Array2<f64> {
+    let mut jac = Array2::zeros((LEN_Y, LEN_Y));
+    for j in 0..LEN_Y {
+      let mut wk = *y;
+      let dx = f64::EPSILON.sqrt() * y[j].abs().max(1.0);
+      wk[j] += dx;
+      let f_dx = self.derivative(t, &wk);
+      for i in 0..LEN_Y {
+        jac[[i, j]] = (f_dx[i] - f[i]) / dx;
+      }
+    }
+    jac
+  }
+}
+
+pub(super) fn norm<const LEN_Y: usize>(y: &[f64; LEN_Y]) -> f64 {
+  y.iter().map(|v| v * v).sum::<f64>().sqrt()
+}
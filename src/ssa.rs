@@ -0,0 +1,11 @@
+mod base;
+mod direct;
+mod next_reaction;
+mod simulator;
+mod tau_leaping;
+
+pub use crate::ssa::base::{ConcreteSsa, Ssa, SsaOptions};
+pub use crate::ssa::direct::Direct;
+pub use crate::ssa::next_reaction::NextReaction;
+pub use crate::ssa::simulator::StochasticSimulator;
+pub use crate::ssa::tau_leaping::TauLeaping;
@@ -0,0 +1,89 @@
+use super::direct::Direct;
+use super::next_reaction::NextReaction;
+use super::tau_leaping::TauLeaping;
+
+use crate::model::ReactionModelTrait;
+
+use rand::rngs::StdRng;
+use rand::Rng;
+
+// epsilon of tau-leaping bounds the relative change of propensities in a leap.
+#[derive(Clone)]
+pub enum SsaOptions {
+  Default,
+
+  TauLeaping { epsilon: f64 },
+}
+
+#[derive(Clone)]
+pub enum Ssa {
+  Direct(SsaOptions),
+  NextReaction(SsaOptions),
+  TauLeaping(SsaOptions),
+}
+
+impl Ssa {
+  #[allow(clippy::new_ret_no_self)]
+  pub fn new<M, const LEN_Y: usize, const LEN_R: usize>(
+    &self,
+    model: &M,
+  ) -> ConcreteSsa<LEN_Y, LEN_R>
+  where
+    M: ReactionModelTrait<LEN_Y, LEN_R>,
+  {
+    match self {
+      Ssa::Direct(options) => ConcreteSsa::Direct {
+        concrete_ssa: Direct::new(model, options),
+      },
+
+      Ssa::NextReaction(options) => ConcreteSsa::NextReaction {
+        concrete_ssa: NextReaction::new(model, options),
+      },
+
+      Ssa::TauLeaping(options) => ConcreteSsa::TauLeaping {
+        concrete_ssa: TauLeaping::new(model, options),
+      },
+    }
+  }
+}
+
+pub enum ConcreteSsa<const LEN_Y: usize, const LEN_R: usize> {
+  Direct {
+    concrete_ssa: Direct<LEN_Y, LEN_R>,
+  },
+  NextReaction {
+    concrete_ssa: NextReaction<LEN_Y, LEN_R>,
+  },
+  TauLeaping {
+    concrete_ssa: TauLeaping<LEN_Y, LEN_R>,
+  },
+}
+
+impl<const LEN_Y: usize, const LEN_R: usize> ConcreteSsa<LEN_Y, LEN_R> {
+  // (next_t, delta_y) of the next reaction (or leap) from (t, y).
+  // next_t is infinity if no reaction can occur.
+  // a leap does not go beyond max_t, while a reaction may occur after max_t.
+  pub fn run<M>(
+    &mut self,
+    model: &M,
+    t: &f64,
+    y: &[f64; LEN_Y],
+    max_t: &f64,
+    rng: &mut StdRng,
+  ) -> (f64, [f64; LEN_Y])
+  where
+    M: ReactionModelTrait<LEN_Y, LEN_R>,
+  {
+    match self {
+      ConcreteSsa::Direct { concrete_ssa } => concrete_ssa.run(model, t, y, rng),
+      ConcreteSsa::NextReaction { concrete_ssa } => concrete_ssa.run(model, t, y, rng),
+      ConcreteSsa::TauLeaping { concrete_ssa } => concrete_ssa.run(model, t, y, max_t, rng),
+    }
+  }
+}
+
+// exponential random number with rate a.
+pub(crate) fn exponential(rng: &mut StdRng, a: f64) -> f64 {
+  let u: f64 = rng.gen_range(f64::MIN_POSITIVE..1.0);
+  -u.ln() / a
+}
@@ -0,0 +1,63 @@
+use super::base::{exponential, SsaOptions};
+
+use crate::model::ReactionModelTrait;
+
+use rand::rngs::StdRng;
+use rand::Rng;
+
+// Gillespie direct method.
+pub struct Direct<const LEN_Y: usize, const LEN_R: usize> {
+  stoichiometry: [[f64; LEN_Y]; LEN_R],
+  a: [f64; LEN_R],
+}
+
+impl<const LEN_Y: usize, const LEN_R: usize> Direct<LEN_Y, LEN_R> {
+  pub fn new<M>(model: &M, options: &SsaOptions) -> Self
+  where
+    M: ReactionModelTrait<LEN_Y, LEN_R>,
+  {
+    match options {
+      SsaOptions::Default => (),
+      _ => panic!("Invalid SsaOptions variant."),
+    };
+
+    Self {
+      stoichiometry: model.stoichiometry(),
+      a: [0f64; LEN_R],
+    }
+  }
+
+  pub fn run<M>(
+    &mut self,
+    model: &M,
+    t: &f64,
+    y: &[f64; LEN_Y],
+    rng: &mut StdRng,
+  ) -> (f64, [f64; LEN_Y])
+  where
+    M: ReactionModelTrait<LEN_Y, LEN_R>,
+  {
+    model.propensities(t, y, &mut self.a);
+    let a0: f64 = self.a.iter().sum();
+    if a0 <= 0.0 {
+      return (f64::INFINITY, [0f64; LEN_Y]);
+    }
+
+    // time to the next reaction
+    let tau = exponential(rng, a0);
+
+    // select a reaction with the probability a[j] / a0
+    let threshold = rng.gen_range(0.0..a0);
+    let mut cumsum = 0.0;
+    let mut reaction = LEN_R - 1;
+    for (j, a) in self.a.iter().enumerate() {
+      cumsum += a;
+      if threshold < cumsum {
+        reaction = j;
+        break;
+      }
+    }
+
+    (t + tau, self.stoichiometry[reaction])
+  }
+}
@@ -0,0 +1,73 @@
+use super::base::{exponential, SsaOptions};
+
+use crate::model::ReactionModelTrait;
+
+use rand::rngs::StdRng;
+
+// next reaction method (Gibson and Bruck, 2000).
+// putative times of reactions not occurred are reused by rescaling
+// with their propensities, so that one random number is drawn per reaction.
+pub struct NextReaction<const LEN_Y: usize, const LEN_R: usize> {
+  stoichiometry: [[f64; LEN_Y]; LEN_R],
+  a: [f64; LEN_R],
+  next_t: [f64; LEN_R],
+  last: Option<usize>,
+}
+
+impl<const LEN_Y: usize, const LEN_R: usize> NextReaction<LEN_Y, LEN_R> {
+  pub fn new<M>(model: &M, options: &SsaOptions) -> Self
+  where
+    M: ReactionModelTrait<LEN_Y, LEN_R>,
+  {
+    match options {
+      SsaOptions::Default => (),
+      _ => panic!("Invalid SsaOptions variant."),
+    };
+
+    Self {
+      stoichiometry: model.stoichiometry(),
+      a: [0f64; LEN_R],
+      next_t: [f64::INFINITY; LEN_R],
+      last: None,
+    }
+  }
+
+  pub fn run<M>(
+    &mut self,
+    model: &M,
+    t: &f64,
+    y: &[f64; LEN_Y],
+    rng: &mut StdRng,
+  ) -> (f64, [f64; LEN_Y])
+  where
+    M: ReactionModelTrait<LEN_Y, LEN_R>,
+  {
+    let old_a = self.a;
+    model.propensities(t, y, &mut self.a);
+
+    // update putative times of reactions
+    for j in 0..LEN_R {
+      self.next_t[j] = if self.a[j] <= 0.0 {
+        f64::INFINITY
+      } else if self.last == Some(j) || old_a[j] <= 0.0 || self.next_t[j].is_infinite() {
+        t + exponential(rng, self.a[j])
+      } else {
+        t + old_a[j] / self.a[j] * (self.next_t[j] - t)
+      };
+    }
+
+    // the earliest reaction occurs
+    let mut reaction = 0;
+    for j in 1..LEN_R {
+      if self.next_t[j] < self.next_t[reaction] {
+        reaction = j;
+      }
+    }
+    if self.next_t[reaction].is_infinite() {
+      return (f64::INFINITY, [0f64; LEN_Y]);
+    }
+    self.last = Some(reaction);
+
+    (self.next_t[reaction], self.stoichiometry[reaction])
+  }
+}
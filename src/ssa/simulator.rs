@@ -0,0 +1,127 @@
+use super::base::Ssa;
+
+use crate::model::ReactionModelTrait;
+use crate::simulator::{EnsembleResult, SimResult};
+use crate::stats;
+
+use rand::rngs::StdRng;
+use std::collections::VecDeque;
+use std::thread;
+
+// simulator of stochastic trajectories of a reaction model.
+// the state at each sampling time is the one after reactions up to the time.
+// runs are reproducible if seed is given.
+#[derive(Clone)]
+pub struct StochasticSimulator<M, const LEN_Y: usize, const LEN_R: usize>
+where
+  M: ReactionModelTrait<LEN_Y, LEN_R>,
+{
+  pub model: M,
+  pub ssa: Ssa,
+  pub seed: Option<u64>,
+}
+
+impl<M, const LEN_Y: usize, const LEN_R: usize> StochasticSimulator<M, LEN_Y, LEN_R>
+where
+  M: ReactionModelTrait<LEN_Y, LEN_R>,
+{
+  pub fn new(model: M, ssa: Ssa, seed: Option<u64>) -> Self {
+    Self { model, ssa, seed }
+  }
+
+  pub fn run(&self, smp_t: &[f64]) -> SimResult<LEN_Y> {
+    let mut rng = stats::make_rng(self.seed, 0);
+    self.run_with(smp_t, &mut rng)
+  }
+
+  // n_runs trajectories in parallel summarized by
+  // the mean and the 5, 50 and 95 percentiles of every species.
+  pub fn ensemble(&self, smp_t: &[f64], n_runs: usize) -> EnsembleResult<LEN_Y> {
+    if n_runs == 0 {
+      panic!("n_runs must be positive.");
+    }
+
+    let n_threads = thread::available_parallelism().map_or(1, |n| n.get());
+
+    // vector for join-handles
+    let mut handles = Vec::new();
+
+    for n_thread in 0..n_threads {
+      let thread_simulator = self.clone();
+      let thread_runs: Vec<usize> = (0..n_runs).skip(n_thread).step_by(n_threads).collect();
+      let thread_smp_t = smp_t.to_vec();
+
+      // ===== FORK =====
+      let handle = thread::spawn(move || {
+        let mut trajectories = Vec::new();
+        for &n in thread_runs.iter() {
+          let mut rng = stats::make_rng(thread_simulator.seed, n as u64);
+          trajectories.push((n, thread_simulator.run_with(&thread_smp_t, &mut rng)));
+        }
+        trajectories
+      });
+      // ================
+
+      handles.push(handle);
+    }
+
+    // ===== JOIN =====
+    let mut trajectories = Vec::new();
+    for handle in handles {
+      trajectories.extend(handle.join().unwrap());
+    }
+    // ================
+
+    trajectories.sort_by_key(|(n, _)| *n);
+    let trajectories: Vec<SimResult<LEN_Y>> =
+      trajectories.into_iter().map(|(_, simres)| simres).collect();
+    EnsembleResult::new(&trajectories)
+  }
+
+  fn run_with(&self, smp_t: &[f64], rng: &mut StdRng) -> SimResult<LEN_Y> {
+    let (ini_t, ini_y) = self.model.init();
+
+    // sorted sampling times after ini_t
+    let mut vec_smp_t = smp_t.to_vec();
+    vec_smp_t.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    vec_smp_t.dedup();
+    vec_smp_t.retain(|&x| x >= ini_t);
+    let mut vdq_smp_t = VecDeque::from(vec_smp_t.clone());
+
+    let mut concrete_ssa = self.ssa.new(&self.model);
+    let mut res_y: Vec<[f64; LEN_Y]> = Vec::new();
+
+    let mut cur_t = ini_t;
+    let mut cur_y = ini_y;
+
+    loop {
+      // store the state at sampling times up to cur_t
+      while vdq_smp_t.front().is_some_and(|&t| t <= cur_t) {
+        vdq_smp_t.pop_front();
+        res_y.push(cur_y);
+      }
+      let max_t = match vdq_smp_t.front() {
+        Some(&t) => t,
+        None => break,
+      };
+
+      let (next_t, delta_y) = concrete_ssa.run(&self.model, &cur_t, &cur_y, &max_t, rng);
+
+      // the state is kept until the next reaction
+      while vdq_smp_t.front().is_some_and(|&t| t < next_t) {
+        vdq_smp_t.pop_front();
+        res_y.push(cur_y);
+      }
+      if vdq_smp_t.is_empty() {
+        break;
+      }
+
+      cur_t = next_t;
+      for i in 0..LEN_Y {
+        cur_y[i] += delta_y[i];
+      }
+    }
+
+    SimResult::new(vec_smp_t, res_y)
+  }
+}
@@ -0,0 +1,103 @@
+use super::base::SsaOptions;
+use super::direct::Direct;
+
+use crate::model::ReactionModelTrait;
+use crate::stats;
+
+use rand::rngs::StdRng;
+
+// explicit tau-leaping with the step size selection of Cao et al. (2006).
+// numbers of reactions in a leap are Poisson random numbers,
+// and the leap is halved if any species becomes negative.
+// exact reactions (direct method) are used when a leap is shorter than
+// a few reactions on average.
+pub struct TauLeaping<const LEN_Y: usize, const LEN_R: usize> {
+  stoichiometry: [[f64; LEN_Y]; LEN_R],
+  a: [f64; LEN_R],
+  epsilon: f64,
+  direct: Direct<LEN_Y, LEN_R>,
+}
+
+impl<const LEN_Y: usize, const LEN_R: usize> TauLeaping<LEN_Y, LEN_R> {
+  // a leap shorter than N_EXACT / a0 is replaced by an exact reaction.
+  const N_EXACT: f64 = 10.0;
+
+  pub fn new<M>(model: &M, options: &SsaOptions) -> Self
+  where
+    M: ReactionModelTrait<LEN_Y, LEN_R>,
+  {
+    let epsilon = match options {
+      SsaOptions::Default => 0.03,
+      SsaOptions::TauLeaping { epsilon } => *epsilon,
+    };
+
+    Self {
+      stoichiometry: model.stoichiometry(),
+      a: [0f64; LEN_R],
+      epsilon,
+      direct: Direct::new(model, &SsaOptions::Default),
+    }
+  }
+
+  pub fn run<M>(
+    &mut self,
+    model: &M,
+    t: &f64,
+    y: &[f64; LEN_Y],
+    max_t: &f64,
+    rng: &mut StdRng,
+  ) -> (f64, [f64; LEN_Y])
+  where
+    M: ReactionModelTrait<LEN_Y, LEN_R>,
+  {
+    model.propensities(t, y, &mut self.a);
+    let a0: f64 = self.a.iter().sum();
+    if a0 <= 0.0 {
+      return (f64::INFINITY, [0f64; LEN_Y]);
+    }
+
+    let mut tau = self.select_tau(y);
+    if tau < Self::N_EXACT / a0 {
+      return self.direct.run(model, t, y, rng);
+    }
+    tau = tau.min(max_t - t);
+
+    loop {
+      let mut delta_y = [0f64; LEN_Y];
+      for j in 0..LEN_R {
+        let k = stats::poisson(rng, self.a[j] * tau);
+        for i in 0..LEN_Y {
+          delta_y[i] += k * self.stoichiometry[j][i];
+        }
+      }
+
+      if (0..LEN_Y).all(|i| y[i] + delta_y[i] >= 0.0) {
+        return (t + tau, delta_y);
+      }
+      tau *= 0.5;
+    }
+  }
+
+  // the largest leap in which the expected change and SD of each species
+  // are within max(epsilon * y / 2, 1), where 2 is taken as the highest order
+  // of reactions consuming the species.
+  fn select_tau(&self, y: &[f64; LEN_Y]) -> f64 {
+    let mut tau = f64::INFINITY;
+    for i in 0..LEN_Y {
+      let mut mu = 0.0;
+      let mut sigma2 = 0.0;
+      for j in 0..LEN_R {
+        mu += self.stoichiometry[j][i] * self.a[j];
+        sigma2 += self.stoichiometry[j][i].powi(2) * self.a[j];
+      }
+      let bound = (self.epsilon * y[i] / 2.0).max(1.0);
+      if mu != 0.0 {
+        tau = tau.min(bound / mu.abs());
+      }
+      if sigma2 > 0.0 {
+        tau = tau.min(bound.powi(2) / sigma2);
+      }
+    }
+    tau
+  }
+}
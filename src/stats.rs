@@ -0,0 +1,247 @@
+// statistical functions shared by analyses.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+// complementary error function with fractional error < 1.2e-7
+// (Chebyshev fitting, Numerical Recipes 6.2).
+pub fn erfc(x: f64) -> f64 {
+  let (t, exponent) = erfc_terms(x.abs());
+  let ans = t * exponent.exp();
+
+  if x >= 0.0 {
+    ans
+  } else {
+    2.0 - ans
+  }
+}
+
+// erfc(z) = t * exp(exponent) for z >= 0
+fn erfc_terms(z: f64) -> (f64, f64) {
+  const COEF: [f64; 10] = [
+    -1.26551223,
+    1.00002368,
+    0.37409196,
+    0.09678418,
+    -0.18628806,
+    0.27886807,
+    -1.13520398,
+    1.48851587,
+    -0.82215223,
+    0.17087277,
+  ];
+
+  let t = 1.0 / (1.0 + 0.5 * z);
+  let poly = COEF.iter().rev().fold(0.0, |acc, c| acc * t + c);
+  (t, -z * z + poly)
+}
+
+// cumulative distribution function of the standard normal distribution.
+pub fn norm_cdf(x: f64) -> f64 {
+  0.5 * erfc(-x / std::f64::consts::SQRT_2)
+}
+
+// ln of norm_cdf without underflow in the lower tail.
+pub fn ln_norm_cdf(x: f64) -> f64 {
+  if x > -5.0 {
+    norm_cdf(x).ln()
+  } else {
+    let (t, exponent) = erfc_terms(-x / std::f64::consts::SQRT_2);
+    (0.5 * t).ln() + exponent
+  }
+}
+
+// quantile function of the standard normal distribution
+// (rational approximation by P. J. Acklam, relative error < 1.2e-9).
+pub fn norm_ppf(p: f64) -> f64 {
+  const A: [f64; 6] = [
+    -3.969683028665376e+01,
+    2.209460984245205e+02,
+    -2.759285104469687e+02,
+    1.38357751867269e+02,
+    -3.066479806614716e+01,
+    2.506628277459239e+00,
+  ];
+  const B: [f64; 5] = [
+    -5.447609879822406e+01,
+    1.615858368580409e+02,
+    -1.556989798598866e+02,
+    6.680131188771972e+01,
+    -1.328068155288572e+01,
+  ];
+  const C: [f64; 6] = [
+    -7.784894002430293e-03,
+    -3.223964580411365e-01,
+    -2.400758277161838e+00,
+    -2.549732539343734e+00,
+    4.374664141464968e+00,
+    2.938163982698783e+00,
+  ];
+  const D: [f64; 4] = [
+    7.784695709041462e-03,
+    3.224671290700398e-01,
+    2.445134137142996e+00,
+    3.754408661907416e+00,
+  ];
+  const P_LOW: f64 = 0.02425;
+
+  let horner = |coef: &[f64], x: f64| coef.iter().fold(0.0, |acc, c| acc * x + c);
+
+  if p <= 0.0 {
+    f64::NEG_INFINITY
+  } else if p >= 1.0 {
+    f64::INFINITY
+  } else if p < P_LOW {
+    let q = (-2.0 * p.ln()).sqrt();
+    horner(&C, q) / (horner(&D, q) * q + 1.0)
+  } else if p <= 1.0 - P_LOW {
+    let q = p - 0.5;
+    let r = q * q;
+    horner(&A, r) * q / (horner(&B, r) * r + 1.0)
+  } else {
+    let q = (-2.0 * (1.0 - p).ln()).sqrt();
+    -horner(&C, q) / (horner(&D, q) * q + 1.0)
+  }
+}
+
+// quantile of the chi-squared distribution with 1 degree of freedom.
+pub fn chi2_1_ppf(p: f64) -> f64 {
+  norm_ppf(0.5 + 0.5 * p).powi(2)
+}
+
+pub fn mean(values: &[f64]) -> f64 {
+  values.iter().sum::<f64>() / values.len() as f64
+}
+
+// sample standard deviation (n - 1).
+pub fn sd(values: &[f64]) -> f64 {
+  let m = mean(values);
+  let ss: f64 = values.iter().map(|v| (v - m).powi(2)).sum();
+  (ss / (values.len() as f64 - 1.0)).sqrt()
+}
+
+// standard normal random number (Box-Muller transform).
+pub fn randn<R: Rng>(rng: &mut R) -> f64 {
+  let u1: f64 = rng.gen_range(f64::MIN_POSITIVE..1.0);
+  let u2: f64 = rng.gen_range(0.0..1.0);
+  (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+// natural logarithm of the gamma function for x > 0
+// (Lanczos approximation, g = 7, n = 9).
+pub fn ln_gamma(x: f64) -> f64 {
+  const G: [f64; 9] = [
+    0.999_999_999_999_809_9,
+    676.520_368_121_885_1,
+    -1_259.139_216_722_402_8,
+    771.323_428_777_653_1,
+    -176.615_029_162_140_6,
+    12.507_343_278_686_905,
+    -0.138_571_095_265_720_12,
+    9.984_369_578_019_572e-6,
+    1.505_632_735_149_311_6e-7,
+  ];
+
+  if x < 0.5 {
+    // reflection formula
+    let pi = std::f64::consts::PI;
+    (pi / (pi * x).sin()).ln() - ln_gamma(1.0 - x)
+  } else {
+    let x = x - 1.0;
+    let mut sum = G[0];
+    for (k, g) in G.iter().enumerate().skip(1) {
+      sum += g / (x + k as f64);
+    }
+    let t = x + 7.5;
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + sum.ln()
+  }
+}
+
+// Poisson random number with mean lambda.
+// multiplication method for small lambda and
+// transformed rejection with squeeze (PTRS, Hormann 1993) otherwise.
+pub fn poisson<R: Rng>(rng: &mut R, lambda: f64) -> f64 {
+  if lambda <= 0.0 {
+    return 0.0;
+  }
+
+  if lambda < 10.0 {
+    let limit = (-lambda).exp();
+    let mut k = 0.0;
+    let mut prod: f64 = rng.gen_range(0.0..1.0);
+    while prod > limit {
+      k += 1.0;
+      prod *= rng.gen_range(0.0..1.0);
+    }
+    return k;
+  }
+
+  let ln_lambda = lambda.ln();
+  let b = 0.931 + 2.53 * lambda.sqrt();
+  let a = -0.059 + 0.02483 * b;
+  let inv_alpha = 1.1239 + 1.1328 / (b - 3.4);
+  let v_r = 0.9277 - 3.6224 / (b - 2.0);
+  loop {
+    let u: f64 = rng.gen_range(0.0..1.0) - 0.5;
+    let v: f64 = rng.gen_range(f64::MIN_POSITIVE..1.0);
+    let us = 0.5 - u.abs();
+    let k = ((2.0 * a / us + b) * u + lambda + 0.43).floor();
+    if us >= 0.07 && v <= v_r {
+      return k;
+    }
+    if k < 0.0 || (us < 0.013 && v > us) {
+      continue;
+    }
+    if v.ln() + inv_alpha.ln() - (a / (us * us) + b).ln()
+      <= -lambda + k * ln_lambda - ln_gamma(k + 1.0)
+    {
+      return k;
+    }
+  }
+}
+
+// quantile of sorted values by linear interpolation (NaN if empty).
+pub fn quantile(sorted: &[f64], q: f64) -> f64 {
+  if sorted.is_empty() {
+    return f64::NAN;
+  }
+  let pos = q * (sorted.len() - 1) as f64;
+  let lo = pos.floor() as usize;
+  let hi = pos.ceil() as usize;
+  sorted[lo] + (pos - lo as f64) * (sorted[hi] - sorted[lo])
+}
+
+// random number generator, reproducible for each stream if seed is given.
+pub fn make_rng(seed: Option<u64>, stream: u64) -> StdRng {
+  match seed {
+    Some(seed) => StdRng::seed_from_u64(stream_seed(seed, stream)),
+    None => StdRng::from_entropy(),
+  }
+}
+
+// seed of a stream, mixed by splitmix64 so that streams of different seeds
+// do not overlap (seed + stream would give stream 1 of seed 0 = stream 0 of seed 1).
+pub fn stream_seed(seed: u64, stream: u64) -> u64 {
+  let mut z = seed ^ stream.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+  z = z.wrapping_add(0x9E37_79B9_7F4A_7C15);
+  z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+  z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+  z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn streams_of_adjacent_seeds_differ() {
+    let draw = |seed, stream| make_rng(Some(seed), stream).gen::<u64>();
+    assert_eq!(draw(1, 2), draw(1, 2));
+    for seed in 0..8 {
+      for stream in 1..8 {
+        assert_ne!(draw(seed, stream), draw(seed + stream, 0));
+        assert_ne!(draw(seed, stream), draw(seed + 1, stream - 1));
+      }
+    }
+  }
+}
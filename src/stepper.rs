@@ -1,8 +1,14 @@
 mod base;
 mod dopri45;
+mod euler_maruyama;
+mod milstein;
+mod radau;
 mod rk4;
 
 pub use crate::stepper::base::Stepper;
 pub use crate::stepper::base::{ConcreteStepper, StepOptions};
 pub use crate::stepper::dopri45::Dopri45;
+pub use crate::stepper::euler_maruyama::EulerMaruyama;
+pub use crate::stepper::milstein::Milstein;
+pub use crate::stepper::radau::Radau;
 pub use crate::stepper::rk4::Rk4;
@@ -1,6 +1,17 @@
 use super::dopri45::Dopri45;
+use super::euler_maruyama::EulerMaruyama;
+use super::milstein::Milstein;
+use super::radau::Radau;
 use super::rk4::Rk4;
 
+use crate::stats;
+
+// EulerMaruyama and Milstein solve SDEs with the diffusion of a model,
+// and their noise is reproducible if seed is given.
+// in ensembles and populations, sample n draws its noise from the n-th stream
+// of seed (see Stepper::with_stream),
+// so that every sample has its own Wiener path.
+// Radau solves stiff ODEs and DAEs with the mass matrix of a model.
 #[derive(Clone)]
 pub enum StepOptions {
   Default,
@@ -16,19 +27,73 @@ pub enum StepOptions {
     hmin: f64,
     hmax: f64,
   },
+
+  EulerMaruyama {
+    h: f64,
+    seed: Option<u64>,
+  },
+
+  Milstein {
+    h: f64,
+    seed: Option<u64>,
+  },
+
+  Radau {
+    h0: f64,
+    abstol: f64,
+    reltol: f64,
+    hmin: f64,
+    hmax: f64,
+  },
 }
 
 #[derive(Clone)]
 pub enum Stepper {
   Rk4(StepOptions),
   Dopri45(StepOptions),
+  EulerMaruyama(StepOptions),
+  Milstein(StepOptions),
+  Radau(StepOptions),
 }
 
 impl Stepper {
-  pub fn new<Ode, const LEN_Y: usize>(&self, ode: Ode) -> ConcreteStepper<Ode, LEN_Y>
+  // the same stepper whose noise is drawn from the n-th stream of the seed.
+  // steppers without noise are unchanged.
+  pub(crate) fn with_stream(&self, stream: u64) -> Self {
+    let offset = |seed: &Option<u64>| seed.map(|seed| stats::stream_seed(seed, stream));
+    match self {
+      Stepper::EulerMaruyama(StepOptions::EulerMaruyama { h, seed }) => {
+        Stepper::EulerMaruyama(StepOptions::EulerMaruyama {
+          h: *h,
+          seed: offset(seed),
+        })
+      }
+      Stepper::Milstein(StepOptions::Milstein { h, seed }) => {
+        Stepper::Milstein(StepOptions::Milstein {
+          h: *h,
+          seed: offset(seed),
+        })
+      }
+      _ => self.clone(),
+    }
+  }
+
+  #[allow(clippy::new_ret_no_self)]
+  pub fn new<Ode, Diff, const LEN_Y: usize>(
+    &self,
+    ode: Ode,
+    diff: Diff,
+    mass: &[f64; LEN_Y],
+  ) -> ConcreteStepper<Ode, Diff, LEN_Y>
   where
     Ode: Fn(&f64, &[f64; LEN_Y], &mut [f64; LEN_Y]),
+    Diff: Fn(&f64, &[f64; LEN_Y], &mut [f64; LEN_Y]),
   {
+    // explicit steppers solve y' = f(t, y) only
+    if !matches!(self, Stepper::Radau(_)) && mass.iter().any(|&m| m != 1.0) {
+      panic!("please use Stepper::Radau for a model with a mass matrix.");
+    }
+
     match self {
       Stepper::Rk4(options) => ConcreteStepper::Rk4 {
         concrete_stepper: Rk4::new(ode, options),
@@ -37,13 +102,26 @@ impl Stepper {
       Stepper::Dopri45(options) => ConcreteStepper::Dopri45 {
         concrete_stepper: Dopri45::new(ode, options),
       },
+
+      Stepper::EulerMaruyama(options) => ConcreteStepper::EulerMaruyama {
+        concrete_stepper: EulerMaruyama::new(ode, diff, options),
+      },
+
+      Stepper::Milstein(options) => ConcreteStepper::Milstein {
+        concrete_stepper: Milstein::new(ode, diff, options),
+      },
+
+      Stepper::Radau(options) => ConcreteStepper::Radau {
+        concrete_stepper: Radau::new(ode, mass, options),
+      },
     }
   }
 }
 
-pub enum ConcreteStepper<Ode, const LEN_Y: usize>
+pub enum ConcreteStepper<Ode, Diff, const LEN_Y: usize>
 where
   Ode: Fn(&f64, &[f64; LEN_Y], &mut [f64; LEN_Y]),
+  Diff: Fn(&f64, &[f64; LEN_Y], &mut [f64; LEN_Y]),
 {
   Rk4 {
     concrete_stepper: Rk4<Ode, LEN_Y>,
@@ -51,16 +129,29 @@ where
   Dopri45 {
     concrete_stepper: Dopri45<Ode, LEN_Y>,
   },
+  EulerMaruyama {
+    concrete_stepper: EulerMaruyama<Ode, Diff, LEN_Y>,
+  },
+  Milstein {
+    concrete_stepper: Milstein<Ode, Diff, LEN_Y>,
+  },
+  Radau {
+    concrete_stepper: Radau<Ode, LEN_Y>,
+  },
 }
 
-impl<Ode, const LEN_Y: usize> ConcreteStepper<Ode, LEN_Y>
+impl<Ode, Diff, const LEN_Y: usize> ConcreteStepper<Ode, Diff, LEN_Y>
 where
   Ode: Fn(&f64, &[f64; LEN_Y], &mut [f64; LEN_Y]),
+  Diff: Fn(&f64, &[f64; LEN_Y], &mut [f64; LEN_Y]),
 {
   pub fn run(&mut self, t: &f64, y: &mut [f64; LEN_Y], dy: &mut [f64; LEN_Y]) -> f64 {
     match self {
       ConcreteStepper::Rk4 { concrete_stepper } => concrete_stepper.run(t, y, dy),
       ConcreteStepper::Dopri45 { concrete_stepper } => concrete_stepper.run(t, y, dy),
+      ConcreteStepper::EulerMaruyama { concrete_stepper } => concrete_stepper.run(t, y, dy),
+      ConcreteStepper::Milstein { concrete_stepper } => concrete_stepper.run(t, y, dy),
+      ConcreteStepper::Radau { concrete_stepper } => concrete_stepper.run(t, y, dy),
     }
   }
 }
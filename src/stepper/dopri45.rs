@@ -94,7 +94,7 @@ where
     };
 
     Self {
-      ode: ode,
+      ode,
       h: h0,
       k1: [0f64; LEN_Y],
       k2: [0f64; LEN_Y],
@@ -107,10 +107,10 @@ where
       y4: [0f64; LEN_Y],
       y5: [0f64; LEN_Y],
       total_tols: [0f64; LEN_Y],
-      abstol: abstol,
-      reltol: reltol,
-      hmin: hmin,
-      hmax: hmax,
+      abstol,
+      reltol,
+      hmin,
+      hmax,
     }
   }
 
@@ -125,7 +125,7 @@ where
       // else, calculate step again after shortening step size.
       if rms_err <= 1.0 {
         // renew t and y
-        next_t = t + self.h.clone();
+        next_t = t + self.h;
         *y = self.y5;
         // extend step size
         self.update_stepsize(rms_err);
@@ -239,9 +239,7 @@ where
       sum_of_squared_err += ((self.y5[i] - self.y4[i]) / self.total_tols[i]).powf(2.0);
     }
 
-    let rms_err = (sum_of_squared_err / LEN_Y as f64).sqrt();
-
-    rms_err
+    (sum_of_squared_err / LEN_Y as f64).sqrt()
   }
 
   fn update_stepsize(&mut self, rms_err: f64) {
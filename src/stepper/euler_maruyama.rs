@@ -0,0 +1,60 @@
+use super::base::StepOptions;
+
+use crate::stats;
+
+use rand::rngs::StdRng;
+
+pub struct EulerMaruyama<Ode, Diff, const LEN_Y: usize>
+where
+  Ode: Fn(&f64, &[f64; LEN_Y], &mut [f64; LEN_Y]),
+  Diff: Fn(&f64, &[f64; LEN_Y], &mut [f64; LEN_Y]),
+{
+  ode: Ode,
+  diff: Diff,
+  h: f64,
+  rng: StdRng,
+  f: [f64; LEN_Y],
+  g: [f64; LEN_Y],
+}
+
+impl<Ode, Diff, const LEN_Y: usize> EulerMaruyama<Ode, Diff, LEN_Y>
+where
+  Ode: Fn(&f64, &[f64; LEN_Y], &mut [f64; LEN_Y]),
+  Diff: Fn(&f64, &[f64; LEN_Y], &mut [f64; LEN_Y]),
+{
+  pub fn new(ode: Ode, diff: Diff, options: &StepOptions) -> Self {
+    let (h, seed) = match options {
+      StepOptions::Default => (1e-3, None),
+      StepOptions::EulerMaruyama { h, seed } => (*h, *seed),
+      _ => panic!("Invalid StepOptions variant."),
+    };
+
+    Self {
+      ode,
+      diff,
+      h,
+      rng: stats::make_rng(seed, 0),
+      f: [0f64; LEN_Y],
+      g: [0f64; LEN_Y],
+    }
+  }
+
+  pub fn run(&mut self, t: &f64, y: &mut [f64; LEN_Y], dy: &mut [f64; LEN_Y]) -> f64 {
+    self.step(t, y, dy);
+    t + self.h
+  }
+
+  // dy is the mean slope over the step including the noise.
+  pub fn step(&mut self, t: &f64, y: &mut [f64; LEN_Y], dy: &mut [f64; LEN_Y]) {
+    (self.ode)(t, y, &mut self.f);
+    self.g = [0f64; LEN_Y];
+    (self.diff)(t, y, &mut self.g);
+
+    let sqrt_h = self.h.sqrt();
+    for i in 0..LEN_Y {
+      let dw = sqrt_h * stats::randn(&mut self.rng);
+      dy[i] = self.f[i] + self.g[i] * dw / self.h;
+      y[i] += dy[i] * self.h;
+    }
+  }
+}
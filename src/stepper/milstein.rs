@@ -0,0 +1,75 @@
+use super::base::StepOptions;
+
+use crate::stats;
+
+use rand::rngs::StdRng;
+
+// derivative-free Milstein scheme for diagonal noise (Kloeden and Platen),
+// where the derivative of the diffusion is approximated
+// by the diffusion at a supporting value.
+pub struct Milstein<Ode, Diff, const LEN_Y: usize>
+where
+  Ode: Fn(&f64, &[f64; LEN_Y], &mut [f64; LEN_Y]),
+  Diff: Fn(&f64, &[f64; LEN_Y], &mut [f64; LEN_Y]),
+{
+  ode: Ode,
+  diff: Diff,
+  h: f64,
+  rng: StdRng,
+  f: [f64; LEN_Y],
+  g: [f64; LEN_Y],
+  g_support: [f64; LEN_Y],
+  wk: [f64; LEN_Y],
+}
+
+impl<Ode, Diff, const LEN_Y: usize> Milstein<Ode, Diff, LEN_Y>
+where
+  Ode: Fn(&f64, &[f64; LEN_Y], &mut [f64; LEN_Y]),
+  Diff: Fn(&f64, &[f64; LEN_Y], &mut [f64; LEN_Y]),
+{
+  pub fn new(ode: Ode, diff: Diff, options: &StepOptions) -> Self {
+    let (h, seed) = match options {
+      StepOptions::Default => (1e-3, None),
+      StepOptions::Milstein { h, seed } => (*h, *seed),
+      _ => panic!("Invalid StepOptions variant."),
+    };
+
+    Self {
+      ode,
+      diff,
+      h,
+      rng: stats::make_rng(seed, 0),
+      f: [0f64; LEN_Y],
+      g: [0f64; LEN_Y],
+      g_support: [0f64; LEN_Y],
+      wk: [0f64; LEN_Y],
+    }
+  }
+
+  pub fn run(&mut self, t: &f64, y: &mut [f64; LEN_Y], dy: &mut [f64; LEN_Y]) -> f64 {
+    self.step(t, y, dy);
+    t + self.h
+  }
+
+  // dy is the mean slope over the step including the noise.
+  pub fn step(&mut self, t: &f64, y: &mut [f64; LEN_Y], dy: &mut [f64; LEN_Y]) {
+    (self.ode)(t, y, &mut self.f);
+    self.g = [0f64; LEN_Y];
+    (self.diff)(t, y, &mut self.g);
+
+    // supporting value
+    let sqrt_h = self.h.sqrt();
+    for i in 0..LEN_Y {
+      self.wk[i] = y[i] + self.f[i] * self.h + self.g[i] * sqrt_h;
+    }
+    self.g_support = [0f64; LEN_Y];
+    (self.diff)(t, &self.wk, &mut self.g_support);
+
+    for i in 0..LEN_Y {
+      let dw = sqrt_h * stats::randn(&mut self.rng);
+      let correction = (self.g_support[i] - self.g[i]) * (dw * dw - self.h) / (2.0 * sqrt_h);
+      dy[i] = self.f[i] + (self.g[i] * dw + correction) / self.h;
+      y[i] += dy[i] * self.h;
+    }
+  }
+}
@@ -0,0 +1,257 @@
+use super::base::StepOptions;
+
+use crate::linalg;
+
+use ndarray::{Array1, Array2};
+
+// 2-stage Radau IIA method (order 3) for stiff ODEs and
+// semi-explicit index-1 DAEs  M y' = f(t, y)  with a diagonal mass matrix M,
+// where rows with M[i] = 0 are algebraic equations 0 = f[i](t, y).
+// the method is stiffly accurate, so that the end of each step satisfies
+// the algebraic equations. stages are solved by simplified Newton iterations
+// with a finite difference Jacobian, whose iteration matrix is LU factorized
+// once for each step size, and the step size is controlled
+// by the local error of step doubling.
+// algebraic states of the initial y are projected onto their equations
+// before the first step, since an inconsistent init breaks the Newton iterations.
+pub struct Radau<Ode, const LEN_Y: usize>
+where
+  Ode: Fn(&f64, &[f64; LEN_Y], &mut [f64; LEN_Y]),
+{
+  ode: Ode,
+  mass: [f64; LEN_Y],
+  h: f64,
+  jac: Array2<f64>,
+  f1: [f64; LEN_Y],
+  f2: [f64; LEN_Y],
+  wk: [f64; LEN_Y],
+  abstol: f64,
+  reltol: f64,
+  hmin: f64,
+  hmax: f64,
+  projected: bool,
+}
+
+impl<Ode, const LEN_Y: usize> Radau<Ode, LEN_Y>
+where
+  Ode: Fn(&f64, &[f64; LEN_Y], &mut [f64; LEN_Y]),
+{
+  const ORDER: f64 = 4.0;
+  const MAX_NEWTON: usize = 10;
+
+  const C1: f64 = 1.0 / 3.0;
+  const A11: f64 = 5.0 / 12.0;
+  const A12: f64 = -1.0 / 12.0;
+  const A21: f64 = 3.0 / 4.0;
+  const A22: f64 = 1.0 / 4.0;
+
+  pub fn new(ode: Ode, mass: &[f64; LEN_Y], options: &StepOptions) -> Self {
+    let (h0, abstol, reltol, hmin, hmax) = match options {
+      StepOptions::Default => (
+        1e-4, // default h0
+        1e-6, // default abstol
+        1e-6, // default reltol
+        1e-8, // default hmin
+        1e-2, // default hmax
+      ),
+
+      StepOptions::Radau {
+        h0,
+        abstol,
+        reltol,
+        hmin,
+        hmax,
+      } => (*h0, *abstol, *reltol, *hmin, *hmax),
+
+      _ => panic!("Invalid StepOptions variant."),
+    };
+
+    Self {
+      ode,
+      mass: *mass,
+      h: h0,
+      jac: Array2::zeros((LEN_Y, LEN_Y)),
+      f1: [0f64; LEN_Y],
+      f2: [0f64; LEN_Y],
+      wk: [0f64; LEN_Y],
+      abstol,
+      reltol,
+      hmin,
+      hmax,
+      projected: false,
+    }
+  }
+
+  pub fn run(&mut self, t: &f64, y: &mut [f64; LEN_Y], dy: &mut [f64; LEN_Y]) -> f64 {
+    if !self.projected {
+      self.project(t, y);
+      self.projected = true;
+    }
+    self.update_jacobian(t, y);
+
+    loop {
+      let h = self.h;
+
+      // one step of h and two steps of h / 2
+      let full = self.factorize(h).and_then(|lu| self.step(t, y, h, &lu));
+      let half = self.factorize(h / 2.0).and_then(|lu| {
+        self
+          .step(t, y, h / 2.0, &lu)
+          .and_then(|mid| self.step(&(t + h / 2.0), &mid, h / 2.0, &lu))
+      });
+
+      if let (Some(full), Some(half)) = (full, half) {
+        let rms_err = self.rms_err(&full, &half);
+
+        // if results are accepted, renew t and y with the two steps,
+        // else, calculate step again after shortening step size.
+        if rms_err <= 1.0 || h <= self.hmin {
+          for i in 0..LEN_Y {
+            dy[i] = (half[i] - y[i]) / h;
+          }
+          *y = half;
+          self.update_stepsize(rms_err);
+          return t + h;
+        }
+        self.update_stepsize(rms_err);
+      } else if h <= self.hmin {
+        panic!("Newton iterations of Radau did not converge at t = {}.", t);
+      } else {
+        // Newton iterations did not converge
+        self.h = (0.25 * h).max(self.hmin);
+      }
+    }
+  }
+
+  // LU factors of the iteration matrix of the stages for a step of h,
+  // or None if it is singular.
+  fn factorize(&self, h: f64) -> Option<linalg::Lu> {
+    let n = LEN_Y;
+    let mut mat = Array2::zeros((2 * n, 2 * n));
+    for i in 0..n {
+      mat[[i, i]] += self.mass[i];
+      mat[[n + i, n + i]] += self.mass[i];
+      for j in 0..n {
+        mat[[i, j]] -= h * Self::A11 * self.jac[[i, j]];
+        mat[[i, n + j]] -= h * Self::A12 * self.jac[[i, j]];
+        mat[[n + i, j]] -= h * Self::A21 * self.jac[[i, j]];
+        mat[[n + i, n + j]] -= h * Self::A22 * self.jac[[i, j]];
+      }
+    }
+    linalg::lu(&mat)
+  }
+
+  // end of a step of h from (t, y) with the LU factors of its iteration matrix,
+  // or None if Newton iterations diverge.
+  fn step(&mut self, t: &f64, y: &[f64; LEN_Y], h: f64, lu: &linalg::Lu) -> Option<[f64; LEN_Y]> {
+    let n = LEN_Y;
+    let mut stage1 = *y;
+    let mut stage2 = *y;
+    let mut residual = Array1::zeros(2 * n);
+    for _ in 0..Self::MAX_NEWTON {
+      (self.ode)(&(t + Self::C1 * h), &stage1, &mut self.f1);
+      (self.ode)(&(t + h), &stage2, &mut self.f2);
+      for i in 0..n {
+        residual[i] =
+          self.mass[i] * (stage1[i] - y[i]) - h * (Self::A11 * self.f1[i] + Self::A12 * self.f2[i]);
+        residual[n + i] =
+          self.mass[i] * (stage2[i] - y[i]) - h * (Self::A21 * self.f1[i] + Self::A22 * self.f2[i]);
+      }
+
+      let delta = lu.solve(&residual);
+      let mut sum_of_squared_delta = 0.0;
+      for i in 0..n {
+        stage1[i] -= delta[i];
+        stage2[i] -= delta[n + i];
+        let tol = self.abstol + self.reltol * stage2[i].abs();
+        sum_of_squared_delta += (delta[n + i] / tol).powi(2);
+      }
+
+      let norm = (sum_of_squared_delta / n as f64).sqrt();
+      if !norm.is_finite() {
+        return None;
+      }
+      if norm < 1e-3 {
+        return Some(stage2);
+      }
+    }
+    None
+  }
+
+  // Newton iterations on the algebraic states of y for 0 = f[i](t, y)
+  // with the differential states fixed.
+  fn project(&mut self, t: &f64, y: &mut [f64; LEN_Y]) {
+    let alg: Vec<usize> = (0..LEN_Y).filter(|&i| self.mass[i] == 0.0).collect();
+    if alg.is_empty() {
+      return;
+    }
+
+    for _ in 0..Self::MAX_NEWTON {
+      // f1 is f(t, y) after the Jacobian
+      self.update_jacobian(t, y);
+      let mat = Array2::from_shape_fn((alg.len(), alg.len()), |(i, j)| self.jac[[alg[i], alg[j]]]);
+      let residual = Array1::from_shape_fn(alg.len(), |i| self.f1[alg[i]]);
+      let delta = match linalg::solve(&mat, &residual) {
+        Some(delta) => delta,
+        None => panic!("algebraic equations are singular in y at t = {}.", t),
+      };
+
+      let mut sum_of_squared_delta = 0.0;
+      for (k, &i) in alg.iter().enumerate() {
+        y[i] -= delta[k];
+        let tol = self.abstol + self.reltol * y[i].abs();
+        sum_of_squared_delta += (delta[k] / tol).powi(2);
+      }
+      if (sum_of_squared_delta / alg.len() as f64).sqrt() < 1e-3 {
+        return;
+      }
+    }
+    panic!(
+      "algebraic states of y are inconsistent with their equations at t = {}.",
+      t
+    );
+  }
+
+  // finite difference Jacobian of the ODE at (t, y).
+  fn update_jacobian(&mut self, t: &f64, y: &[f64; LEN_Y]) {
+    (self.ode)(t, y, &mut self.f1);
+    for j in 0..LEN_Y {
+      self.wk = *y;
+      let dx = f64::EPSILON.sqrt() * y[j].abs().max(1.0);
+      self.wk[j] += dx;
+      (self.ode)(t, &self.wk, &mut self.f2);
+      for i in 0..LEN_Y {
+        self.jac[[i, j]] = (self.f2[i] - self.f1[i]) / dx;
+      }
+    }
+  }
+
+  // local error estimated by Richardson extrapolation.
+  fn rms_err(&self, full: &[f64; LEN_Y], half: &[f64; LEN_Y]) -> f64 {
+    let factor = 2f64.powf(Self::ORDER - 1.0) - 1.0;
+    let mut sum_of_squared_err = 0.0;
+    for i in 0..LEN_Y {
+      let tol = self.abstol + self.reltol * half[i].abs();
+      sum_of_squared_err += ((half[i] - full[i]) / factor / tol).powi(2);
+    }
+    (sum_of_squared_err / LEN_Y as f64).sqrt()
+  }
+
+  fn update_stepsize(&mut self, rms_err: f64) {
+    let ratio = 0.8 * ((1.0 / rms_err).powf(1.0 / Self::ORDER));
+
+    if ratio < 0.25 {
+      self.h *= 0.25;
+    } else if ratio < 4.0 {
+      self.h *= ratio;
+    } else {
+      self.h *= 4.0;
+    }
+
+    if self.h < self.hmin {
+      self.h = self.hmin
+    } else if self.hmax < self.h {
+      self.h = self.hmax
+    }
+  }
+}
@@ -25,8 +25,8 @@ where
     };
 
     Self {
-      ode: ode,
-      h: h,
+      ode,
+      h,
       k1: [0f64; LEN_Y],
       k2: [0f64; LEN_Y],
       k3: [0f64; LEN_Y],
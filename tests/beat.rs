@@ -0,0 +1,155 @@
+use aphreco::prelude::*;
+
+use core::str::FromStr;
+
+// beat 0 adds 1 to y[0], and beat 1 adds 1 + y[0] to y[1].
+// beat 1 precedes beat 0 by less than the precision of f64.
+#[derive(Clone)]
+struct Counter;
+
+impl SimModelTrait<2, 1, 2> for Counter {
+  fn new() -> Self {
+    Self
+  }
+
+  fn init(&self) -> (f64, [f64; 2]) {
+    (0.0, [0.0, 0.0])
+  }
+
+  fn ode(&self, _t: &f64, _y: &[f64; 2], deriv_y: &mut [f64; 2]) {
+    deriv_y[0] = 0.0;
+    deriv_y[1] = 0.0;
+  }
+
+  fn rec(&self, _t: &f64, y: &[f64; 2], delta_y: &mut [f64; 2], act: &[bool; 2]) {
+    if act[0] {
+      delta_y[0] += 1.0;
+    }
+    if act[1] {
+      delta_y[1] += 1.0 + y[0];
+    }
+  }
+
+  fn cond(&self, dec_t: &Decimal, act: &mut [bool; 2], next_t: &[Decimal; 2], _y: &[f64; 2]) {
+    act[0] = *dec_t == next_t[0];
+    act[1] = *dec_t == next_t[1];
+  }
+
+  fn beat(&self, _t: &f64, _y: &[f64; 2]) -> [[Decimal; 3]; 2] {
+    let dec = |s: &str| Decimal::from_str(s).unwrap();
+    [
+      [dec("0.1000000000000000000001"), dec("100"), dec("1")],
+      [dec("0.1"), dec("100"), dec("1")],
+    ]
+  }
+
+  fn cre(&self, _t: &f64, _y: &mut [f64; 2]) {}
+}
+
+#[test]
+fn beats_closer_than_f64_precision() {
+  let simulator = Simulator::new(Counter, Stepper::Rk4(StepOptions::Default));
+  let simres = simulator.run(&[5.0]);
+
+  // beats fire at 0.1, 1.1, 2.1, 3.1 and 4.1 in order of their times,
+  // so beat 1 always sees y[0] before beat 0 increments it.
+  assert_eq!(simres.y[0], [5.0, 15.0]);
+}
+
+// beat i adds 1 to y[i] every 1.0 from 0.0 and 0.5, respectively.
+#[derive(Clone)]
+struct Phases;
+
+impl SimModelTrait<2, 1, 2> for Phases {
+  fn new() -> Self {
+    Self
+  }
+
+  fn init(&self) -> (f64, [f64; 2]) {
+    (0.0, [0.0, 0.0])
+  }
+
+  fn ode(&self, _t: &f64, _y: &[f64; 2], deriv_y: &mut [f64; 2]) {
+    deriv_y[0] = 0.0;
+    deriv_y[1] = 0.0;
+  }
+
+  fn rec(&self, _t: &f64, _y: &[f64; 2], delta_y: &mut [f64; 2], act: &[bool; 2]) {
+    for i in 0..2 {
+      if act[i] {
+        delta_y[i] += 1.0;
+      }
+    }
+  }
+
+  fn cond(&self, dec_t: &Decimal, act: &mut [bool; 2], next_t: &[Decimal; 2], _y: &[f64; 2]) {
+    act[0] = *dec_t == next_t[0];
+    act[1] = *dec_t == next_t[1];
+  }
+
+  fn beat(&self, _t: &f64, _y: &[f64; 2]) -> [[Decimal; 3]; 2] {
+    [beat![0.0, 100.0, 1.0], beat![0.5, 100.0, 1.0]]
+  }
+
+  fn cre(&self, _t: &f64, _y: &mut [f64; 2]) {}
+}
+
+#[test]
+fn run_from_keeps_phases_of_beats() {
+  let simulator = Simulator::new(Phases, Stepper::Rk4(StepOptions::Default));
+  let simres = simulator.run_from(2.25, [0.0, 0.0], &[3.9]);
+
+  // beat 0 at 3.0, and beat 1 at 2.5 and 3.5.
+  assert_eq!(simres.y[0], [1.0, 2.0]);
+}
+
+// Phases starting at 2.25, with beat 2 which stops at 1.0 before it.
+#[derive(Clone)]
+struct LateStart;
+
+impl SimModelTrait<3, 1, 3> for LateStart {
+  fn new() -> Self {
+    Self
+  }
+
+  fn init(&self) -> (f64, [f64; 3]) {
+    (2.25, [0.0, 0.0, 0.0])
+  }
+
+  fn ode(&self, _t: &f64, _y: &[f64; 3], deriv_y: &mut [f64; 3]) {
+    *deriv_y = [0.0; 3];
+  }
+
+  fn rec(&self, _t: &f64, _y: &[f64; 3], delta_y: &mut [f64; 3], act: &[bool; 3]) {
+    for i in 0..3 {
+      if act[i] {
+        delta_y[i] += 1.0;
+      }
+    }
+  }
+
+  fn cond(&self, dec_t: &Decimal, act: &mut [bool; 3], next_t: &[Decimal; 3], _y: &[f64; 3]) {
+    for i in 0..3 {
+      act[i] = *dec_t == next_t[i];
+    }
+  }
+
+  fn beat(&self, _t: &f64, _y: &[f64; 3]) -> [[Decimal; 3]; 3] {
+    [
+      beat![0.0, 100.0, 1.0],
+      beat![0.5, 100.0, 1.0],
+      beat![0.0, 1.0, 0.5],
+    ]
+  }
+
+  fn cre(&self, _t: &f64, _y: &mut [f64; 3]) {}
+}
+
+#[test]
+fn run_keeps_phases_of_beats_started_before_init() {
+  let simulator = Simulator::new(LateStart, Stepper::Rk4(StepOptions::Default));
+  let simres = simulator.run(&[3.9]);
+
+  // beat 0 at 3.0, beat 1 at 2.5 and 3.5, and beat 2 has stopped.
+  assert_eq!(simres.y[0], [1.0, 2.0, 0.0]);
+}
@@ -0,0 +1,222 @@
+use aphreco::prelude::*;
+
+// dy/dt = J(p) y + (1, 0) with J = [[p, 1], [p - 1, p]],
+// whose eigenvalues are p +- i sqrt(1 - p) for p < 1 and p +- sqrt(p - 1) for p > 1.
+// the equilibrium loses stability by a Hopf at p = 0, and the unstable focus
+// turns into an unstable node at p = 1 without any bifurcation.
+#[derive(Clone)]
+struct FocusNode {
+  p: [f64; 1],
+}
+
+impl SimModelTrait<2, 1, 0> for FocusNode {
+  fn new() -> Self {
+    Self { p: [-0.5] }
+  }
+
+  fn init(&self) -> (f64, [f64; 2]) {
+    (0.0, [0.0, 0.0])
+  }
+
+  fn ode(&self, _t: &f64, y: &[f64; 2], deriv_y: &mut [f64; 2]) {
+    let p = self.p[0];
+    deriv_y[0] = p * y[0] + y[1] + 1.0;
+    deriv_y[1] = (p - 1.0) * y[0] + p * y[1];
+  }
+
+  fn rec(&self, _t: &f64, _y: &[f64; 2], _delta_y: &mut [f64; 2], _act: &[bool; 0]) {}
+
+  fn cond(&self, _dec_t: &Decimal, _act: &mut [bool; 0], _next_t: &[Decimal; 0], _y: &[f64; 2]) {}
+
+  fn beat(&self, _t: &f64, _y: &[f64; 2]) -> [[Decimal; 3]; 0] {
+    []
+  }
+
+  fn cre(&self, _t: &f64, _y: &mut [f64; 2]) {}
+}
+
+impl OptModelTrait<2, 1, 0, 1> for FocusNode {
+  fn getp(&self) -> &[f64; 1] {
+    &self.p
+  }
+
+  fn getx(&self) -> (Vec<usize>, Option<Vec<(f64, f64)>>) {
+    (vec![0], None)
+  }
+
+  fn setp(&mut self, index: usize, value: f64) {
+    self.p[index] = value;
+  }
+}
+
+#[test]
+fn focus_to_node_is_not_a_bifurcation() {
+  let simulator = Simulator::new(FocusNode::new(), Stepper::Dopri45(StepOptions::Default));
+  let continuation = Continuation::new(
+    0,
+    ContinuationMethod::Natural,
+    (-1.0, 1.9),
+    0.05,
+    100,
+    1e-10,
+  );
+  let contres = continuation.run(&simulator);
+
+  assert_eq!(contres.bifurcations.len(), 1);
+  let hopf = contres.bifurcations[0];
+  assert_eq!(hopf.kind, BifurcationKind::Hopf);
+  assert!(hopf.p.abs() < 1e-6, "{}", hopf.p);
+
+  // the last point is within the bounds
+  assert!(*contres.p.last().unwrap() <= 1.9);
+}
+
+#[test]
+fn pseudo_arclength_stays_in_bounds() {
+  let simulator = Simulator::new(FocusNode::new(), Stepper::Dopri45(StepOptions::Default));
+  let continuation = Continuation::new(
+    0,
+    ContinuationMethod::PseudoArclength,
+    (-1.0, 0.5),
+    0.07,
+    100,
+    1e-10,
+  );
+  let contres = continuation.run(&simulator);
+
+  assert!(contres.p.iter().all(|&p| (-1.0..=0.5).contains(&p)));
+  assert_eq!(contres.bifurcations.len(), 1);
+}
+
+// saddle-node normal form dy/dt = p - y^2 with the equilibria +- sqrt(p),
+// which meet at the fold p = 0.
+#[derive(Clone)]
+struct Fold {
+  p: [f64; 1],
+}
+
+impl SimModelTrait<1, 1, 0> for Fold {
+  fn new() -> Self {
+    Self { p: [1.0] }
+  }
+
+  fn init(&self) -> (f64, [f64; 1]) {
+    (0.0, [0.5])
+  }
+
+  fn ode(&self, _t: &f64, y: &[f64; 1], deriv_y: &mut [f64; 1]) {
+    deriv_y[0] = self.p[0] - y[0] * y[0];
+  }
+
+  fn rec(&self, _t: &f64, _y: &[f64; 1], _delta_y: &mut [f64; 1], _act: &[bool; 0]) {}
+
+  fn cond(&self, _dec_t: &Decimal, _act: &mut [bool; 0], _next_t: &[Decimal; 0], _y: &[f64; 1]) {}
+
+  fn beat(&self, _t: &f64, _y: &[f64; 1]) -> [[Decimal; 3]; 0] {
+    []
+  }
+
+  fn cre(&self, _t: &f64, _y: &mut [f64; 1]) {}
+}
+
+impl OptModelTrait<1, 1, 0, 1> for Fold {
+  fn getp(&self) -> &[f64; 1] {
+    &self.p
+  }
+
+  fn getx(&self) -> (Vec<usize>, Option<Vec<(f64, f64)>>) {
+    (vec![0], None)
+  }
+
+  fn setp(&mut self, index: usize, value: f64) {
+    self.p[index] = value;
+  }
+}
+
+// Hopf normal form in cartesian coordinates
+// dx/dt = p x - y - x r^2, dy/dt = x + p y - y r^2 with the eigenvalues p +- i at 0.
+#[derive(Clone)]
+struct Hopf {
+  p: [f64; 1],
+}
+
+impl SimModelTrait<2, 1, 0> for Hopf {
+  fn new() -> Self {
+    Self { p: [-0.5] }
+  }
+
+  fn init(&self) -> (f64, [f64; 2]) {
+    (0.0, [0.1, 0.0])
+  }
+
+  fn ode(&self, _t: &f64, y: &[f64; 2], deriv_y: &mut [f64; 2]) {
+    let (p, r2) = (self.p[0], y[0] * y[0] + y[1] * y[1]);
+    deriv_y[0] = p * y[0] - y[1] - y[0] * r2;
+    deriv_y[1] = y[0] + p * y[1] - y[1] * r2;
+  }
+
+  fn rec(&self, _t: &f64, _y: &[f64; 2], _delta_y: &mut [f64; 2], _act: &[bool; 0]) {}
+
+  fn cond(&self, _dec_t: &Decimal, _act: &mut [bool; 0], _next_t: &[Decimal; 0], _y: &[f64; 2]) {}
+
+  fn beat(&self, _t: &f64, _y: &[f64; 2]) -> [[Decimal; 3]; 0] {
+    []
+  }
+
+  fn cre(&self, _t: &f64, _y: &mut [f64; 2]) {}
+}
+
+impl OptModelTrait<2, 1, 0, 1> for Hopf {
+  fn getp(&self) -> &[f64; 1] {
+    &self.p
+  }
+
+  fn getx(&self) -> (Vec<usize>, Option<Vec<(f64, f64)>>) {
+    (vec![0], None)
+  }
+
+  fn setp(&mut self, index: usize, value: f64) {
+    self.p[index] = value;
+  }
+}
+
+#[test]
+fn fold_of_normal_form() {
+  let simulator = Simulator::new(Fold::new(), Stepper::Dopri45(StepOptions::Default));
+  let continuation = Continuation::new(
+    0,
+    ContinuationMethod::PseudoArclength,
+    (-1.0, 2.0),
+    -0.05,
+    200,
+    1e-10,
+  );
+  let contres = continuation.run(&simulator);
+
+  // the branch turns at p = 0 from y = sqrt(p) to y = -sqrt(p)
+  assert!(contres.y.iter().any(|y| y[0] < -0.5));
+  assert_eq!(contres.bifurcations.len(), 1);
+  let fold = contres.bifurcations[0];
+  assert_eq!(fold.kind, BifurcationKind::SaddleNode);
+  assert!(fold.p.abs() < 1e-2, "{}", fold.p);
+}
+
+#[test]
+fn hopf_of_normal_form() {
+  let simulator = Simulator::new(Hopf::new(), Stepper::Dopri45(StepOptions::Default));
+  let continuation = Continuation::new(
+    0,
+    ContinuationMethod::Natural,
+    (-1.0, 1.0),
+    0.05,
+    100,
+    1e-10,
+  );
+  let contres = continuation.run(&simulator);
+
+  assert_eq!(contres.bifurcations.len(), 1);
+  let hopf = contres.bifurcations[0];
+  assert_eq!(hopf.kind, BifurcationKind::Hopf);
+  assert!(hopf.p.abs() < 1e-6, "{}", hopf.p);
+  assert!(hopf.y.iter().all(|y| y.abs() < 1e-6));
+}
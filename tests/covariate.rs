@@ -0,0 +1,133 @@
+use aphreco::prelude::*;
+
+use ndarray::{arr1, arr2};
+
+// constant y = p = [cl, v] with cl ~ (WT / 70)^0.75 and v by SEX.
+#[derive(Clone)]
+struct Scaled {
+  p: [f64; 2],
+}
+
+impl SimModelTrait<2, 2, 0> for Scaled {
+  fn new() -> Self {
+    Self { p: [2.0, 5.0] }
+  }
+
+  fn init(&self) -> (f64, [f64; 2]) {
+    (0.0, self.p)
+  }
+
+  fn ode(&self, _t: &f64, _y: &[f64; 2], deriv_y: &mut [f64; 2]) {
+    *deriv_y = [0.0; 2];
+  }
+
+  fn rec(&self, _t: &f64, _y: &[f64; 2], _delta_y: &mut [f64; 2], _act: &[bool; 0]) {}
+
+  fn cond(&self, _dec_t: &Decimal, _act: &mut [bool; 0], _next_t: &[Decimal; 0], _y: &[f64; 2]) {}
+
+  fn beat(&self, _t: &f64, _y: &[f64; 2]) -> [[Decimal; 3]; 0] {
+    []
+  }
+
+  fn cre(&self, _t: &f64, _y: &mut [f64; 2]) {}
+}
+
+impl OptModelTrait<2, 2, 0, 2> for Scaled {
+  fn getp(&self) -> &[f64; 2] {
+    &self.p
+  }
+
+  fn getx(&self) -> (Vec<usize>, Option<Vec<(f64, f64)>>) {
+    (vec![0, 1], None)
+  }
+
+  fn covariates(&self) -> Vec<Covariate> {
+    vec![
+      Covariate::new(
+        0,
+        "WT",
+        Relation::Power {
+          reference: 70.0,
+          exponent: 0.75,
+        },
+      ),
+      Covariate::new(
+        1,
+        "SEX",
+        Relation::Categorical {
+          factors: vec![(1.0, 0.8)],
+        },
+      ),
+    ]
+  }
+
+  fn setp(&mut self, index: usize, value: f64) {
+    self.p[index] = value;
+  }
+}
+
+fn simulator() -> Simulator<Scaled, 2, 2, 0> {
+  Simulator::new(Scaled::new(), Stepper::Rk4(StepOptions::Rk4 { h: 0.1 }))
+}
+
+fn covariates(wt: f64, sex: f64) -> Vec<(String, f64)> {
+  vec![("WT".to_string(), wt), ("SEX".to_string(), sex)]
+}
+
+#[test]
+fn effects_of_relations() {
+  let power = Relation::Power {
+    reference: 70.0,
+    exponent: 0.75,
+  };
+  assert_eq!(
+    Covariate::new(0, "WT", power).effect(140.0),
+    2f64.powf(0.75)
+  );
+  let linear = Relation::Linear {
+    reference: 40.0,
+    slope: -0.01,
+  };
+  assert!((Covariate::new(0, "AGE", linear).effect(60.0) - 0.8).abs() < 1e-12);
+  let categorical = Relation::Categorical {
+    factors: vec![(1.0, 0.8), (2.0, 1.5)],
+  };
+  let sex = Covariate::new(0, "SEX", categorical);
+  assert_eq!(sex.effect(2.0), 1.5);
+  assert_eq!(sex.effect(0.0), 1.0);
+}
+
+#[test]
+fn objective_applies_covariates_of_data() {
+  // the subject with WT = 140 and SEX = 1
+  let (cl, v) = (2.0 * 2f64.powf(0.75), 5.0 * 0.8);
+  let mut data = Data::new(vec![(0, 0.1, cl, None, None), (1, 0.1, v, None, None)]);
+  data.set_covariate("WT", 140.0);
+  data.set_covariate("SEX", 1.0);
+
+  let simres = simulator().with_covariates(&data.covariates).run(&[0.1]);
+  assert_eq!(simres.y[0], [cl, v]);
+
+  let mut objective = Objective::new(simulator(), data);
+  assert!(objective.obj(&arr1(&[2.0, 5.0])) < 1e-20);
+}
+
+#[test]
+#[should_panic(expected = "covariate 'SEX' is not given in data.")]
+fn objective_requires_declared_covariates() {
+  let mut data = Data::new(vec![(0, 0.1, 2.0, None, None)]);
+  data.set_covariate("WT", 70.0);
+  Objective::new(simulator(), data);
+}
+
+#[test]
+fn population_applies_covariates_to_typical_values() {
+  let population = Population::new(vec![0, 1], arr2(&[[1e-12, 0.0], [0.0, 1e-12]]), 2, Some(1))
+    .with_covariates(vec![covariates(35.0, 0.0), covariates(140.0, 1.0)]);
+  let popres = population.run(&simulator(), &[0.1]);
+
+  let expected = [[2.0 * 0.5f64.powf(0.75), 5.0], [2.0 * 2f64.powf(0.75), 4.0]];
+  for (x, expected) in popres.params.iter().zip(expected.iter()) {
+    assert!((x - &arr1(expected)).mapv(f64::abs).sum() < 1e-4, "{}", x);
+  }
+}
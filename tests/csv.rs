@@ -0,0 +1,153 @@
+use aphreco::data::DataError;
+use aphreco::prelude::*;
+
+use std::fs;
+
+// path of a temporary CSV file with the content.
+fn write_csv(name: &str, content: &str) -> String {
+  let path = std::env::temp_dir().join(format!("aphreco_{}_{}.csv", name, std::process::id()));
+  fs::write(&path, content).unwrap();
+  path.to_str().unwrap().to_string()
+}
+
+const STATES: [(&str, usize); 2] = [("A", 0), ("B", 1)];
+
+#[test]
+fn long_format_with_censoring() {
+  let path = write_csv(
+    "long",
+    "# comment\n\
+     state,time,value,value_err,blq,lloq,WT\n\
+     A,1.0,2.5,0.1,0,0.5,70\n\
+     B,1.0,,,1,0.5,70\n\
+     1,2.0,<0.2,,,,\n\
+     \"A\",2.0,ALQ,,0,,70\n",
+  );
+  // ALQ without uloq is an error
+  match Data::from_csv(&path, CsvFormat::Long, &STATES, &["WT"]) {
+    Err(DataError::MissingLimit { line, column }) => {
+      assert_eq!((line, column.as_str()), (6, "value"))
+    }
+    _ => panic!("ALQ without uloq must be an error."),
+  }
+
+  let path = write_csv(
+    "long_uloq",
+    "state,time,value,value_err,blq,lloq,uloq,WT\n\
+     A,1.0,2.5,0.1,0,0.5,,70\n\
+     B,1.0,,,1,0.5,,70\n\
+     1,2.0,<0.2,,,,,\n\
+     \"A\",2.0,ALQ,,0,,10,70\n",
+  );
+  let data = Data::from_csv(&path, CsvFormat::Long, &STATES, &["WT"]).unwrap();
+  assert_eq!(
+    data.obs,
+    vec![
+      (0, 1.0, 2.5, None, Some(0.1)),
+      (1, 1.0, 0.5, None, None),
+      (1, 2.0, 0.2, None, None),
+      (0, 2.0, 10.0, None, None),
+    ]
+  );
+  assert_eq!(
+    data.censor(),
+    &[
+      Censor::Quantified,
+      Censor::Below(0.5),
+      Censor::Below(0.2),
+      Censor::Above(10.0)
+    ]
+  );
+  assert_eq!(data.covariate("WT"), Some(70.0));
+}
+
+#[test]
+fn long_format_blq_without_lloq() {
+  let path = write_csv("long_blq", "state,time,value,blq\nA,1.0,,1\n");
+  match Data::from_csv(&path, CsvFormat::Long, &STATES, &[]) {
+    Err(DataError::MissingLimit { line, column }) => {
+      assert_eq!((line, column.as_str()), (2, "value"))
+    }
+    _ => panic!("BLQ without lloq must be an error."),
+  }
+}
+
+#[test]
+fn wide_format_with_censoring() {
+  let path = write_csv(
+    "wide",
+    "time,A,B,WT\n\
+     0.5,1.0,NA,60\n\
+     1.0,\"2.0\",<0.1,60\n\
+     2.0,>50,.,60\n",
+  );
+  let data = Data::from_csv(&path, CsvFormat::Wide, &STATES, &["WT"]).unwrap();
+  assert_eq!(
+    data.obs,
+    vec![
+      (0, 0.5, 1.0, None, None),
+      (0, 1.0, 2.0, None, None),
+      (1, 1.0, 0.1, None, None),
+      (0, 2.0, 50.0, None, None),
+    ]
+  );
+  assert_eq!(data.censor()[2], Censor::Below(0.1));
+  assert_eq!(data.censor()[3], Censor::Above(50.0));
+  assert_eq!(data.covariate("WT"), Some(60.0));
+
+  // BLQ cells have no limit in the wide format
+  let path = write_csv("wide_blq", "time,A\n1.0,BLQ\n");
+  assert!(matches!(
+    Data::from_csv(&path, CsvFormat::Wide, &STATES, &[]),
+    Err(DataError::MissingLimit { line: 2, .. })
+  ));
+}
+
+#[test]
+fn wide_format_rejects_unknown_columns() {
+  // a typo of a state
+  let path = write_csv("wide_typo", "time,A,b\n1.0,2.0,3.0\n");
+  match Data::from_csv(&path, CsvFormat::Wide, &STATES, &[]) {
+    Err(DataError::UnknownState { line, name }) => assert_eq!((line, name.as_str()), (1, "b")),
+    _ => panic!("unknown columns must be an error."),
+  }
+}
+
+#[test]
+fn malformed_rows() {
+  let read = |name: &str, content: &str| {
+    Data::from_csv(&write_csv(name, content), CsvFormat::Long, &STATES, &["WT"])
+  };
+  assert!(matches!(
+    read("short", "state,time,value\nA,1.0\n"),
+    Err(DataError::RowLength {
+      line: 2,
+      expected: 3,
+      found: 2
+    })
+  ));
+  assert!(matches!(
+    read("value", "state,time,value\nA,1.0,abc\n"),
+    Err(DataError::InvalidValue { line: 2, .. })
+  ));
+  assert!(matches!(
+    read("state", "state,time,value\nC,1.0,2.0\n"),
+    Err(DataError::UnknownState { line: 2, .. })
+  ));
+  assert!(matches!(
+    read("time", "state,time,value\nA,,2.0\n"),
+    Err(DataError::InvalidValue { line: 2, .. })
+  ));
+  assert!(matches!(
+    read("flag", "state,time,value,blq\nA,1.0,2.0,maybe\n"),
+    Err(DataError::InvalidValue { line: 2, .. })
+  ));
+  assert!(matches!(
+    read(
+      "covariate",
+      "state,time,value,WT\nA,1.0,2.0,60\nA,2.0,2.0,70\n"
+    ),
+    Err(DataError::InconsistentCovariate { line: 3, .. })
+  ));
+  assert!(matches!(read("header", ""), Err(DataError::Empty)));
+}
@@ -0,0 +1,105 @@
+use aphreco::prelude::*;
+
+// y[0]' = -y[0] with the algebraic state 0 = 10 y[0] - y[1]^3 - y[1],
+// whose init y[1] = 0 is inconsistent (y[1] = 2 at t = 0).
+#[derive(Clone)]
+struct Algebraic;
+
+impl SimModelTrait<2, 0, 0> for Algebraic {
+  fn new() -> Self {
+    Self
+  }
+
+  fn init(&self) -> (f64, [f64; 2]) {
+    (0.0, [1.0, 0.0])
+  }
+
+  fn ode(&self, _t: &f64, y: &[f64; 2], deriv_y: &mut [f64; 2]) {
+    deriv_y[0] = -y[0];
+    deriv_y[1] = 10.0 * y[0] - y[1].powi(3) - y[1];
+  }
+
+  fn rec(&self, _t: &f64, _y: &[f64; 2], _delta_y: &mut [f64; 2], _act: &[bool; 0]) {}
+
+  fn cond(&self, _dec_t: &Decimal, _act: &mut [bool; 0], _next_t: &[Decimal; 0], _y: &[f64; 2]) {}
+
+  fn beat(&self, _t: &f64, _y: &[f64; 2]) -> [[Decimal; 3]; 0] {
+    []
+  }
+
+  fn cre(&self, _t: &f64, _y: &mut [f64; 2]) {}
+
+  fn mass(&self) -> [f64; 2] {
+    [1.0, 0.0]
+  }
+}
+
+#[test]
+fn inconsistent_algebraic_init_is_projected() {
+  let simulator = Simulator::new(Algebraic::new(), Stepper::Radau(StepOptions::Default));
+  let simres = simulator.run(&[1e-3, 0.5, 1.0, 2.0]);
+
+  for (t, y) in simres.t.iter().zip(simres.y.iter()) {
+    let exact = (-t).exp();
+    assert!((y[0] - exact).abs() < 1e-5, "t = {}: {:?}", t, y);
+    let residual = 10.0 * exact - y[1].powi(3) - y[1];
+    assert!(residual.abs() < 1e-4, "t = {}: {:?}", t, y);
+  }
+}
+
+// Robertson's stiff chemical kinetics (Hairer & Wanner, 1996).
+#[derive(Clone)]
+struct Robertson;
+
+impl SimModelTrait<3, 0, 0> for Robertson {
+  fn new() -> Self {
+    Self
+  }
+
+  fn init(&self) -> (f64, [f64; 3]) {
+    (0.0, [1.0, 0.0, 0.0])
+  }
+
+  fn ode(&self, _t: &f64, y: &[f64; 3], deriv_y: &mut [f64; 3]) {
+    deriv_y[0] = -0.04 * y[0] + 1e4 * y[1] * y[2];
+    deriv_y[1] = 0.04 * y[0] - 1e4 * y[1] * y[2] - 3e7 * y[1] * y[1];
+    deriv_y[2] = 3e7 * y[1] * y[1];
+  }
+
+  fn rec(&self, _t: &f64, _y: &[f64; 3], _delta_y: &mut [f64; 3], _act: &[bool; 0]) {}
+
+  fn cond(&self, _dec_t: &Decimal, _act: &mut [bool; 0], _next_t: &[Decimal; 0], _y: &[f64; 3]) {}
+
+  fn beat(&self, _t: &f64, _y: &[f64; 3]) -> [[Decimal; 3]; 0] {
+    []
+  }
+
+  fn cre(&self, _t: &f64, _y: &mut [f64; 3]) {}
+}
+
+#[test]
+fn robertson_at_t_40() {
+  let stepper = Stepper::Radau(StepOptions::Radau {
+    h0: 1e-6,
+    abstol: 1e-10,
+    reltol: 1e-6,
+    hmin: 1e-12,
+    hmax: 1.0,
+  });
+  let simulator = Simulator::new(Robertson::new(), stepper);
+  let simres = simulator.run(&[40.0]);
+
+  // reference solution at t = 40
+  let reference = [7.158271e-1, 9.185535e-6, 2.841637e-1];
+  let y = simres.y[0];
+  for i in 0..3 {
+    assert!(
+      ((y[i] - reference[i]) / reference[i]).abs() < 1e-4,
+      "{:?} != {:?}",
+      y,
+      reference
+    );
+  }
+  // mass is conserved
+  assert!((y.iter().sum::<f64>() - 1.0).abs() < 1e-8);
+}
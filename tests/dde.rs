@@ -0,0 +1,81 @@
+use aphreco::prelude::*;
+
+// y' = -y(t - tau) with y = 1 for t <= 0.
+#[derive(Clone)]
+struct Delayed {
+  tau: f64,
+}
+
+impl SimModelTrait<1, 0, 0> for Delayed {
+  fn new() -> Self {
+    Self { tau: 1.0 }
+  }
+
+  fn init(&self) -> (f64, [f64; 1]) {
+    (0.0, [1.0])
+  }
+
+  fn ode(&self, _t: &f64, _y: &[f64; 1], _deriv_y: &mut [f64; 1]) {
+    unimplemented!("solved by run_dde.");
+  }
+
+  fn rec(&self, _t: &f64, _y: &[f64; 1], _delta_y: &mut [f64; 1], _act: &[bool; 0]) {}
+
+  fn cond(&self, _dec_t: &Decimal, _act: &mut [bool; 0], _next_t: &[Decimal; 0], _y: &[f64; 1]) {}
+
+  fn beat(&self, _t: &f64, _y: &[f64; 1]) -> [[Decimal; 3]; 0] {
+    []
+  }
+
+  fn cre(&self, _t: &f64, _y: &mut [f64; 1]) {}
+}
+
+impl DdeModelTrait<1, 0, 0> for Delayed {
+  fn delays(&self) -> Vec<f64> {
+    vec![self.tau]
+  }
+
+  fn dde(&self, t: &f64, _y: &[f64; 1], history: &History<1>, deriv_y: &mut [f64; 1]) {
+    deriv_y[0] = -history.lag(t, self.tau)[0];
+  }
+}
+
+// solution by the method of steps, a polynomial on each interval of the delay
+fn exact(t: f64) -> f64 {
+  let mut y = 1.0 - t;
+  if t > 1.0 {
+    y += (t - 1.0).powi(2) / 2.0;
+  }
+  if t > 2.0 {
+    y -= (t - 2.0).powi(3) / 6.0;
+  }
+  y
+}
+
+#[test]
+fn dde_follows_method_of_steps() {
+  let smp_t = [0.5, 1.0, 1.5, 2.0, 2.5, 3.0];
+  for stepper in [
+    Stepper::Rk4(StepOptions::Rk4 { h: 1e-2 }),
+    Stepper::Dopri45(StepOptions::Default),
+  ] {
+    let simulator = Simulator::new(Delayed::new(), stepper);
+    let simres = simulator.run_dde(&smp_t);
+    for (t, y) in simres.t.iter().zip(simres.y.iter()) {
+      assert!(
+        (y[0] - exact(*t)).abs() < 1e-6,
+        "t = {}: {} != {}",
+        t,
+        y[0],
+        exact(*t)
+      );
+    }
+  }
+}
+
+#[test]
+#[should_panic(expected = "delays must be positive.")]
+fn dde_rejects_non_positive_delays() {
+  let simulator = Simulator::new(Delayed { tau: 0.0 }, Stepper::Rk4(StepOptions::Default));
+  simulator.run_dde(&[1.0]);
+}
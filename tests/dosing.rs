@@ -0,0 +1,49 @@
+use aphreco::prelude::*;
+
+// y[0] receives doses, and beat 0 adds 1 to y[1] whenever cond is evaluated.
+#[derive(Clone)]
+struct Dosed;
+
+impl SimModelTrait<2, 1, 1> for Dosed {
+  fn new() -> Self {
+    Self
+  }
+
+  fn init(&self) -> (f64, [f64; 2]) {
+    (0.0, [0.0, 0.0])
+  }
+
+  fn ode(&self, _t: &f64, _y: &[f64; 2], deriv_y: &mut [f64; 2]) {
+    deriv_y[0] = 0.0;
+    deriv_y[1] = 0.0;
+  }
+
+  fn rec(&self, _t: &f64, _y: &[f64; 2], delta_y: &mut [f64; 2], act: &[bool; 1]) {
+    if act[0] {
+      delta_y[1] += 1.0;
+    }
+  }
+
+  fn cond(&self, _dec_t: &Decimal, act: &mut [bool; 1], _next_t: &[Decimal; 1], _y: &[f64; 2]) {
+    act[0] = true;
+  }
+
+  fn beat(&self, _t: &f64, _y: &[f64; 2]) -> [[Decimal; 3]; 1] {
+    [beat![0.0, 100.0, 0.3]]
+  }
+
+  fn cre(&self, _t: &f64, _y: &mut [f64; 2]) {}
+}
+
+#[test]
+fn repeated_boluses_coinciding_with_beats() {
+  // dose times 0.1 * n are not exactly the beat times 0.3, 0.6 and 0.9
+  // (e.g. 0.1 * 3 = 0.30000000000000004), but each bolus is given once.
+  let regimen = DosingRegimen::new().repeat(Dose::bolus(0.0, 1.0, 0), 0.1, 10);
+  let simulator = Simulator::new(Dosed, Stepper::Rk4(StepOptions::Default)).with_regimen(regimen);
+  let simres = simulator.run(&[1.0]);
+
+  assert_eq!(simres.y[0][0], 10.0);
+  // cond is evaluated at the beat times 0.0, 0.3, 0.6, 0.9 and the end time 1.0 only.
+  assert_eq!(simres.y[0][1], 5.0);
+}
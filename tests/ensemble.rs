@@ -0,0 +1,73 @@
+use aphreco::prelude::*;
+
+use ndarray::arr1;
+
+// constant y = p = [a, b].
+#[derive(Clone)]
+struct Constants {
+  p: [f64; 2],
+}
+
+impl SimModelTrait<2, 2, 0> for Constants {
+  fn new() -> Self {
+    Self { p: [2.0, 5.0] }
+  }
+
+  fn init(&self) -> (f64, [f64; 2]) {
+    (0.0, self.p)
+  }
+
+  fn ode(&self, _t: &f64, _y: &[f64; 2], deriv_y: &mut [f64; 2]) {
+    *deriv_y = [0.0; 2];
+  }
+
+  fn rec(&self, _t: &f64, _y: &[f64; 2], _delta_y: &mut [f64; 2], _act: &[bool; 0]) {}
+
+  fn cond(&self, _dec_t: &Decimal, _act: &mut [bool; 0], _next_t: &[Decimal; 0], _y: &[f64; 2]) {}
+
+  fn beat(&self, _t: &f64, _y: &[f64; 2]) -> [[Decimal; 3]; 0] {
+    []
+  }
+
+  fn cre(&self, _t: &f64, _y: &mut [f64; 2]) {}
+}
+
+impl OptModelTrait<2, 2, 0, 2> for Constants {
+  fn getp(&self) -> &[f64; 2] {
+    &self.p
+  }
+
+  fn getx(&self) -> (Vec<usize>, Option<Vec<(f64, f64)>>) {
+    (vec![0, 1], None)
+  }
+
+  fn setp(&mut self, index: usize, value: f64) {
+    self.p[index] = value;
+  }
+}
+
+#[test]
+fn ensemble_summarizes_samples() {
+  let simulator = Simulator::new(Constants::new(), Stepper::Rk4(StepOptions::Rk4 { h: 0.1 }));
+
+  // b = 1, ..., 100 and a failed sample, while a stays at the value in a model
+  let mut samples: Vec<_> = (1..=100).map(|b| arr1(&[b as f64])).collect();
+  samples.push(arr1(&[f64::NAN]));
+  let ensres = simulator.ensemble(&[0.1, 0.2], &[1], &samples);
+
+  assert_eq!((ensres.n_samples, ensres.n_dropped), (100, 1));
+  assert_eq!(ensres.mean.t, vec![0.1, 0.2]);
+  for i in 0..2 {
+    assert_eq!(ensres.mean.y[i], [2.0, 50.5]);
+    assert_eq!(ensres.q05.y[i], [2.0, 5.95]);
+    assert_eq!(ensres.q50.y[i], [2.0, 50.5]);
+    assert!((ensres.q95.y[i][1] - 95.05).abs() < 1e-12);
+  }
+}
+
+#[test]
+#[should_panic(expected = "please give one or more parameter samples.")]
+fn ensemble_requires_samples() {
+  let simulator = Simulator::new(Constants::new(), Stepper::Rk4(StepOptions::Rk4 { h: 0.1 }));
+  simulator.ensemble(&[0.1], &[1], &[]);
+}
@@ -0,0 +1,92 @@
+use aphreco::prelude::*;
+
+// integral of the exogenous input y' = u.
+#[derive(Clone)]
+struct Integrator;
+
+impl SimModelTrait<1, 0, 0> for Integrator {
+  fn new() -> Self {
+    Self
+  }
+
+  fn init(&self) -> (f64, [f64; 1]) {
+    (0.0, [0.0])
+  }
+
+  fn ode(&self, _t: &f64, _y: &[f64; 1], deriv_y: &mut [f64; 1]) {
+    deriv_y[0] = 0.0;
+  }
+
+  fn ode_input(&self, _t: &f64, _y: &[f64; 1], u: &[f64], deriv_y: &mut [f64; 1]) {
+    deriv_y[0] = u[0];
+  }
+
+  fn rec(&self, _t: &f64, _y: &[f64; 1], _delta_y: &mut [f64; 1], _act: &[bool; 0]) {}
+
+  fn cond(&self, _dec_t: &Decimal, _act: &mut [bool; 0], _next_t: &[Decimal; 0], _y: &[f64; 1]) {}
+
+  fn beat(&self, _t: &f64, _y: &[f64; 1]) -> [[Decimal; 3]; 0] {
+    []
+  }
+
+  fn cre(&self, _t: &f64, _y: &mut [f64; 1]) {}
+}
+
+fn integrate(input: Input, smp_t: &[f64]) -> Vec<f64> {
+  let simulator = Simulator::new(
+    Integrator::new(),
+    Stepper::Rk4(StepOptions::Rk4 { h: 0.25 }),
+  )
+  .with_inputs(vec![input]);
+  simulator.run(smp_t).y.iter().map(|y| y[0]).collect()
+}
+
+#[test]
+fn interpolation_of_inputs() {
+  let (t, u) = (vec![0.0, 1.0, 2.0, 3.0], vec![0.0, 0.0, 1.0, 1.0]);
+  let constant = Input::piecewise_constant(t.clone(), u.clone());
+  let linear = Input::piecewise_linear(t.clone(), u.clone());
+  let cubic = Input::cubic(t, u);
+
+  for input in [&constant, &linear, &cubic] {
+    // the table and held values outside it
+    assert_eq!(input.value(-1.0), 0.0);
+    assert_eq!(input.value(2.0), 1.0);
+    assert_eq!(input.value(4.0), 1.0);
+  }
+  assert_eq!(constant.value(1.5), 0.0);
+  assert_eq!(linear.value(1.5), 0.5);
+  assert!((cubic.value(1.5) - 0.5).abs() < 1e-12);
+
+  // the monotone cubic does not overshoot the flat parts
+  for i in 0..=300 {
+    let value = cubic.value(i as f64 / 100.0);
+    assert!((0.0..=1.0).contains(&value), "{}", value);
+  }
+}
+
+#[test]
+fn simulation_restarts_at_breakpoints() {
+  // u = 1, 3, 0 on [0, 0.7), [0.7, 1.7), [1.7, ..), whose breakpoints are
+  // inside steps of h = 0.25, and y is linear between them
+  let input = Input::piecewise_constant(vec![0.0, 0.7, 1.7], vec![1.0, 3.0, 0.0]);
+  let y = integrate(input, &[0.5, 0.7, 1.0, 1.5, 2.0, 3.0]);
+  let expected = [0.5, 0.7, 1.6, 3.1, 3.7, 3.7];
+  for (y, expected) in y.iter().zip(expected.iter()) {
+    assert!((y - expected).abs() < 1e-12, "{} != {}", y, expected);
+  }
+
+  // u = t up to 2, which Rk4 integrates exactly at its steps
+  let input = Input::piecewise_linear(vec![0.0, 2.0], vec![0.0, 2.0]);
+  let y = integrate(input, &[0.5, 1.0, 2.0, 3.0]);
+  let expected = [0.125, 0.5, 2.0, 4.0];
+  for (y, expected) in y.iter().zip(expected.iter()) {
+    assert!((y - expected).abs() < 1e-12, "{} != {}", y, expected);
+  }
+}
+
+#[test]
+#[should_panic(expected = "t of an input must be strictly increasing.")]
+fn input_requires_increasing_times() {
+  Input::piecewise_linear(vec![0.0, 1.0, 1.0], vec![0.0, 1.0, 2.0]);
+}
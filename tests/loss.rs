@@ -0,0 +1,74 @@
+use aphreco::prelude::*;
+
+use ndarray::Array1;
+
+fn robust_losses() -> [Loss; 4] {
+  [
+    Loss::Huber { delta: 1.5 },
+    Loss::Cauchy { c: 1.5 },
+    Loss::SoftL1 { c: 1.5 },
+    Loss::Tukey { c: 1.5 },
+  ]
+}
+
+#[test]
+fn robust_losses_are_quadratic_for_small_residuals() {
+  for loss in robust_losses() {
+    for r in [-1e-3, 1e-4, 1e-3] {
+      let rho = loss.rho(r);
+      assert!(
+        ((rho - r * r) / (r * r)).abs() < 1e-6,
+        "{} != {}",
+        rho,
+        r * r
+      );
+    }
+  }
+}
+
+#[test]
+fn robust_losses_down_weight_outliers() {
+  // Huber is linear and Tukey is constant outside the scale
+  assert_eq!(
+    Loss::Huber { delta: 1.5 }.rho(10.0),
+    2.0 * 1.5 * 10.0 - 1.5 * 1.5
+  );
+  assert_eq!(Loss::Tukey { c: 1.5 }.rho(10.0), 1.5 * 1.5 / 3.0);
+  for loss in robust_losses() {
+    assert!(loss.rho(10.0) < 100.0);
+  }
+}
+
+#[test]
+fn robust_losses_scale_residuals_by_sd() {
+  let arr_res_y = Array1::from(vec![3.0, -0.5]);
+  let arr_obs_sd = Array1::from(vec![2.0, 0.5]);
+  let censor = [Censor::Quantified; 2];
+  for loss in robust_losses() {
+    let expected = loss.rho(1.5) + loss.rho(-1.0);
+    assert_eq!(loss.eval(&arr_res_y, &arr_obs_sd, &censor), expected);
+  }
+  // SSR is not weighted
+  assert_eq!(Loss::Ssr.eval(&arr_res_y, &arr_obs_sd, &censor), 9.25);
+}
+
+#[test]
+fn censored_observations_count_only_in_gaussian() {
+  // r = LLOQ - sim = 0 at the second observation
+  let arr_res_y = Array1::from(vec![1.0, 0.0, 0.0]);
+  let arr_obs_sd = Array1::from(vec![1.0, 1.0, 1.0]);
+  let censor = [Censor::Quantified, Censor::Below(2.0), Censor::Above(3.0)];
+  for loss in robust_losses().into_iter().chain([Loss::Ssr]) {
+    assert_eq!(loss.eval(&arr_res_y, &arr_obs_sd, &censor), loss.rho(1.0));
+  }
+
+  // probabilities of 1/2 below LLOQ and above ULOQ
+  let gaussian = Loss::Gaussian.eval(&arr_res_y, &arr_obs_sd, &censor);
+  let expected = 1.0 + (2.0 * std::f64::consts::PI).ln() + 2.0 * 2.0 * 2f64.ln();
+  assert!(
+    (gaussian - expected).abs() < 1e-6,
+    "{} != {}",
+    gaussian,
+    expected
+  );
+}
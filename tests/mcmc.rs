@@ -0,0 +1,132 @@
+use aphreco::mcmc::McmcResult;
+use aphreco::prelude::*;
+
+// constant y = mu, which panics if panics is set.
+#[derive(Clone)]
+struct Constant {
+  p: [f64; 1],
+  panics: bool,
+}
+
+impl SimModelTrait<1, 1, 0> for Constant {
+  fn new() -> Self {
+    Self {
+      p: [2.0],
+      panics: false,
+    }
+  }
+
+  fn init(&self) -> (f64, [f64; 1]) {
+    if self.panics {
+      panic!("model failed.");
+    }
+    (0.0, [self.p[0]])
+  }
+
+  fn ode(&self, _t: &f64, _y: &[f64; 1], deriv_y: &mut [f64; 1]) {
+    deriv_y[0] = 0.0;
+  }
+
+  fn rec(&self, _t: &f64, _y: &[f64; 1], _delta_y: &mut [f64; 1], _act: &[bool; 0]) {}
+
+  fn cond(&self, _dec_t: &Decimal, _act: &mut [bool; 0], _next_t: &[Decimal; 0], _y: &[f64; 1]) {}
+
+  fn beat(&self, _t: &f64, _y: &[f64; 1]) -> [[Decimal; 3]; 0] {
+    []
+  }
+
+  fn cre(&self, _t: &f64, _y: &mut [f64; 1]) {}
+}
+
+impl OptModelTrait<1, 1, 0, 1> for Constant {
+  fn getp(&self) -> &[f64; 1] {
+    &self.p
+  }
+
+  fn getx(&self) -> (Vec<usize>, Option<Vec<(f64, f64)>>) {
+    (vec![0], None)
+  }
+
+  fn setp(&mut self, index: usize, value: f64) {
+    self.p[index] = value;
+  }
+}
+
+// with a flat prior, the posterior of mu is N(mean(y), sd^2 / n) = N(2.5, 0.5^2).
+fn objective(model: Constant) -> Objective<Constant, 1, 1, 0, 1> {
+  let obs = [1.0, 2.0, 3.0, 4.0]
+    .iter()
+    .map(|&y| (0, 0.5, y, None, Some(1.0)))
+    .collect();
+  let simulator = Simulator::new(model, Stepper::Rk4(StepOptions::Default));
+  let mut objective = Objective::new(simulator, Data::new(obs));
+  objective.set_loss(Loss::Gaussian).unwrap();
+  objective
+}
+
+fn check_gaussian_posterior(mcmcres: &McmcResult) {
+  let mean = mcmcres.mean()[0];
+  let sd = mcmcres.sd()[0];
+  assert!((mean - 2.5).abs() < 0.05, "mean: {}", mean);
+  assert!((sd - 0.5).abs() < 0.05, "sd: {}", sd);
+  assert!(mcmcres.rhat[0] < 1.05, "rhat: {}", mcmcres.rhat[0]);
+}
+
+#[test]
+fn ensemble_samples_gaussian_posterior() {
+  let sampler = Sampler::Ensemble(SampleOptions::Ensemble {
+    n_iter: 2000,
+    n_walkers: 8,
+    burn_in: 500,
+    thin: 1,
+    stretch: 2.0,
+    seed: Some(1),
+    verbose: false,
+  });
+  check_gaussian_posterior(&sampler.run(&mut objective(Constant::new())));
+}
+
+#[test]
+fn adaptive_metropolis_samples_gaussian_posterior() {
+  let sampler = Sampler::AdaptiveMetropolis(SampleOptions::AdaptiveMetropolis {
+    n_iter: 5000,
+    n_chains: 4,
+    burn_in: 1000,
+    thin: 1,
+    seed: Some(1),
+    verbose: false,
+  });
+  check_gaussian_posterior(&sampler.run(&mut objective(Constant::new())));
+}
+
+#[test]
+#[should_panic(expected = "model failed.")]
+fn ensemble_resumes_panics_of_walkers() {
+  let mut model = Constant::new();
+  model.panics = true;
+  let sampler = Sampler::Ensemble(SampleOptions::Ensemble {
+    n_iter: 10,
+    n_walkers: 8,
+    burn_in: 0,
+    thin: 1,
+    stretch: 2.0,
+    seed: Some(1),
+    verbose: false,
+  });
+  sampler.run(&mut objective(model));
+}
+
+#[test]
+#[should_panic(expected = "stretch must be greater than 1")]
+fn ensemble_rejects_stretch_of_one() {
+  let sampler = Sampler::Ensemble(SampleOptions::Ensemble {
+    n_iter: 10,
+    n_walkers: 8,
+    burn_in: 0,
+    thin: 1,
+    stretch: 1.0,
+    seed: Some(1),
+    verbose: false,
+  });
+  sampler.run(&mut objective(Constant::new()));
+}
@@ -0,0 +1,99 @@
+use aphreco::prelude::*;
+use ndarray::arr1;
+
+// Wiener process dy = dW with drift p[0] = 0.
+#[derive(Clone)]
+struct Wiener {
+  p: [f64; 1],
+}
+
+impl SimModelTrait<1, 1, 0> for Wiener {
+  fn new() -> Self {
+    Self { p: [0.0] }
+  }
+
+  fn init(&self) -> (f64, [f64; 1]) {
+    (0.0, [0.0])
+  }
+
+  fn ode(&self, _t: &f64, _y: &[f64; 1], deriv_y: &mut [f64; 1]) {
+    deriv_y[0] = self.p[0];
+  }
+
+  fn rec(&self, _t: &f64, _y: &[f64; 1], _delta_y: &mut [f64; 1], _act: &[bool; 0]) {}
+
+  fn cond(&self, _dec_t: &Decimal, _act: &mut [bool; 0], _next_t: &[Decimal; 0], _y: &[f64; 1]) {}
+
+  fn beat(&self, _t: &f64, _y: &[f64; 1]) -> [[Decimal; 3]; 0] {
+    []
+  }
+
+  fn cre(&self, _t: &f64, _y: &mut [f64; 1]) {}
+
+  fn diff(&self, _t: &f64, _y: &[f64; 1], diff_y: &mut [f64; 1]) {
+    diff_y[0] = 1.0;
+  }
+}
+
+impl OptModelTrait<1, 1, 0, 1> for Wiener {
+  fn getp(&self) -> &[f64; 1] {
+    &self.p
+  }
+
+  fn getx(&self) -> (Vec<usize>, Option<Vec<(f64, f64)>>) {
+    (vec![0], None)
+  }
+
+  fn setp(&mut self, index: usize, value: f64) {
+    self.p[index] = value;
+  }
+}
+
+#[test]
+fn ensemble_members_have_their_own_wiener_paths() {
+  for stepper in [
+    Stepper::EulerMaruyama(StepOptions::EulerMaruyama {
+      h: 1e-2,
+      seed: Some(1),
+    }),
+    Stepper::Milstein(StepOptions::Milstein {
+      h: 1e-2,
+      seed: Some(1),
+    }),
+  ] {
+    let simulator = Simulator::new(Wiener::new(), stepper);
+    let samples = vec![arr1(&[0.0]); 20];
+    let ensres = simulator.ensemble(&[1.0], &[0], &samples);
+
+    // the same parameters give different paths, which are reproducible
+    assert!(ensres.q05.y[0][0] < ensres.q95.y[0][0]);
+    let again = simulator.ensemble(&[1.0], &[0], &samples);
+    assert_eq!(ensres.mean.y, again.mean.y);
+  }
+}
+
+#[test]
+fn ensembles_of_adjacent_seeds_do_not_share_paths() {
+  let ensemble = |seed, n_samples| {
+    let stepper = Stepper::EulerMaruyama(StepOptions::EulerMaruyama {
+      h: 1e-2,
+      seed: Some(seed),
+    });
+    let simulator = Simulator::new(Wiener::new(), stepper);
+    simulator.ensemble(&[1.0], &[0], &vec![arr1(&[0.0]); n_samples])
+  };
+
+  // paths of samples 0 and 1 of seed 1 from the quantiles of two samples
+  let ensres = ensemble(1, 2);
+  let (q05, q95) = (ensres.q05.y[0][0], ensres.q95.y[0][0]);
+  let paths = [
+    (0.95 * q05 - 0.05 * q95) / 0.9,
+    (0.95 * q95 - 0.05 * q05) / 0.9,
+  ];
+
+  // sample 0 of seed 2 is neither of them
+  let path = ensemble(2, 1).mean.y[0][0];
+  for other in paths {
+    assert!((path - other).abs() > 1e-6, "{} == {}", path, other);
+  }
+}
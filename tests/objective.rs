@@ -0,0 +1,210 @@
+use aphreco::objective::ObjectiveError;
+use aphreco::prelude::*;
+
+// one-compartment elimination y = y0 exp(-k t), p = [k, y0].
+#[derive(Clone)]
+struct Elimination {
+  p: [f64; 2],
+}
+
+impl SimModelTrait<1, 2, 0> for Elimination {
+  fn new() -> Self {
+    Self { p: [0.5, 10.0] }
+  }
+
+  fn init(&self) -> (f64, [f64; 1]) {
+    (0.0, [self.p[1]])
+  }
+
+  fn ode(&self, _t: &f64, y: &[f64; 1], deriv_y: &mut [f64; 1]) {
+    deriv_y[0] = -self.p[0] * y[0];
+  }
+
+  fn rec(&self, _t: &f64, _y: &[f64; 1], _delta_y: &mut [f64; 1], _act: &[bool; 0]) {}
+
+  fn cond(&self, _dec_t: &Decimal, _act: &mut [bool; 0], _next_t: &[Decimal; 0], _y: &[f64; 1]) {}
+
+  fn beat(&self, _t: &f64, _y: &[f64; 1]) -> [[Decimal; 3]; 0] {
+    []
+  }
+
+  fn cre(&self, _t: &f64, _y: &mut [f64; 1]) {}
+}
+
+impl OptModelTrait<1, 2, 0, 2> for Elimination {
+  fn getp(&self) -> &[f64; 2] {
+    &self.p
+  }
+
+  fn getx(&self) -> (Vec<usize>, Option<Vec<(f64, f64)>>) {
+    (vec![0, 1], Some(vec![(0.01, 5.0), (1.0, 100.0)]))
+  }
+
+  fn setp(&mut self, index: usize, value: f64) {
+    self.p[index] = value;
+  }
+}
+
+fn simulator() -> Simulator<Elimination, 1, 2, 0> {
+  Simulator::new(Elimination::new(), Stepper::Rk4(StepOptions::Default))
+}
+
+#[test]
+#[should_panic(expected = "y[1] in data is out of range.")]
+fn objective_rejects_states_out_of_range() {
+  let data = Data::new(vec![(0, 1.0, 6.0, None, None), (1, 2.0, 3.0, None, None)]);
+  Objective::new(simulator(), data);
+}
+
+#[test]
+fn objective_rejects_non_positive_scales() {
+  let data = Data::new(vec![(0, 1.0, 6.0, None, None)]);
+  let mut objective = Objective::new(simulator(), data);
+  for c in [0.0, -1.0, f64::NAN] {
+    assert!(matches!(
+      objective.set_loss(Loss::Cauchy { c }),
+      Err(ObjectiveError::InvalidScale(_))
+    ));
+    assert!(matches!(objective.loss(), Loss::Ssr));
+  }
+  objective.set_loss(Loss::Huber { delta: 1.0 }).unwrap();
+  assert!(matches!(objective.loss(), Loss::Huber { .. }));
+}
+
+#[test]
+fn gaussian_loss_requires_yerr() {
+  let data = Data::new(vec![
+    (0, 1.0, 6.0, None, Some(0.5)),
+    (0, 2.0, 3.0, None, None),
+  ]);
+  let mut objective = Objective::new(simulator(), data);
+  assert!(matches!(
+    objective.set_loss(Loss::Gaussian),
+    Err(ObjectiveError::MissingYerr)
+  ));
+
+  let data = Data::new(vec![(0, 1.0, 6.0, None, Some(0.5))]);
+  let mut objective = objective.with_data(data);
+  objective.set_loss(Loss::Gaussian).unwrap();
+}
+
+#[test]
+#[should_panic(expected = "the Gaussian loss requires yerr of all observations.")]
+fn gaussian_objective_rejects_data_without_yerr() {
+  let data = Data::new(vec![(0, 1.0, 6.0, None, Some(0.5))]);
+  let mut objective = Objective::new(simulator(), data);
+  objective.set_loss(Loss::Gaussian).unwrap();
+  objective.with_data(Data::new(vec![(0, 1.0, 6.0, None, None)]));
+}
+
+#[test]
+fn prior_penalties() {
+  assert_eq!(Prior::Normal { mean: 1.0, sd: 2.0 }.penalty(5.0), 4.0);
+  let e = std::f64::consts::E;
+  let lognormal = Prior::LogNormal {
+    mu: 0.0,
+    sigma: 0.5,
+  };
+  assert!((lognormal.penalty(e) - 6.0).abs() < 1e-12);
+  assert_eq!(lognormal.penalty(0.0), f64::INFINITY);
+  let uniform = Prior::Uniform { lb: 0.0, ub: 1.0 };
+  assert_eq!(uniform.penalty(0.5), 0.0);
+  assert_eq!(uniform.penalty(1.5), f64::INFINITY);
+  assert_eq!(
+    Prior::L1 {
+      center: 1.0,
+      lambda: 2.0
+    }
+    .penalty(-1.0),
+    4.0
+  );
+  assert_eq!(
+    Prior::L2 {
+      center: 1.0,
+      lambda: 2.0
+    }
+    .penalty(-1.0),
+    8.0
+  );
+}
+
+#[test]
+#[should_panic(expected = "p[2] is not in x_index.")]
+fn prior_requires_a_parameter_in_x() {
+  let data = Data::new(vec![(0, 1.0, 6.0, None, None)]);
+  Objective::new(simulator(), data).add_prior(
+    2,
+    Prior::L1 {
+      center: 0.0,
+      lambda: 1.0,
+    },
+  );
+}
+
+// y = 10 exp(-0.5 t) without noise.
+fn elimination_data() -> Data {
+  let obs = [1.0, 2.0, 4.0]
+    .iter()
+    .map(|&t: &f64| (0, t, 10.0 * (-0.5 * t).exp(), None, None))
+    .collect();
+  Data::new(obs)
+}
+
+fn nelder_mead(max_iter: u64) -> Optimizer {
+  Optimizer::NelderMead(OptOptions::NelderMead {
+    max_iter,
+    adaptive: true,
+    x_abstol: 1e-10,
+    f_abstol: 1e-12,
+    verbose: false,
+  })
+}
+
+#[test]
+fn prior_pulls_the_optimum() {
+  let mut objective = Objective::new(simulator(), elimination_data());
+  objective.add_prior(
+    1,
+    Prior::Normal {
+      mean: 12.0,
+      sd: 0.1,
+    },
+  );
+  let optres = nelder_mead(0).run(&mut objective);
+
+  // y0 between the data (10) and the prior (12), close to the prior
+  assert!(11.0 < optres.x[1] && optres.x[1] < 12.0, "{}", optres.x[1]);
+  let arr_res_y = objective.residuals(&optres.x);
+  assert_eq!(optres.f_data, objective.data_loss(&arr_res_y));
+  assert_eq!(optres.f_prior, objective.penalty(&optres.x));
+  assert!((optres.f - optres.f_data - optres.f_prior).abs() < 1e-9);
+  assert!(optres.f_data > 0.0 && optres.f_prior > 0.0);
+
+  // parameters followed by f, f_data and f_prior
+  let dir = std::env::temp_dir().join(format!("aphreco_optres_{}", std::process::id()));
+  std::fs::create_dir_all(&dir).unwrap();
+  optres.save(dir.to_str().unwrap());
+  let saved = std::fs::read_to_string(dir.join("optres.csv")).unwrap();
+  let lines: Vec<&str> = saved.lines().collect();
+  assert_eq!(lines.len(), 5);
+  assert_eq!(lines[3], format!("f_data,{}", optres.f_data));
+  assert_eq!(lines[4], format!("f_prior,{}", optres.f_prior));
+}
+
+#[test]
+fn data_part_is_finite_for_an_infinite_penalty() {
+  // the initial simplex is far outside the support of the prior
+  let mut objective = Objective::new(simulator(), elimination_data());
+  objective.add_prior(
+    0,
+    Prior::Uniform {
+      lb: 100.0,
+      ub: 200.0,
+    },
+  );
+  let optres = nelder_mead(2).run(&mut objective);
+
+  assert_eq!(optres.f, f64::INFINITY);
+  assert_eq!(optres.f_prior, f64::INFINITY);
+  assert!(optres.f_data.is_finite());
+}
@@ -0,0 +1,62 @@
+use aphreco::prelude::*;
+
+// one-compartment model with a bolus dose of 1.0 every tau from t = 0,
+// and elimination rate constant k.
+#[derive(Clone)]
+struct OneCompartment {
+  k: f64,
+  tau: f64,
+}
+
+impl SimModelTrait<1, 1, 1> for OneCompartment {
+  fn new() -> Self {
+    Self { k: 0.5, tau: 2.0 }
+  }
+
+  fn init(&self) -> (f64, [f64; 1]) {
+    (0.0, [0.0])
+  }
+
+  fn ode(&self, _t: &f64, y: &[f64; 1], deriv_y: &mut [f64; 1]) {
+    deriv_y[0] = -self.k * y[0];
+  }
+
+  fn rec(&self, _t: &f64, _y: &[f64; 1], delta_y: &mut [f64; 1], act: &[bool; 1]) {
+    if act[0] {
+      delta_y[0] += 1.0;
+    }
+  }
+
+  fn cond(&self, dec_t: &Decimal, act: &mut [bool; 1], next_t: &[Decimal; 1], _y: &[f64; 1]) {
+    act[0] = *dec_t == next_t[0];
+  }
+
+  fn beat(&self, _t: &f64, _y: &[f64; 1]) -> [[Decimal; 3]; 1] {
+    [beat![0.0, 1000.0, self.tau]]
+  }
+
+  fn cre(&self, _t: &f64, _y: &mut [f64; 1]) {}
+}
+
+#[test]
+fn accumulation_ratio_of_repeated_bolus() {
+  let model = OneCompartment::new();
+  let (k, tau) = (model.k, model.tau);
+  let simulator = Simulator::new(model, Stepper::Rk4(StepOptions::Rk4 { h: 1e-3 }));
+  let periodic = simulator
+    .periodic_steady_state(0, 20, 1e-10, 200)
+    .expect("periodic steady state is not found.");
+
+  // R = 1 / (1 - exp(-k tau)) for troughs, peaks and AUCs
+  // (AUCs by the trapezoidal rule over 20 points)
+  let expected = 1.0 / (1.0 - (-k * tau).exp());
+  let accumulation = [
+    (periodic.accumulation_trough[0], 1e-6),
+    (periodic.accumulation_peak[0], 1e-6),
+    (periodic.accumulation_auc[0], 1e-3),
+  ];
+  for (ratio, tol) in accumulation {
+    assert!((ratio - expected).abs() < tol, "{} != {}", ratio, expected);
+  }
+  assert!((periodic.peak[0] - expected).abs() < 1e-6);
+}
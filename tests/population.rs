@@ -0,0 +1,156 @@
+use aphreco::prelude::*;
+
+use ndarray::{arr1, arr2, Array1};
+
+// constant y = p = [a, b].
+#[derive(Clone)]
+struct Constants {
+  p: [f64; 2],
+}
+
+impl SimModelTrait<2, 2, 0> for Constants {
+  fn new() -> Self {
+    Self { p: [2.0, 5.0] }
+  }
+
+  fn init(&self) -> (f64, [f64; 2]) {
+    (0.0, self.p)
+  }
+
+  fn ode(&self, _t: &f64, _y: &[f64; 2], deriv_y: &mut [f64; 2]) {
+    *deriv_y = [0.0; 2];
+  }
+
+  fn rec(&self, _t: &f64, _y: &[f64; 2], _delta_y: &mut [f64; 2], _act: &[bool; 0]) {}
+
+  fn cond(&self, _dec_t: &Decimal, _act: &mut [bool; 0], _next_t: &[Decimal; 0], _y: &[f64; 2]) {}
+
+  fn beat(&self, _t: &f64, _y: &[f64; 2]) -> [[Decimal; 3]; 0] {
+    []
+  }
+
+  fn cre(&self, _t: &f64, _y: &mut [f64; 2]) {}
+}
+
+impl OptModelTrait<2, 2, 0, 2> for Constants {
+  fn getp(&self) -> &[f64; 2] {
+    &self.p
+  }
+
+  fn getx(&self) -> (Vec<usize>, Option<Vec<(f64, f64)>>) {
+    (vec![0, 1], None)
+  }
+
+  fn setp(&mut self, index: usize, value: f64) {
+    self.p[index] = value;
+  }
+}
+
+fn simulator() -> Simulator<Constants, 2, 2, 0> {
+  Simulator::new(Constants::new(), Stepper::Rk4(StepOptions::Rk4 { h: 0.1 }))
+}
+
+// mean and variance of ln x of subjects for each parameter
+fn log_moments(params: &[Array1<f64>]) -> (Array1<f64>, Array1<f64>) {
+  let n = params.len() as f64;
+  let logs: Vec<Array1<f64>> = params.iter().map(|x| x.mapv(f64::ln)).collect();
+  let mean = logs.iter().fold(Array1::zeros(2), |acc, x| acc + x) / n;
+  let var = logs
+    .iter()
+    .fold(Array1::zeros(2), |acc, x| acc + (x - &mean).mapv(|v| v * v))
+    / (n - 1.0);
+  (mean, var)
+}
+
+#[test]
+fn population_has_log_normal_parameters() {
+  let omega = arr2(&[[0.04, 0.03], [0.03, 0.09]]);
+  let population = Population::new(vec![0, 1], omega.clone(), 4000, Some(1));
+  let popres = population.run(&simulator(), &[0.1]);
+
+  // subjects are simulated with their parameters exp(ln p + eta)
+  for ((x, eta), simres) in popres
+    .params
+    .iter()
+    .zip(popres.eta.iter())
+    .zip(popres.subjects.iter())
+  {
+    assert_eq!(x, &(&arr1(&[2.0, 5.0]) * &eta.mapv(f64::exp)));
+    assert_eq!(simres.y[0], [x[0], x[1]]);
+  }
+
+  // moments and the correlation of ln p
+  let (mean, var) = log_moments(&popres.params);
+  assert!((mean[0] - 2f64.ln()).abs() < 1e-2, "{}", mean);
+  assert!((mean[1] - 5f64.ln()).abs() < 1e-2, "{}", mean);
+  assert!((var[0] - 0.04).abs() < 4e-3, "{}", var);
+  assert!((var[1] - 0.09).abs() < 9e-3, "{}", var);
+  let n = popres.eta.len() as f64;
+  let cov = popres.eta.iter().map(|eta| eta[0] * eta[1]).sum::<f64>() / n;
+  assert!((cov - 0.03).abs() < 5e-3, "{}", cov);
+
+  // and the same subjects for the same seed
+  let again = population.run(&simulator(), &[0.1]);
+  assert_eq!(popres.params, again.params);
+}
+
+#[test]
+fn saem_recovers_population_parameters() {
+  // subjects of theta = [2, 5] and omega^2 = [0.04, 0.09]
+  let population = Population::new(vec![0, 1], arr2(&[[0.04, 0.0], [0.0, 0.09]]), 200, Some(1));
+  let popres = population.run(&simulator(), &[0.1]);
+
+  // three observations of each state with residuals -0.01, 0, 0.01
+  let subjects: Vec<Data> = popres
+    .params
+    .iter()
+    .map(|x| {
+      let mut obs = Vec::new();
+      for (t, e) in [(0.1, -0.01), (0.2, 0.0), (0.3, 0.01)] {
+        obs.push((0, t, x[0] + e, None, None));
+        obs.push((1, t, x[1] + e, None, None));
+      }
+      Data::new(obs)
+    })
+    .collect();
+
+  // start away from the truth with priors and a loss which are ignored
+  let mut model = Constants::new();
+  model.p = [1.0, 10.0];
+  let simulator = Simulator::new(model, Stepper::Rk4(StepOptions::Rk4 { h: 0.1 }));
+  let mut objective = Objective::new(simulator, subjects[0].clone());
+  objective.add_prior(
+    0,
+    Prior::Normal {
+      mean: 1.0,
+      sd: 1e-3,
+    },
+  );
+  objective.set_loss(Loss::Cauchy { c: 1e-3 }).unwrap();
+
+  let saem = Saem::new(100, 100, ErrorModel::Additive, Some(1), false);
+  let nlmeres = saem.run(&objective, &subjects);
+
+  // estimates are the moments of the subjects, which are close to the truth
+  let (mean, var) = log_moments(&popres.params);
+  for k in 0..2 {
+    let theta = mean[k].exp();
+    assert!(
+      (nlmeres.theta[k] / theta - 1.0).abs() < 1e-2,
+      "{}",
+      nlmeres.theta
+    );
+    assert!(
+      (nlmeres.omega2[k] / var[k] - 1.0).abs() < 0.1,
+      "{}",
+      nlmeres.omega2
+    );
+  }
+  assert!(
+    (&nlmeres.theta / &arr1(&[2.0, 5.0]) - 1.0)
+      .mapv(f64::abs)
+      .sum()
+      < 0.1
+  );
+  assert!((nlmeres.sigma - 0.01).abs() < 5e-3, "{}", nlmeres.sigma);
+}
@@ -0,0 +1,84 @@
+use aphreco::prelude::*;
+
+use ndarray::arr1;
+
+// constant y[0] = mu and y[1] = nu, p = [mu, nu].
+#[derive(Clone)]
+struct Constants {
+  p: [f64; 2],
+}
+
+impl SimModelTrait<2, 2, 0> for Constants {
+  fn new() -> Self {
+    Self { p: [2.0, 5.0] }
+  }
+
+  fn init(&self) -> (f64, [f64; 2]) {
+    (0.0, self.p)
+  }
+
+  fn ode(&self, _t: &f64, _y: &[f64; 2], deriv_y: &mut [f64; 2]) {
+    *deriv_y = [0.0; 2];
+  }
+
+  fn rec(&self, _t: &f64, _y: &[f64; 2], _delta_y: &mut [f64; 2], _act: &[bool; 0]) {}
+
+  fn cond(&self, _dec_t: &Decimal, _act: &mut [bool; 0], _next_t: &[Decimal; 0], _y: &[f64; 2]) {}
+
+  fn beat(&self, _t: &f64, _y: &[f64; 2]) -> [[Decimal; 3]; 0] {
+    []
+  }
+
+  fn cre(&self, _t: &f64, _y: &mut [f64; 2]) {}
+}
+
+impl OptModelTrait<2, 2, 0, 2> for Constants {
+  fn getp(&self) -> &[f64; 2] {
+    &self.p
+  }
+
+  fn getx(&self) -> (Vec<usize>, Option<Vec<(f64, f64)>>) {
+    (vec![0, 1], None)
+  }
+
+  fn setp(&mut self, index: usize, value: f64) {
+    self.p[index] = value;
+  }
+}
+
+#[test]
+fn profile_of_gaussian_means() {
+  // mu ~ 1, 2, 3, 4 and nu ~ 5, 7 with SD 1
+  let mut obs = Vec::new();
+  for y in [1.0, 2.0, 3.0, 4.0] {
+    obs.push((0, 0.5, y, None, Some(1.0)));
+  }
+  for y in [5.0, 7.0] {
+    obs.push((1, 0.5, y, None, Some(1.0)));
+  }
+  let simulator = Simulator::new(Constants::new(), Stepper::Rk4(StepOptions::Default));
+  let mut objective = Objective::new(simulator, Data::new(obs));
+  objective.set_loss(Loss::Gaussian).unwrap();
+
+  let optimizer = Optimizer::NelderMead(OptOptions::NelderMead {
+    max_iter: 0,
+    adaptive: true,
+    x_abstol: 1e-10,
+    f_abstol: 1e-12,
+    verbose: false,
+  });
+  let optres = optimizer.run(&mut objective);
+  assert!((&optres.x - &arr1(&[2.5, 6.0])).mapv(f64::abs).sum() < 1e-6);
+
+  // -2 ln L is f + n (mean - mean(y))^2, so that the 95% interval
+  // is mean(y) -+ sqrt(chi2 / n)
+  let profres = ProfileLikelihood::new(40, 0.02, 0.95).run(&mut objective, &optimizer, &optres);
+  let chi2 = 3.841458820694124;
+  assert!((profres.threshold - chi2).abs() < 1e-6);
+  for (profile, (mean, n)) in profres.profiles.iter().zip([(2.5, 4.0), (6.0, 2.0)]) {
+    let half = (chi2 / n).sqrt();
+    let (lower, upper) = (profile.lower.unwrap(), profile.upper.unwrap());
+    assert!((lower - (mean - half)).abs() < 1e-2, "{}", lower);
+    assert!((upper - (mean + half)).abs() < 1e-2, "{}", upper);
+  }
+}
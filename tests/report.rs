@@ -0,0 +1,112 @@
+use aphreco::optimizer::FitReport;
+use aphreco::prelude::*;
+
+use ndarray::arr1;
+
+// constant y = mu.
+#[derive(Clone)]
+struct Constant {
+  p: [f64; 1],
+}
+
+impl SimModelTrait<1, 1, 0> for Constant {
+  fn new() -> Self {
+    Self { p: [2.0] }
+  }
+
+  fn init(&self) -> (f64, [f64; 1]) {
+    (0.0, [self.p[0]])
+  }
+
+  fn ode(&self, _t: &f64, _y: &[f64; 1], deriv_y: &mut [f64; 1]) {
+    deriv_y[0] = 0.0;
+  }
+
+  fn rec(&self, _t: &f64, _y: &[f64; 1], _delta_y: &mut [f64; 1], _act: &[bool; 0]) {}
+
+  fn cond(&self, _dec_t: &Decimal, _act: &mut [bool; 0], _next_t: &[Decimal; 0], _y: &[f64; 1]) {}
+
+  fn beat(&self, _t: &f64, _y: &[f64; 1]) -> [[Decimal; 3]; 0] {
+    []
+  }
+
+  fn cre(&self, _t: &f64, _y: &mut [f64; 1]) {}
+}
+
+impl OptModelTrait<1, 1, 0, 1> for Constant {
+  fn getp(&self) -> &[f64; 1] {
+    &self.p
+  }
+
+  fn getx(&self) -> (Vec<usize>, Option<Vec<(f64, f64)>>) {
+    (vec![0], None)
+  }
+
+  fn setp(&mut self, index: usize, value: f64) {
+    self.p[index] = value;
+  }
+}
+
+// residuals of ys from mu = 2 at t = 1, 2, ..., and an observation below
+// LLOQ = 2 (i.e. with the probability of 1/2) at the end.
+fn fit(ys: &[f64], yerr: Option<f64>) -> FitReport {
+  let mut obs: Vec<Obs> = ys
+    .iter()
+    .enumerate()
+    .map(|(i, &y)| (0, i as f64 + 1.0, y, None, yerr))
+    .collect();
+  obs.push((0, ys.len() as f64 + 1.0, 2.0, None, yerr));
+  let mut censor = vec![Censor::Quantified; ys.len()];
+  censor.push(Censor::Below(2.0));
+
+  let simulator = Simulator::new(Constant::new(), Stepper::Rk4(StepOptions::Rk4 { h: 0.5 }));
+  let mut objective = Objective::new(simulator, Data::with_censor(obs, censor));
+  let mut optres = OptResult::new(arr1(&[2.0]), vec![0], 0.0);
+  optres.fit_report(&mut objective);
+  optres.report.unwrap()
+}
+
+#[test]
+fn likelihood_with_yerr() {
+  let report = fit(&[1.0, 3.0, 1.0, 3.0, 1.0, 3.0], Some(2.0));
+
+  // -2 ln L = sum (r / sd)^2 + ln(2 pi sd^2) of quantified and -2 ln(1/2) of censored
+  let ln_2pi = (2.0 * std::f64::consts::PI).ln();
+  let loglik = -0.5 * (6.0 * (0.25 + ln_2pi + 2.0 * 2f64.ln()) + 2.0 * 2f64.ln());
+  assert!((report.loglik - loglik).abs() < 1e-6, "{}", report.loglik);
+  assert_eq!((report.n_obs, report.n_par), (7, 1));
+  assert!((report.aic - (2.0 - 2.0 * loglik)).abs() < 1e-6);
+  assert!((report.aicc - (report.aic + 4.0 / 5.0)).abs() < 1e-6);
+  assert!((report.bic - (7f64.ln() - 2.0 * loglik)).abs() < 1e-6);
+}
+
+#[test]
+fn likelihood_without_yerr() {
+  let report = fit(&[1.0, 3.0, 1.0, 3.0, 1.0, 3.0], None);
+
+  // SD = 1 estimated from quantified observations as a parameter
+  let loglik = -3.0 * ((2.0 * std::f64::consts::PI).ln() + 1.0);
+  assert!((report.loglik - loglik).abs() < 1e-12, "{}", report.loglik);
+  assert_eq!((report.n_obs, report.n_par), (6, 2));
+  assert!((report.aic - (4.0 - 2.0 * loglik)).abs() < 1e-12);
+  assert!((report.aicc - (report.aic + 4.0)).abs() < 1e-12);
+  assert!((report.bic - (2.0 * 6f64.ln() - 2.0 * loglik)).abs() < 1e-12);
+}
+
+#[test]
+fn runs_of_residuals() {
+  // alternating residuals have 6 runs against 4 expected (variance 1.2)
+  let report = fit(&[1.0, 3.0, 1.0, 3.0, 1.0, 3.0], None);
+  let state = &report.states[0];
+  assert_eq!((state.index, state.n_obs, state.n_runs), (0, 6, 6));
+  assert!(state.mean.abs() < 1e-12);
+  assert!((state.sd - 1.2f64.sqrt()).abs() < 1e-12);
+  assert!((state.z_runs - 2.0 / 1.2f64.sqrt()).abs() < 1e-12);
+
+  // and systematic residuals have 2 runs
+  let report = fit(&[3.0, 3.0, 3.0, 1.0, 1.0, 1.0], None);
+  let state = &report.states[0];
+  assert_eq!(state.n_runs, 2);
+  assert!((state.z_runs + 2.0 / 1.2f64.sqrt()).abs() < 1e-12);
+  assert!(state.p_runs < 0.1);
+}
@@ -0,0 +1,169 @@
+use aphreco::prelude::*;
+
+// one-compartment elimination y = exp(-k t), p = [k].
+#[derive(Clone)]
+struct Elimination {
+  p: [f64; 1],
+}
+
+impl SimModelTrait<1, 1, 0> for Elimination {
+  fn new() -> Self {
+    Self { p: [0.5] }
+  }
+
+  fn init(&self) -> (f64, [f64; 1]) {
+    (0.0, [1.0])
+  }
+
+  fn ode(&self, _t: &f64, y: &[f64; 1], deriv_y: &mut [f64; 1]) {
+    deriv_y[0] = -self.p[0] * y[0];
+  }
+
+  fn rec(&self, _t: &f64, _y: &[f64; 1], _delta_y: &mut [f64; 1], _act: &[bool; 0]) {}
+
+  fn cond(&self, _dec_t: &Decimal, _act: &mut [bool; 0], _next_t: &[Decimal; 0], _y: &[f64; 1]) {}
+
+  fn beat(&self, _t: &f64, _y: &[f64; 1]) -> [[Decimal; 3]; 0] {
+    []
+  }
+
+  fn cre(&self, _t: &f64, _y: &mut [f64; 1]) {}
+}
+
+#[test]
+fn local_sensitivity_of_elimination() {
+  let simulator = Simulator::new(Elimination::new(), Stepper::Rk4(StepOptions::Default));
+  let smp_t = [1.0, 2.0, 4.0];
+  let localres = LocalSensitivity::new(1e-4, vec![0]).run(
+    &simulator,
+    &smp_t,
+    |model: &mut Elimination, index, factor| model.p[index] *= factor,
+  );
+
+  // d ln y / d ln k = -k t
+  assert_eq!(localres.index, vec![0]);
+  for (t, coef_t) in localres.t.iter().zip(localres.coef[0].iter()) {
+    assert!(
+      (coef_t[0] + 0.5 * t).abs() < 1e-6,
+      "t = {}: {}",
+      t,
+      coef_t[0]
+    );
+  }
+}
+
+// y = p[0] + 2 p[1] for p in [0, 1]^2, which panics outside the bounds.
+#[derive(Clone)]
+struct Linear {
+  p: [f64; 2],
+}
+
+impl SimModelTrait<1, 2, 0> for Linear {
+  fn new() -> Self {
+    Self { p: [0.5, 0.5] }
+  }
+
+  fn init(&self) -> (f64, [f64; 1]) {
+    (0.0, [self.p[0] + 2.0 * self.p[1]])
+  }
+
+  fn ode(&self, _t: &f64, _y: &[f64; 1], deriv_y: &mut [f64; 1]) {
+    deriv_y[0] = 0.0;
+  }
+
+  fn rec(&self, _t: &f64, _y: &[f64; 1], _delta_y: &mut [f64; 1], _act: &[bool; 0]) {}
+
+  fn cond(&self, _dec_t: &Decimal, _act: &mut [bool; 0], _next_t: &[Decimal; 0], _y: &[f64; 1]) {}
+
+  fn beat(&self, _t: &f64, _y: &[f64; 1]) -> [[Decimal; 3]; 0] {
+    []
+  }
+
+  fn cre(&self, _t: &f64, _y: &mut [f64; 1]) {}
+}
+
+impl OptModelTrait<1, 2, 0, 2> for Linear {
+  fn getp(&self) -> &[f64; 2] {
+    &self.p
+  }
+
+  fn getx(&self) -> (Vec<usize>, Option<Vec<(f64, f64)>>) {
+    (vec![0, 1], Some(vec![(0.0, 1.0), (0.0, 1.0)]))
+  }
+
+  fn setp(&mut self, index: usize, value: f64) {
+    assert!(
+      (0.0..=1.0).contains(&value),
+      "p[{}] = {} is out of bounds.",
+      index,
+      value
+    );
+    self.p[index] = value;
+  }
+}
+
+#[test]
+fn morris_stays_in_bounds_with_odd_levels() {
+  let simulator = Simulator::new(Linear::new(), Stepper::Rk4(StepOptions::Default));
+  for n_levels in [3, 4, 5] {
+    let morrisres = Morris::new(20, n_levels, vec![(0, 1.0)], Some(1)).run(&simulator);
+
+    // elementary effects of a linear output are its coefficients
+    for (k, coef) in [1.0, 2.0].iter().enumerate() {
+      assert!(
+        (morrisres.mu[[0, k]] - coef).abs() < 1e-9,
+        "{}",
+        morrisres.mu
+      );
+      assert!((morrisres.mu_star[[0, k]] - coef).abs() < 1e-9);
+      assert!(morrisres.sigma[[0, k]] < 1e-9);
+    }
+  }
+}
+
+#[test]
+fn sobol_indices_of_linear_output() {
+  let simulator = Simulator::new(Linear::new(), Stepper::Rk4(StepOptions::Default));
+  let sobolres = Sobol::new(1024, vec![(0, 1.0)]).run(&simulator);
+
+  // Var(p[0] + 2 p[1]) = 5 / 12, shared 1 : 4 without interaction
+  assert!((sobolres.variance[0] - 5.0 / 12.0).abs() < 1e-2);
+  for (k, share) in [0.2, 0.8].iter().enumerate() {
+    assert!(
+      (sobolres.first[[0, k]] - share).abs() < 2e-2,
+      "{}",
+      sobolres.first
+    );
+    assert!(
+      (sobolres.total[[0, k]] - share).abs() < 2e-2,
+      "{}",
+      sobolres.total
+    );
+  }
+}
+
+#[test]
+fn local_sensitivity_is_nan_where_output_is_zero() {
+  // y = 1 - 2 * 0.5 = 0, which changes with both parameters
+  let mut model = Linear::new();
+  model.p = [1.0, -0.5];
+  let simulator = Simulator::new(model, Stepper::Rk4(StepOptions::Default));
+  let localres = LocalSensitivity::new(1e-4, vec![0, 1]).run(
+    &simulator,
+    &[1.0],
+    |model: &mut Linear, index, factor| model.p[index] *= factor,
+  );
+  assert!(localres.coef[0][0][0].is_nan());
+  assert!(localres.coef[1][0][0].is_nan());
+
+  // y = 0 which does not change has no sensitivity
+  let mut model = Linear::new();
+  model.p = [0.0, 0.0];
+  let simulator = Simulator::new(model, Stepper::Rk4(StepOptions::Default));
+  let localres = LocalSensitivity::new(1e-4, vec![0]).run(
+    &simulator,
+    &[1.0],
+    |model: &mut Linear, index, factor| model.p[index] *= factor,
+  );
+  assert_eq!(localres.coef[0][0][0], 0.0);
+}
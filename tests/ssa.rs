@@ -0,0 +1,56 @@
+use aphreco::prelude::*;
+
+// birth-death process 0 -> X at rate k1 and X -> 0 at rate k2 x,
+// whose mean follows the ODE dm/dt = k1 - k2 m exactly.
+#[derive(Clone)]
+struct BirthDeath {
+  k1: f64,
+  k2: f64,
+}
+
+impl ReactionModelTrait<1, 2> for BirthDeath {
+  fn new() -> Self {
+    Self { k1: 10.0, k2: 0.5 }
+  }
+
+  fn init(&self) -> (f64, [f64; 1]) {
+    (0.0, [0.0])
+  }
+
+  fn propensities(&self, _t: &f64, y: &[f64; 1], a: &mut [f64; 2]) {
+    a[0] = self.k1;
+    a[1] = self.k2 * y[0];
+  }
+
+  fn stoichiometry(&self) -> [[f64; 1]; 2] {
+    [[1.0], [-1.0]]
+  }
+}
+
+#[test]
+fn mean_of_birth_death_follows_ode() {
+  let model = BirthDeath::new();
+  let (k1, k2) = (model.k1, model.k2);
+  let smp_t = [0.5, 1.0, 2.0, 5.0];
+
+  for ssa in [
+    Ssa::Direct(SsaOptions::Default),
+    Ssa::NextReaction(SsaOptions::Default),
+    Ssa::TauLeaping(SsaOptions::Default),
+  ] {
+    let simulator = StochasticSimulator::new(model.clone(), ssa, Some(1));
+    let ensres = simulator.ensemble(&smp_t, 2000);
+
+    // the variance is at most k1 / k2 = 20, so that the SE of the mean is about 0.1
+    for (t, mean) in ensres.mean.t.iter().zip(ensres.mean.y.iter()) {
+      let expected = k1 / k2 * (1.0 - (-k2 * t).exp());
+      assert!(
+        (mean[0] - expected).abs() < 0.5,
+        "t = {}: {} != {}",
+        t,
+        mean[0],
+        expected
+      );
+    }
+  }
+}
@@ -0,0 +1,100 @@
+use aphreco::prelude::*;
+
+// turnover y' = k_in - k_out y with y clamped at 0 or more by cre.
+#[derive(Clone)]
+struct Turnover {
+  p: [f64; 2],
+}
+
+impl SimModelTrait<1, 2, 0> for Turnover {
+  fn new() -> Self {
+    Self { p: [2.0, 0.5] }
+  }
+
+  fn init(&self) -> (f64, [f64; 1]) {
+    (0.0, [1.0])
+  }
+
+  fn ode(&self, _t: &f64, y: &[f64; 1], deriv_y: &mut [f64; 1]) {
+    deriv_y[0] = self.p[0] - self.p[1] * y[0];
+  }
+
+  fn rec(&self, _t: &f64, _y: &[f64; 1], _delta_y: &mut [f64; 1], _act: &[bool; 0]) {}
+
+  fn cond(&self, _dec_t: &Decimal, _act: &mut [bool; 0], _next_t: &[Decimal; 0], _y: &[f64; 1]) {}
+
+  fn beat(&self, _t: &f64, _y: &[f64; 1]) -> [[Decimal; 3]; 0] {
+    []
+  }
+
+  fn cre(&self, _t: &f64, y: &mut [f64; 1]) {
+    y[0] = y[0].max(0.0);
+  }
+}
+
+#[test]
+fn steady_state_of_turnover() {
+  let simulator = Simulator::new(Turnover::new(), Stepper::Rk4(StepOptions::Rk4 { h: 1e-2 }));
+  let y = simulator.steady_state(1e-10, 100.0).unwrap();
+  assert!((y[0] - 4.0).abs() < 1e-9, "{:?}", y);
+
+  // which is kept by the simulation from it
+  let simres = simulator.run_from(0.0, y, &[1.0, 10.0]);
+  for y_t in simres.y.iter() {
+    assert!((y_t[0] - 4.0).abs() < 1e-9, "{:?}", y_t);
+  }
+}
+
+#[test]
+fn steady_state_respects_cre() {
+  // f(y) = 0 at y = -1, which cre does not allow, and y stays at 0
+  // with dy/dt = -1 under cre, i.e. no steady state
+  let mut model = Turnover::new();
+  model.p = [-1.0, 1.0];
+  let simulator = Simulator::new(model, Stepper::Rk4(StepOptions::Rk4 { h: 1e-2 }));
+  assert_eq!(simulator.steady_state(1e-10, 10.0), None);
+}
+
+// y[0]' = 1 - y[0] with the algebraic state 0 = 10 y[0] - y[1]^3 - y[1].
+#[derive(Clone)]
+struct Algebraic;
+
+impl SimModelTrait<2, 0, 0> for Algebraic {
+  fn new() -> Self {
+    Self
+  }
+
+  fn init(&self) -> (f64, [f64; 2]) {
+    (0.0, [0.0, 0.0])
+  }
+
+  fn ode(&self, _t: &f64, y: &[f64; 2], deriv_y: &mut [f64; 2]) {
+    deriv_y[0] = 1.0 - y[0];
+    deriv_y[1] = 10.0 * y[0] - y[1].powi(3) - y[1];
+  }
+
+  fn rec(&self, _t: &f64, _y: &[f64; 2], _delta_y: &mut [f64; 2], _act: &[bool; 0]) {}
+
+  fn cond(&self, _dec_t: &Decimal, _act: &mut [bool; 0], _next_t: &[Decimal; 0], _y: &[f64; 2]) {}
+
+  fn beat(&self, _t: &f64, _y: &[f64; 2]) -> [[Decimal; 3]; 0] {
+    []
+  }
+
+  fn cre(&self, _t: &f64, _y: &mut [f64; 2]) {}
+
+  fn mass(&self) -> [f64; 2] {
+    [1.0, 0.0]
+  }
+}
+
+#[test]
+fn steady_state_of_dae() {
+  let simulator = Simulator::new(Algebraic::new(), Stepper::Radau(StepOptions::Default));
+  let y = simulator.steady_state(1e-10, 100.0).unwrap();
+  assert!(
+    (y[0] - 1.0).abs() < 1e-9 && (y[1] - 2.0).abs() < 1e-9,
+    "{:?}",
+    y
+  );
+}
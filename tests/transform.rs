@@ -0,0 +1,151 @@
+use aphreco::prelude::*;
+
+use ndarray::arr1;
+
+// one-compartment elimination y = y0 exp(-k t), p = [k, y0].
+#[derive(Clone)]
+struct Elimination {
+  p: [f64; 2],
+  transforms: Vec<Transform>,
+}
+
+impl SimModelTrait<1, 2, 0> for Elimination {
+  fn new() -> Self {
+    Self {
+      p: [0.5, 10.0],
+      transforms: vec![Transform::Logit, Transform::Log],
+    }
+  }
+
+  fn init(&self) -> (f64, [f64; 1]) {
+    (0.0, [self.p[1]])
+  }
+
+  fn ode(&self, _t: &f64, y: &[f64; 1], deriv_y: &mut [f64; 1]) {
+    deriv_y[0] = -self.p[0] * y[0];
+  }
+
+  fn rec(&self, _t: &f64, _y: &[f64; 1], _delta_y: &mut [f64; 1], _act: &[bool; 0]) {}
+
+  fn cond(&self, _dec_t: &Decimal, _act: &mut [bool; 0], _next_t: &[Decimal; 0], _y: &[f64; 1]) {}
+
+  fn beat(&self, _t: &f64, _y: &[f64; 1]) -> [[Decimal; 3]; 0] {
+    []
+  }
+
+  fn cre(&self, _t: &f64, _y: &mut [f64; 1]) {}
+}
+
+impl OptModelTrait<1, 2, 0, 2> for Elimination {
+  fn getp(&self) -> &[f64; 2] {
+    &self.p
+  }
+
+  fn getx(&self) -> (Vec<usize>, Option<Vec<(f64, f64)>>) {
+    (vec![0, 1], Some(vec![(0.01, 5.0), (1.0, 100.0)]))
+  }
+
+  fn getx_transform(&self) -> Option<Vec<Transform>> {
+    Some(self.transforms.clone())
+  }
+
+  fn setp(&mut self, index: usize, value: f64) {
+    self.p[index] = value;
+  }
+}
+
+fn objective(transforms: Vec<Transform>) -> Objective<Elimination, 1, 2, 0, 2> {
+  let model = Elimination {
+    transforms,
+    ..Elimination::new()
+  };
+  let simulator = Simulator::new(model, Stepper::Rk4(StepOptions::Rk4 { h: 0.1 }));
+  let data = Data::new(vec![(0, 1.0, 6.0, None, None), (0, 2.0, 3.7, None, None)]);
+  Objective::new(simulator, data)
+}
+
+const TRANSFORMS: [Transform; 4] = [
+  Transform::Identity,
+  Transform::Log,
+  Transform::Log10,
+  Transform::Logit,
+];
+
+#[test]
+fn transforms_round_trip() {
+  let bounds = Some((0.5, 4.0));
+  for transform in TRANSFORMS.iter() {
+    for &x in [0.6, 1.0, 2.5, 3.9].iter() {
+      let z = transform.to_z(x, bounds);
+      let x_back = transform.to_x(z, bounds);
+      assert!(
+        (x_back - x).abs() < 1e-12,
+        "{:?}: {} != {}",
+        transform,
+        x_back,
+        x
+      );
+    }
+  }
+  assert_eq!(Transform::Log10.to_z(100.0, None), 2.0);
+  assert_eq!(Transform::Logit.to_z(2.25, bounds), 0.0);
+}
+
+#[test]
+fn log_jacobians_are_derivatives_of_to_x() {
+  let bounds = Some((0.5, 4.0));
+  let h = 1e-6;
+  for transform in TRANSFORMS.iter() {
+    for &z in [-1.5, 0.0, 0.3, 1.2].iter() {
+      let dxdz = (transform.to_x(z + h, bounds) - transform.to_x(z - h, bounds)) / (2.0 * h);
+      let log_jacobian = transform.log_jacobian(z, bounds);
+      assert!(
+        (log_jacobian - dxdz.abs().ln()).abs() < 1e-6,
+        "{:?} at {}: {} != {}",
+        transform,
+        z,
+        log_jacobian,
+        dxdz.abs().ln()
+      );
+    }
+  }
+  // no overflow far from the middle of the bounded interval
+  assert!(Transform::Logit.log_jacobian(800.0, bounds).is_finite());
+}
+
+#[test]
+fn logit_clamps_the_bounds_to_its_search_range() {
+  let bounds = Some((0.5, 4.0));
+  let range = 999f64.ln();
+  assert_eq!(Transform::Logit.z_bounds((0.5, 4.0)), (-range, range));
+  assert_eq!(Transform::Logit.to_z(0.5, bounds), -range);
+  assert_eq!(Transform::Logit.to_z(4.5, bounds), range);
+  assert_eq!(Transform::Log10.z_bounds((0.1, 1000.0)), (-1.0, 3.0));
+}
+
+#[test]
+#[should_panic(expected = "please define lower and upper bounds for logit transform.")]
+fn logit_requires_bounds() {
+  Transform::Logit.to_z(1.0, None);
+}
+
+#[test]
+fn objective_searches_in_the_transformed_space() {
+  let mut objective = objective(vec![Transform::Logit, Transform::Log]);
+  let x = arr1(&[0.5, 10.0]);
+  let z = objective.to_z(&x);
+  assert!((z[0] - (0.49f64 / 4.5).ln()).abs() < 1e-12);
+  assert!((z[1] - 10f64.ln()).abs() < 1e-12);
+  assert!((&objective.to_x(&z) - &x).iter().all(|d| d.abs() < 1e-12));
+  assert!((objective.objz(&z) - objective.obj(&x)).abs() < 1e-12);
+
+  let z_bounds = objective.z_bounds();
+  assert_eq!(z_bounds[0], (-999f64.ln(), 999f64.ln()));
+  assert_eq!(z_bounds[1], (1f64.ln(), 100f64.ln()));
+}
+
+#[test]
+#[should_panic(expected = "getx_transform must have the same length as x_index (1 != 2).")]
+fn objective_rejects_transforms_of_a_wrong_length() {
+  objective(vec![Transform::Log]);
+}
@@ -0,0 +1,93 @@
+use aphreco::optimizer::Uncertainty;
+use aphreco::prelude::*;
+
+use ndarray::arr1;
+
+// constant y = mu.
+#[derive(Clone)]
+struct Constant {
+  p: [f64; 1],
+}
+
+impl SimModelTrait<1, 1, 0> for Constant {
+  fn new() -> Self {
+    Self { p: [2.5] }
+  }
+
+  fn init(&self) -> (f64, [f64; 1]) {
+    (0.0, [self.p[0]])
+  }
+
+  fn ode(&self, _t: &f64, _y: &[f64; 1], deriv_y: &mut [f64; 1]) {
+    deriv_y[0] = 0.0;
+  }
+
+  fn rec(&self, _t: &f64, _y: &[f64; 1], _delta_y: &mut [f64; 1], _act: &[bool; 0]) {}
+
+  fn cond(&self, _dec_t: &Decimal, _act: &mut [bool; 0], _next_t: &[Decimal; 0], _y: &[f64; 1]) {}
+
+  fn beat(&self, _t: &f64, _y: &[f64; 1]) -> [[Decimal; 3]; 0] {
+    []
+  }
+
+  fn cre(&self, _t: &f64, _y: &mut [f64; 1]) {}
+}
+
+impl OptModelTrait<1, 1, 0, 1> for Constant {
+  fn getp(&self) -> &[f64; 1] {
+    &self.p
+  }
+
+  fn getx(&self) -> (Vec<usize>, Option<Vec<(f64, f64)>>) {
+    (vec![0], None)
+  }
+
+  fn setp(&mut self, index: usize, value: f64) {
+    self.p[index] = value;
+  }
+}
+
+// y = 1, 2, 3, 4 with SD 1 whose mean is 2.5,
+// and an observation below LLOQ = 0.1 if censored.
+fn objective(censored: bool) -> Objective<Constant, 1, 1, 0, 1> {
+  let mut obs: Vec<Obs> = [1.0, 2.0, 3.0, 4.0]
+    .iter()
+    .map(|&y| (0, 0.5, y, None, Some(1.0)))
+    .collect();
+  let mut censor = vec![Censor::Quantified; 4];
+  if censored {
+    obs.push((0, 0.5, 0.1, None, Some(1.0)));
+    censor.push(Censor::Below(0.1));
+  }
+  let simulator = Simulator::new(Constant::new(), Stepper::Rk4(StepOptions::Default));
+  Objective::new(simulator, Data::with_censor(obs, censor))
+}
+
+#[test]
+fn standard_error_of_a_mean() {
+  // SE of the mean is the SD of data / sqrt(n) = sqrt(5 / 3 / 4) without yerr,
+  // for every loss but Gaussian (with or without censored observations)
+  for censored in [false, true] {
+    for loss in [
+      Loss::Ssr,
+      Loss::Huber { delta: 0.5 },
+      Loss::Cauchy { c: 0.5 },
+    ] {
+      let mut objective = objective(censored);
+      objective.set_loss(loss).unwrap();
+      let uncertainty = Uncertainty::new(&arr1(&[2.5]), &mut objective);
+      let se = uncertainty.se[0];
+      assert!((se - (5.0f64 / 12.0).sqrt()).abs() < 1e-6, "{}", se);
+    }
+  }
+
+  // and yerr / sqrt(n) for Gaussian
+  let mut objective = objective(false);
+  objective.set_loss(Loss::Gaussian).unwrap();
+  let uncertainty = Uncertainty::new(&arr1(&[2.5]), &mut objective);
+  assert!(
+    (uncertainty.se[0] - 0.5).abs() < 1e-6,
+    "{}",
+    uncertainty.se[0]
+  );
+}